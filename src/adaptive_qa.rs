@@ -1,14 +1,257 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+
 use crate::metrics;
 use crate::prosody;
-use crate::utils;
+use crate::utils::{self, SeededRng};
+
+const QUESTION_WORDS: &[&str] = &[
+    "why", "how", "what", "which", "where", "when", "who", "maybe", "unsure", "confused",
+];
+const NEGATION_WORDS: &[&str] = &[
+    "not", "no", "never", "nothing", "nobody", "none", "don't", "dont", "can't", "cant", "won't",
+    "isn't", "doesn't", "didn't",
+];
+const CALM_WORDS: &[&str] = &[
+    "calm", "peace", "peaceful", "thank", "thanks", "grateful", "good", "great", "love", "happy",
+    "relaxed", "clear", "gentle", "breathe", "steady", "okay", "fine", "hello", "hi", "yes",
+    "ready", "glad",
+];
+
+/// Drift added per unit of pace above 1.0 (negative when slower).
+const PACE_DRIFT_GAIN: f32 = 0.15;
+
+/// Drift and resonance used before an utterance is seen at all.
+const EMPTY_PROMPT: (f32, f32) = (0.3, 0.5);
+
+/// Word weights that steer [`analyze_prompt_with_lexicon`]. Drift weights feed
+/// the churn/chaos signal; positive resonance weights lift resonance and
+/// negative ones pull it down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lexicon {
+    pub drift: HashMap<String, f32>,
+    pub res: HashMap<String, f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexiconKind {
+    Drift,
+    Res,
+}
+
+impl Lexicon {
+    pub fn insert(&mut self, word: &str, weight: f32, kind: LexiconKind) {
+        let map = match kind {
+            LexiconKind::Drift => &mut self.drift,
+            LexiconKind::Res => &mut self.res,
+        };
+        map.insert(word.trim().to_lowercase(), weight);
+    }
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        let mut lexicon = Self {
+            drift: HashMap::new(),
+            res: HashMap::new(),
+        };
+        for word in QUESTION_WORDS {
+            lexicon.insert(word, 1.0, LexiconKind::Drift);
+        }
+        for word in NEGATION_WORDS {
+            lexicon.insert(word, 1.0, LexiconKind::Drift);
+            lexicon.insert(word, -1.0, LexiconKind::Res);
+        }
+        for word in CALM_WORDS {
+            lexicon.insert(word, 1.0, LexiconKind::Res);
+        }
+        lexicon
+    }
+}
+
+/// Loads `word,weight,kind` rows (kind is `drift` or `res`) on top of the
+/// built-in lexicon; a weight of 0 disables a default word. Blank lines, `#`
+/// comments, a header row and malformed rows are skipped, and an unreadable
+/// file yields the defaults.
+pub fn load_lexicon(path: &str) -> Lexicon {
+    let mut lexicon = Lexicon::default();
+    let Ok(txt) = fs::read_to_string(path) else {
+        return lexicon;
+    };
+
+    for line in txt.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+        if parts.len() != 3 || parts[0].is_empty() {
+            continue;
+        }
+        let kind = match parts[2].to_ascii_lowercase().as_str() {
+            "drift" => LexiconKind::Drift,
+            "res" => LexiconKind::Res,
+            _ => continue,
+        };
+        if let Ok(weight) = parts[1].parse::<f32>()
+            && weight.is_finite()
+        {
+            lexicon.insert(parts[0], weight, kind);
+        }
+    }
+    lexicon
+}
+
+/// Scores a prompt from its wording alone, so the same text always yields the
+/// same `(drift, resonance)`.
+///
+/// Drift grows with lexical churn (unique/total tokens), question and negation
+/// words, `!`/`?` marks, and a device `pace_factor` above 1.0; resonance grows
+/// with calm/positive words and is pulled down by negations.
+pub fn analyze_prompt(input: &str, pace_factor: f32) -> (f32, f32) {
+    analyze_prompt_with_lexicon(input, pace_factor, &Lexicon::default())
+}
+
+/// [`analyze_prompt`] with caller-supplied word weights.
+pub fn analyze_prompt_with_lexicon(input: &str, pace_factor: f32, lexicon: &Lexicon) -> (f32, f32) {
+    let pace_bias = (pace_factor - 1.0) * PACE_DRIFT_GAIN;
+    let tokens: Vec<String> = prosody::tokenize(input)
+        .into_iter()
+        .map(str::to_lowercase)
+        .collect();
+    if tokens.is_empty() {
+        let (drift, res) = EMPTY_PROMPT;
+        return (metrics::clamp01(drift + pace_bias), res);
+    }
+
+    let total = tokens.len() as f32;
+    let mut unique: Vec<&str> = tokens.iter().map(String::as_str).collect();
+    unique.sort_unstable();
+    unique.dedup();
+    let churn = unique.len() as f32 / total;
+
+    let mut chaos = 0.0;
+    let mut calm = 0.0;
+    let mut unrest = 0.0;
+    for token in &tokens {
+        chaos += lexicon.drift.get(token).copied().unwrap_or(0.0);
+        let weight = lexicon.res.get(token).copied().unwrap_or(0.0);
+        if weight >= 0.0 {
+            calm += weight;
+        } else {
+            unrest -= weight;
+        }
+    }
+    let marks = input.chars().filter(|c| matches!(c, '!' | '?')).count() as f32;
+
+    // Churn alone tops out at 0.25, so plain calm text stays under the
+    // default drift baseline and the stabilizer's warm threshold; only
+    // chaos words, punctuation and pace push it further.
+    let drift = 0.05
+        + 0.2 * churn
+        + 0.35 * metrics::clamp01(chaos / total * 2.0)
+        + (0.05 * marks).min(0.2)
+        + pace_bias;
+    let res = 0.35 + 0.5 * metrics::clamp01(calm / total * 3.0)
+        - 0.2 * metrics::clamp01(unrest / total * 3.0);
 
-pub fn analyze_prompt(input: &str) -> (f32, f32) {
-    let (drift, res) = utils::hash01(input);
     (metrics::clamp01(drift), metrics::clamp01(res))
 }
 
-pub fn apply_prosody_bias(mut drift: f32, mut res: f32, tone: &prosody::ToneTag) -> (f32, f32) {
-    match tone {
+/// Default number of prompts remembered by an [`AnalysisCache`].
+pub const ANALYSIS_CACHE_CAPACITY: usize = 256;
+
+/// Bounded memo of prompt scores keyed by normalized text, evicting the least
+/// recently used entry once `capacity` is exceeded.
+pub struct AnalysisCache<F> {
+    analyzer: F,
+    entries: HashMap<String, (f32, f32)>,
+    order: VecDeque<String>,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl<F> AnalysisCache<F>
+where
+    F: FnMut(&str) -> (f32, f32),
+{
+    pub fn new(capacity: usize, analyzer: F) -> Self {
+        Self {
+            analyzer,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn analyze_cached(&mut self, input: &str) -> (f32, f32) {
+        let key = utils::normalize_text(input);
+        if let Some(&scores) = self.entries.get(&key) {
+            self.hits += 1;
+            self.promote(&key);
+            return scores;
+        }
+
+        self.misses += 1;
+        let scores = (self.analyzer)(input);
+        self.entries.insert(key.clone(), scores);
+        self.promote(&key);
+        while self.order.len() > self.capacity {
+            if let Some(old_key) = self.order.pop_back() {
+                self.entries.remove(&old_key);
+            }
+        }
+        scores
+    }
+
+    fn promote(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_front(key.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+/// Maximum jitter [`analyze_prompt_seeded`] adds to each score.
+pub const SEEDED_JITTER: f32 = 0.05;
+
+/// [`analyze_prompt`] plus reproducible jitter: the RNG is seeded from `seed`
+/// and the input text, so equal seeds and inputs give equal scores.
+pub fn analyze_prompt_seeded(input: &str, pace_factor: f32, seed: u64) -> (f32, f32) {
+    jitter_seeded(analyze_prompt(input, pace_factor), input, seed)
+}
+
+/// Adds the bounded seeded jitter of [`analyze_prompt_seeded`] to scores that
+/// were computed elsewhere (e.g. with a custom [`Lexicon`]).
+pub fn jitter_seeded(scores: (f32, f32), input: &str, seed: u64) -> (f32, f32) {
+    let (drift, res) = scores;
+    let mut rng = SeededRng::new(seed ^ utils::fnv1a64(input));
+    let drift = drift + rng.gen_range(-SEEDED_JITTER, SEEDED_JITTER);
+    let res = res + rng.gen_range(-SEEDED_JITTER, SEEDED_JITTER);
+    (metrics::clamp01(drift), metrics::clamp01(res))
+}
+
+pub fn apply_prosody_bias(mut drift: f32, mut res: f32, prosody: &prosody::Prosody) -> (f32, f32) {
+    match prosody.tone {
         prosody::ToneTag::Calm => {
             res += 0.02;
         }
@@ -16,9 +259,24 @@ pub fn apply_prosody_bias(mut drift: f32, mut res: f32, tone: &prosody::ToneTag)
             res -= 0.01;
             drift += 0.02;
         }
+        prosody::ToneTag::Anxious => {
+            res -= 0.02;
+            drift += 0.03;
+        }
+        prosody::ToneTag::Sad => {
+            res -= 0.03;
+            drift += 0.01;
+        }
         prosody::ToneTag::Neutral => {}
     }
 
+    if prosody.is_exclamation {
+        drift += 0.02;
+    }
+    if prosody.is_question {
+        res -= 0.015;
+    }
+
     drift = metrics::clamp01(drift);
     res = metrics::clamp01(res);
 
@@ -1,52 +1,164 @@
-#[derive(Default)]
+use crate::spark;
+
 pub struct AlertStats {
     pub drift_breaches: usize,
     pub res_breaches: usize,
     pub total: usize,
     pub max_drift: f32,
+    /// Lowest resonance seen; `f32::INFINITY` until the first sample.
     pub min_res: f32,
+    /// Cycles whose drift also crossed `crit_drift`.
+    pub drift_crit: usize,
+    /// Cycles whose resonance also fell below `crit_res`.
+    pub res_crit: usize,
+    /// Critical drift threshold. `None`, or a value not above the warn
+    /// baseline, keeps a single tier.
+    pub crit_drift: Option<f32>,
+    /// Critical resonance threshold, active only below the warn baseline.
+    pub crit_res: Option<f32>,
+    /// Consecutive cycles, up to the latest, with drift above the baseline.
+    pub current_drift_streak: usize,
+    pub max_drift_streak: usize,
+    /// Consecutive cycles, up to the latest, with resonance below the baseline.
+    pub current_res_streak: usize,
+    pub max_res_streak: usize,
+}
+
+impl Default for AlertStats {
+    fn default() -> Self {
+        Self {
+            drift_breaches: 0,
+            res_breaches: 0,
+            total: 0,
+            max_drift: 0.0,
+            min_res: f32::INFINITY,
+            drift_crit: 0,
+            res_crit: 0,
+            crit_drift: None,
+            crit_res: None,
+            current_drift_streak: 0,
+            max_drift_streak: 0,
+            current_res_streak: 0,
+            max_res_streak: 0,
+        }
+    }
+}
+
+impl AlertStats {
+    /// Empty stats that also count excursions past the critical thresholds.
+    pub fn with_crit(crit_drift: Option<f32>, crit_res: Option<f32>) -> Self {
+        Self {
+            crit_drift,
+            crit_res,
+            ..Self::default()
+        }
+    }
+
+    /// The longest run of consecutive breaches on either signal.
+    pub fn longest_streak(&self) -> usize {
+        self.max_drift_streak.max(self.max_res_streak)
+    }
+
+    /// The critical drift threshold, if it forms a tier above `base_drift`.
+    fn crit_drift_tier(&self, base_drift: f32) -> Option<f32> {
+        self.crit_drift.filter(|crit| *crit > base_drift)
+    }
+
+    /// The critical resonance threshold, if it forms a tier below `base_res`.
+    fn crit_res_tier(&self, base_res: f32) -> Option<f32> {
+        self.crit_res.filter(|crit| *crit < base_res)
+    }
 }
 
 pub fn update(stats: &mut AlertStats, drift: f32, res: f32, base_drift: f32, base_res: f32) {
     stats.total += 1;
     if drift > base_drift {
         stats.drift_breaches += 1;
+        stats.current_drift_streak += 1;
+        stats.max_drift_streak = stats.max_drift_streak.max(stats.current_drift_streak);
+    } else {
+        stats.current_drift_streak = 0;
     }
     if res < base_res {
         stats.res_breaches += 1;
+        stats.current_res_streak += 1;
+        stats.max_res_streak = stats.max_res_streak.max(stats.current_res_streak);
+    } else {
+        stats.current_res_streak = 0;
+    }
+    if stats
+        .crit_drift_tier(base_drift)
+        .is_some_and(|crit| drift > crit)
+    {
+        stats.drift_crit += 1;
+    }
+    if stats.crit_res_tier(base_res).is_some_and(|crit| res < crit) {
+        stats.res_crit += 1;
     }
     if drift > stats.max_drift {
         stats.max_drift = drift;
     }
-    if stats.min_res == 0.0 || res < stats.min_res {
-        stats.min_res = res;
-    }
+    stats.min_res = stats.min_res.min(res);
 }
 
-pub fn summary_lines(stats: &AlertStats, base_drift: f32, base_res: f32) -> Vec<String> {
-    let header = format!(
+pub fn summary_lines(
+    stats: &AlertStats,
+    base_drift: f32,
+    base_res: f32,
+    ascii: bool,
+) -> Vec<String> {
+    let crit_drift = stats.crit_drift_tier(base_drift);
+    let crit_res = stats.crit_res_tier(base_res);
+    let mut header = format!(
         "[health] baseline_drift>{:.2}, baseline_res<{:.2}",
         base_drift, base_res
     );
-    let breaches = format!(
-        "[health] breaches: drift={}, res={}, total={}",
-        stats.drift_breaches, stats.res_breaches, stats.total
-    );
+    if let Some(crit) = crit_drift {
+        header.push_str(&format!(", crit_drift>{:.2}", crit));
+    }
+    if let Some(crit) = crit_res {
+        header.push_str(&format!(", crit_res<{:.2}", crit));
+    }
+    let breaches = if crit_drift.is_some() || crit_res.is_some() {
+        format!(
+            "[health] breaches: drift={} (crit={}), res={} (crit={}), total={}",
+            stats.drift_breaches, stats.drift_crit, stats.res_breaches, stats.res_crit, stats.total
+        )
+    } else {
+        format!(
+            "[health] breaches: drift={}, res={}, total={}",
+            stats.drift_breaches, stats.res_breaches, stats.total
+        )
+    };
+    let res_min = if stats.min_res.is_finite() {
+        format!("{:.2}", stats.min_res)
+    } else {
+        "n/a".to_string()
+    };
     let worst = format!(
-        "[health] worst: drift_max={:.2}, res_min={:.2}",
-        stats.max_drift, stats.min_res
+        "[health] worst: drift_max={:.2}, res_min={}",
+        stats.max_drift, res_min
     );
-    let ok = stats.drift_breaches == 0 && stats.res_breaches == 0;
-    let status = format!(
-        "[health] status: {}",
-        if ok { "OK ✅" } else { "ATTENTION ⚠️" }
+    let streaks = format!(
+        "[health] longest streak: drift={}, res={}",
+        stats.max_drift_streak, stats.max_res_streak
     );
+    let ok = stats.drift_breaches == 0 && stats.res_breaches == 0;
+    let glyphs = spark::glyphs(ascii);
+    let critical = stats.drift_crit > 0 || stats.res_crit > 0;
+    let status = if ok {
+        format!("[health] status: OK {}", glyphs.ok)
+    } else if critical {
+        format!("[health] status: CRITICAL {}", glyphs.warning)
+    } else {
+        format!("[health] status: ATTENTION {}", glyphs.warning)
+    };
 
-    vec![header, breaches, worst, status]
+    vec![header, breaches, worst, streaks, status]
 }
 
-pub fn print_summary(stats: &AlertStats, base_drift: f32, base_res: f32) {
-    let lines = summary_lines(stats, base_drift, base_res);
+pub fn print_summary(stats: &AlertStats, base_drift: f32, base_res: f32, ascii: bool) {
+    let lines = summary_lines(stats, base_drift, base_res, ascii);
     if lines.is_empty() {
         return;
     }
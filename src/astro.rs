@@ -1,6 +1,6 @@
 use std::collections::{HashMap, VecDeque};
-use std::fs::{self, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
+use std::fs;
+use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 
 use crate::metrics;
@@ -147,7 +147,7 @@ impl AstroStore {
 
         if let Ok(file) = fs::File::open(&store.path) {
             let reader = BufReader::new(file);
-            for line in reader.lines().flatten() {
+            for line in reader.lines().map_while(Result::ok) {
                 if let Some(trace) = AstroTrace::from_json_line(&line) {
                     store.insert_trace(trace);
                 }
@@ -290,16 +290,7 @@ impl AstroStore {
     }
 
     fn append_trace(&self, trace: &AstroTrace) -> std::io::Result<()> {
-        if let Some(parent) = self.path.parent() {
-            if !parent.as_os_str().is_empty() {
-                fs::create_dir_all(parent)?;
-            }
-        }
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)?;
-        writeln!(file, "{}", trace.to_json_line())
+        utils::append_line(&self.path, &trace.to_json_line())
     }
 }
 
@@ -315,7 +306,9 @@ pub fn topic_key(text: &str, tone: ToneTag) -> String {
         collapsed,
         format!("{:?}", tone).to_ascii_lowercase()
     );
-    let (a, b) = utils::hash01(&seed);
+    let h = utils::fnv1a64(&seed);
+    let a = ((h >> 11) & 0xFFFF) as f32 / 65535.0;
+    let b = ((h >> 27) & 0xFFFF) as f32 / 65535.0;
     let a_val = (a * 1_048_575.0).round() as u32;
     let b_val = (b * 1_048_575.0).round() as u32;
     format!("astro-{:05x}{:05x}", a_val, b_val)
@@ -6,7 +6,17 @@
 //! Padmasambhava teaches: "Recognize the nature of mind, and you are free."
 //! This module allows the system to observe its own state - meta-cognition.
 
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+
 use crate::metrics::clamp01;
+use crate::session::parse_json_object;
+use crate::utils;
+
+/// Restored observations count toward the clarity bonus only up to this many,
+/// so a long history doesn't peg clarity before the session has been observed
+pub const RESTORED_OBSERVATION_CAP: usize = 3;
 
 /// Meta-cognitive state of the system
 #[derive(Debug, Clone)]
@@ -28,6 +38,31 @@ pub struct MetaCognition {
 
     /// Number of observations made
     pub observation_count: usize,
+
+    /// Observations carried over from a previous run (part of `observation_count`)
+    restored_observations: usize,
+}
+
+/// How reliable the measurements behind an observation were
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ObservationContext {
+    /// `Prosody::confidence` of the utterance (0.3 for one word, 1.0 for eight+)
+    pub measurement_confidence: Option<f32>,
+    /// Turns the stabilizer has spent in its current state
+    pub steps_in_state: Option<usize>,
+}
+
+/// Share of confidence that depends on measurement reliability
+const MEASUREMENT_WEIGHT: f32 = 0.6;
+/// Turns in a state before it counts as settled
+const SETTLED_STEPS: usize = 3;
+
+/// Meta-cognitive state persisted between runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetaState {
+    pub observation_count: usize,
+    pub confidence: f32,
+    pub clarity: f32,
 }
 
 impl MetaCognition {
@@ -39,12 +74,40 @@ impl MetaCognition {
             clarity: 0.5,
             doubt: 0.5,
             observation_count: 0,
+            restored_observations: 0,
         }
     }
 
+    /// Resume from state saved by a previous run
+    pub fn warm_start(&mut self, state: &MetaState) {
+        self.observation_count = state.observation_count;
+        self.restored_observations = state.observation_count;
+        self.confidence = clamp01(state.confidence);
+        self.clarity = clamp01(state.clarity);
+        self.doubt = clamp01(1.0 - self.confidence).max(0.1);
+    }
+
     /// Observe the system's own state based on recent metrics
     pub fn observe(&mut self, measured_drift: f32, measured_res: f32,
                    stabilizer_state: &str, sync_corrections: f32) {
+        self.observe_with(
+            measured_drift,
+            measured_res,
+            stabilizer_state,
+            sync_corrections,
+            &ObservationContext::default(),
+        );
+    }
+
+    /// `observe`, discounting confidence for unreliable measurements
+    pub fn observe_with(
+        &mut self,
+        measured_drift: f32,
+        measured_res: f32,
+        stabilizer_state: &str,
+        sync_corrections: f32,
+        ctx: &ObservationContext,
+    ) {
         self.observation_count += 1;
 
         // Self-drift: how much are our own parameters changing?
@@ -58,17 +121,29 @@ impl MetaCognition {
             "Warming" => clamp01(measured_res),
             "Overheat" => clamp01(measured_res - 0.2),
             "Cooldown" => clamp01(measured_res - 0.1),
+            "Panic" => clamp01(measured_res - 0.3),
             _ => measured_res,
         };
 
         // Confidence: how sure are we about our measurements?
         // Low drift + high resonance = high confidence
         // High drift + low resonance = low confidence
-        self.confidence = clamp01((1.0 - measured_drift) * measured_res);
+        let mut confidence = (1.0 - measured_drift) * measured_res;
+        // A one-word utterance can't be trusted, however clean it looks
+        if let Some(measurement) = ctx.measurement_confidence {
+            confidence *= 1.0 - MEASUREMENT_WEIGHT * (1.0 - clamp01(measurement));
+        }
+        // A state the stabilizer only just entered hasn't settled yet
+        if let Some(steps) = ctx.steps_in_state {
+            confidence *= 0.85 + 0.15 * steps.min(SETTLED_STEPS) as f32 / SETTLED_STEPS as f32;
+        }
+        self.confidence = clamp01(confidence);
 
         // Clarity: how well do we understand what's happening?
         // Increases with observation count (up to a point)
-        let observation_bonus = (self.observation_count as f32 * 0.05).min(0.3);
+        let counted = self.observation_count - self.restored_observations
+            + self.restored_observations.min(RESTORED_OBSERVATION_CAP);
+        let observation_bonus = (counted as f32 * 0.05).min(0.3);
         self.clarity = clamp01(self.confidence + observation_bonus);
 
         // Doubt: inverse of confidence with a floor
@@ -80,14 +155,24 @@ impl MetaCognition {
         self.doubt > 0.6 && self.confidence < 0.4
     }
 
+    /// How forcefully to act on measurements: 1.0 normally, shrinking toward
+    /// 0.5 as doubt grows while the system expresses doubt
+    pub fn caution(&self) -> f32 {
+        if self.should_express_doubt() {
+            1.0 - 0.5 * self.doubt
+        } else {
+            1.0
+        }
+    }
+
     /// Is the system in a clear, stable state?
     pub fn is_clear_and_stable(&self) -> bool {
         self.clarity > 0.7 && self.self_drift < 0.3
     }
 
-    /// Generate a self-assessment message
-    pub fn self_assess(&self) -> String {
-        let state = if self.is_clear_and_stable() {
+    /// Derived label for the current self-state
+    pub fn state_label(&self) -> &'static str {
+        if self.is_clear_and_stable() {
             "Clear & Stable"
         } else if self.should_express_doubt() {
             "Uncertain"
@@ -95,11 +180,31 @@ impl MetaCognition {
             "Self-Adjusting"
         } else {
             "Observing"
-        };
+        }
+    }
 
+    /// Generate a self-assessment message
+    pub fn self_assess(&self) -> String {
         format!(
             "self_state={} conf={:.2} clarity={:.2} doubt={:.2}",
-            state, self.confidence, self.clarity, self.doubt
+            self.state_label(),
+            self.confidence,
+            self.clarity,
+            self.doubt
+        )
+    }
+
+    /// Raw self-assessment fields as a single JSON object
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"state\":\"{}\",\"self_drift\":{:.4},\"self_resonance\":{:.4},\"confidence\":{:.4},\"clarity\":{:.4},\"doubt\":{:.4},\"observation_count\":{}}}",
+            self.state_label(),
+            self.self_drift,
+            self.self_resonance,
+            self.confidence,
+            self.clarity,
+            self.doubt,
+            self.observation_count
         )
     }
 }
@@ -110,11 +215,45 @@ impl Default for MetaCognition {
     }
 }
 
+/// Latest parseable meta state in a JSONL file
+pub fn load_state(path: &str) -> Option<MetaState> {
+    let txt = fs::read_to_string(path).ok()?;
+    txt.lines().rev().find_map(parse_state)
+}
+
+/// Replace the saved meta state with the current observation count,
+/// confidence and clarity
+pub fn save_state(path: &str, meta: &MetaCognition) -> io::Result<()> {
+    utils::write_atomic(
+        path,
+        &format!(
+            "{{\"observation_count\":{},\"confidence\":{:.6},\"clarity\":{:.6}}}\n",
+            meta.observation_count, meta.confidence, meta.clarity
+        ),
+    )
+}
+
+fn parse_state(line: &str) -> Option<MetaState> {
+    let fields = parse_json_object(line)?;
+    let num = |key: &str| fields.iter().find(|(k, _)| k == key)?.1.as_f64();
+    Some(MetaState {
+        observation_count: num("observation_count")? as usize,
+        confidence: num("confidence")? as f32,
+        clarity: num("clarity")? as f32,
+    })
+}
+
+/// Number of recent self-drift EMAs kept for trend detection
+pub const META_TREND_WINDOW: usize = 5;
+/// Rise in self-drift EMA across the window that calls for more awareness
+pub const META_TREND_RISING: f32 = 0.05;
+
 /// Meta-stabilizer: stabilizes the meta-cognition layer itself
 pub struct MetaStabilizer {
     ema_self_drift: f32,
     ema_confidence: f32,
     alpha: f32,  // EMA smoothing factor
+    recent_self_drift: VecDeque<f32>,
 }
 
 impl MetaStabilizer {
@@ -123,6 +262,7 @@ impl MetaStabilizer {
             ema_self_drift: 0.0,
             ema_confidence: 0.5,
             alpha,
+            recent_self_drift: VecDeque::with_capacity(META_TREND_WINDOW),
         }
     }
 
@@ -132,6 +272,20 @@ impl MetaStabilizer {
                             + (1.0 - self.alpha) * self.ema_self_drift;
         self.ema_confidence = self.alpha * meta.confidence
                             + (1.0 - self.alpha) * self.ema_confidence;
+
+        if self.recent_self_drift.len() == META_TREND_WINDOW {
+            self.recent_self_drift.pop_front();
+        }
+        self.recent_self_drift.push_back(self.ema_self_drift);
+    }
+
+    /// Slope of the self-drift EMA over the recent window (latest minus
+    /// oldest); positive while the system is growing less stable
+    pub fn self_drift_trend(&self) -> f32 {
+        match (self.recent_self_drift.front(), self.recent_self_drift.back()) {
+            (Some(oldest), Some(latest)) => latest - oldest,
+            _ => 0.0,
+        }
     }
 
     /// Get stabilized meta-metrics
@@ -141,7 +295,9 @@ impl MetaStabilizer {
 
     /// Should we increase meta-awareness?
     pub fn needs_more_awareness(&self) -> bool {
-        self.ema_self_drift > 0.4 || self.ema_confidence < 0.5
+        self.ema_self_drift > 0.4
+            || self.ema_confidence < 0.5
+            || self.self_drift_trend() > META_TREND_RISING
     }
 }
 
@@ -187,8 +343,8 @@ mod tests {
         stabilizer.update(&meta);
 
         let (drift, conf) = stabilizer.get_stable_metrics();
-        assert!(drift >= 0.0 && drift <= 1.0);
-        assert!(conf >= 0.0 && conf <= 1.0);
+        assert!((0.0..=1.0).contains(&drift));
+        assert!((0.0..=1.0).contains(&conf));
     }
 
     #[test]
@@ -5,8 +5,17 @@
 //!
 //! "Tikkun olam begins with compassion for one's neighbor." - Hasidic wisdom
 
+use std::fs;
+use std::io;
+
 use crate::metrics::clamp01;
 use crate::prosody::ToneTag;
+use crate::session::{SyncDelta, parse_json_object};
+use crate::spark;
+use crate::utils;
+
+/// Share of a warm-started compassion level kept after each turn.
+const CARRY_DECAY: f32 = 0.5;
 
 /// Types of detected suffering
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -15,6 +24,113 @@ pub enum SufferingType {
     Mild,      // Slight distress
     Moderate,  // Clear distress signals
     Severe,    // High distress, needs support
+    Grief,     // Slow, sad, disconnected - needs space rather than calming
+}
+
+/// Phrases offered when the system decides to support the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct SupportTemplates {
+    /// Offered for Moderate suffering
+    pub moderate: String,
+    /// Offered for Severe suffering
+    pub severe: String,
+    /// Offered for Grief
+    pub grief: String,
+    /// Appended once the suffering streak reaches `long_streak`
+    pub long_streak_note: String,
+    pub long_streak: usize,
+}
+
+impl Default for SupportTemplates {
+    fn default() -> Self {
+        Self {
+            moderate: "It sounds like this is weighing on you. I'm here with you.".to_string(),
+            severe: "This sounds really hard. Let's slow down together — you don't have to carry it alone.".to_string(),
+            grief: "I'm so sorry. There's no rush here — take all the time you need.".to_string(),
+            long_streak_note: "We've been circling this for a while; we can take it one small step at a time.".to_string(),
+            long_streak: 3,
+        }
+    }
+}
+
+/// Coefficients and thresholds used by `detect_suffering`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SufferingWeights {
+    /// Drift above / resonance below these marks counts as emotional chaos
+    pub chaos_drift: f32,
+    pub chaos_res: f32,
+    pub chaos_drift_gain: f32,
+    pub chaos_res_gain: f32,
+    pub overheat: f32,
+    pub panic: f32,
+    /// Energetic speech faster than `fast_wpm` reads as anxiety
+    pub fast_wpm: f32,
+    pub fast_speech: f32,
+    pub anxious_tone: f32,
+    pub sad_tone: f32,
+    /// Sad speech slower than `grief_wpm` with resonance below `chaos_res`
+    /// reads as grief
+    pub grief_wpm: f32,
+    pub grief: f32,
+    pub repeated_theme: f32,
+    /// Added once the streak exceeds `streak_turns`
+    pub streak: f32,
+    pub streak_turns: usize,
+    /// Lower bounds of the Mild / Moderate / Severe bands
+    pub mild: f32,
+    pub moderate: f32,
+    pub severe: f32,
+}
+
+impl Default for SufferingWeights {
+    fn default() -> Self {
+        Self {
+            chaos_drift: 0.5,
+            chaos_res: 0.6,
+            chaos_drift_gain: 2.0,
+            chaos_res_gain: 1.5,
+            overheat: 0.3,
+            panic: 0.45,
+            fast_wpm: 180.0,
+            fast_speech: 0.2,
+            anxious_tone: 0.2,
+            sad_tone: 0.15,
+            grief_wpm: 130.0,
+            grief: 0.3,
+            repeated_theme: 0.25,
+            streak: 0.3,
+            streak_turns: 2,
+            mild: 0.2,
+            moderate: 0.4,
+            severe: 0.7,
+        }
+    }
+}
+
+/// Tunable behavior of the compassion layer
+#[derive(Debug, Clone)]
+pub struct CompassionCfg {
+    pub support: SupportTemplates,
+    pub weights: SufferingWeights,
+    /// Compassion level above which compassionate mode activates
+    pub activation_threshold: f32,
+    /// Consecutive improving turns that count as sustained recovery
+    pub recovery_turns: usize,
+    /// Scale applied to activation (and extra decay applied to any carried
+    /// level) during sustained recovery
+    pub recovery_damping: f32,
+}
+
+impl Default for CompassionCfg {
+    fn default() -> Self {
+        Self {
+            support: SupportTemplates::default(),
+            weights: SufferingWeights::default(),
+            activation_threshold: 0.5,
+            recovery_turns: 2,
+            recovery_damping: 0.7,
+        }
+    }
 }
 
 /// Compassion metrics for the system
@@ -40,10 +156,35 @@ pub struct CompassionMetrics {
 
     /// Consecutive turns with suffering
     pub suffering_streak: usize,
+
+    /// Compassion carried over from a previous session; floors the level and
+    /// fades each turn
+    pub carried_level: f32,
+
+    /// Consecutive turns with falling drift and rising resonance
+    pub recovery_streak: usize,
+
+    /// Kindness recorded for each turn, oldest first
+    pub kindness_history: Vec<f32>,
+
+    pub cfg: CompassionCfg,
+
+    last_sample: Option<(f32, f32)>,
+}
+
+/// Compassion state persisted between sessions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompassionSeed {
+    pub compassion_level: f32,
+    pub ts_unix: i64,
 }
 
 impl CompassionMetrics {
     pub fn new() -> Self {
+        Self::with_cfg(CompassionCfg::default())
+    }
+
+    pub fn with_cfg(cfg: CompassionCfg) -> Self {
         Self {
             user_suffering: 0.0,
             suffering_type: SufferingType::None,
@@ -52,7 +193,36 @@ impl CompassionMetrics {
             compassion_level: 0.0,
             suffering_count: 0,
             suffering_streak: 0,
+            carried_level: 0.0,
+            recovery_streak: 0,
+            kindness_history: Vec::new(),
+            cfg,
+            last_sample: None,
+        }
+    }
+
+    /// Resume from a (decayed) seed saved by a previous session
+    pub fn warm_start(&mut self, seed: &CompassionSeed) {
+        self.compassion_level = clamp01(seed.compassion_level);
+        self.carried_level = self.compassion_level;
+    }
+
+    /// Track de-escalation: drift falling while resonance rises turn over turn
+    pub fn detect_recovery(&mut self, drift: f32, resonance: f32) {
+        let improving = self
+            .last_sample
+            .is_some_and(|(last_drift, last_res)| drift < last_drift && resonance > last_res);
+        if improving {
+            self.recovery_streak += 1;
+        } else {
+            self.recovery_streak = 0;
         }
+        self.last_sample = Some((drift, resonance));
+    }
+
+    /// Has the user been recovering for at least `recovery_turns` turns?
+    pub fn is_recovering(&self) -> bool {
+        self.recovery_streak >= self.cfg.recovery_turns.max(1)
     }
 
     /// Detect user suffering from conversational metrics
@@ -65,51 +235,69 @@ impl CompassionMetrics {
         stabilizer_state: &str,
         repeated_theme: bool,
     ) {
+        let w = self.cfg.weights;
         let mut suffering_score = 0.0;
 
         // Pattern 1: High drift + low resonance = emotional chaos
-        if drift > 0.5 && resonance < 0.6 {
-            suffering_score += (drift - 0.5) * 2.0; // Amplify signal
-            suffering_score += (0.6 - resonance) * 1.5;
+        if drift > w.chaos_drift && resonance < w.chaos_res {
+            suffering_score += (drift - w.chaos_drift) * w.chaos_drift_gain; // Amplify signal
+            suffering_score += (w.chaos_res - resonance) * w.chaos_res_gain;
         }
 
-        // Pattern 2: Overheat state = overwhelmed
-        if stabilizer_state == "Overheat" {
-            suffering_score += 0.3;
+        // Pattern 2: Overheat state = overwhelmed, Panic = sustained overwhelm
+        match stabilizer_state {
+            "Overheat" => suffering_score += w.overheat,
+            "Panic" => suffering_score += w.panic,
+            _ => {}
         }
 
         // Pattern 3: Fast chaotic speech (anxiety)
-        if matches!(tone, ToneTag::Energetic) && wpm > 180.0 {
-            suffering_score += 0.2;
+        if matches!(tone, ToneTag::Energetic) && wpm > w.fast_wpm {
+            suffering_score += w.fast_speech;
+        }
+
+        // Pattern 3b: Affect cues in the transcript (worry or sadness)
+        match tone {
+            ToneTag::Anxious => suffering_score += w.anxious_tone,
+            ToneTag::Sad => suffering_score += w.sad_tone,
+            _ => {}
+        }
+
+        // Pattern 3c: Slow, sad, disconnected speech (grief)
+        let grieving = matches!(tone, ToneTag::Sad) && wpm < w.grief_wpm && resonance < w.chaos_res;
+        if grieving {
+            suffering_score += w.grief;
         }
 
         // Pattern 4: Repeated theme without progress (stuck)
         if repeated_theme {
-            suffering_score += 0.25;
+            suffering_score += w.repeated_theme;
             self.suffering_streak += 1;
         } else {
             self.suffering_streak = 0;
         }
 
         // Pattern 5: Extended suffering streak
-        if self.suffering_streak > 2 {
-            suffering_score += 0.3;
+        if self.suffering_streak > w.streak_turns {
+            suffering_score += w.streak;
         }
 
         self.user_suffering = clamp01(suffering_score);
 
         // Classify suffering type
-        self.suffering_type = if self.user_suffering < 0.2 {
+        self.suffering_type = if self.user_suffering < w.mild {
             SufferingType::None
-        } else if self.user_suffering < 0.4 {
+        } else if grieving {
+            SufferingType::Grief
+        } else if self.user_suffering < w.moderate {
             SufferingType::Mild
-        } else if self.user_suffering < 0.7 {
+        } else if self.user_suffering < w.severe {
             SufferingType::Moderate
         } else {
             SufferingType::Severe
         };
 
-        if self.user_suffering > 0.2 {
+        if self.user_suffering > w.mild {
             self.suffering_count += 1;
         }
 
@@ -150,32 +338,69 @@ impl CompassionMetrics {
         self.response_kindness = clamp01(kindness);
     }
 
+    /// Score this turn's kindness once the guard outcome (its label, e.g.
+    /// "rephrased:high_drift") and the sync corrections are known
+    pub fn record_turn_kindness(&mut self, guard_flag: Option<&str>, sync: Option<&SyncDelta>) {
+        let was_rephrased = guard_flag.is_some_and(|flag| flag.starts_with("rephrased"));
+        let (pace_delta, pause_delta_ms, res_boost) = sync
+            .map(|delta| (delta.pace_delta, delta.pause_delta_ms, delta.res_boost))
+            .unwrap_or((0.0, 0, 0.0));
+        self.calculate_kindness(was_rephrased, pace_delta, pause_delta_ms, res_boost);
+        self.kindness_history.push(self.response_kindness);
+    }
+
     /// Update overall compassion activation level
     pub fn update_compassion_level(&mut self) {
         // Compassion activates proportionally to suffering
         // But also considers healing intent and kindness
-        let activation = (self.user_suffering * 0.5)
+        let mut activation = (self.user_suffering * 0.5)
             + (self.healing_intent * 0.3)
             + (self.response_kindness * 0.2);
 
-        self.compassion_level = clamp01(activation);
+        // Sustained recovery: let compassion stand down faster
+        if self.is_recovering() {
+            let damping = self.cfg.recovery_damping.clamp(0.0, 1.0);
+            activation *= damping;
+            self.carried_level *= damping * CARRY_DECAY;
+        }
+
+        self.compassion_level = clamp01(activation.max(self.carried_level));
+        self.carried_level *= CARRY_DECAY;
     }
 
     /// Should the system activate compassionate mode?
     pub fn should_activate_compassion(&self) -> bool {
-        self.compassion_level > 0.5
+        self.compassion_level > self.cfg.activation_threshold
     }
 
     /// Should the system offer explicit support?
     pub fn should_offer_support(&self) -> bool {
         matches!(
             self.suffering_type,
-            SufferingType::Moderate | SufferingType::Severe
+            SufferingType::Moderate | SufferingType::Severe | SufferingType::Grief
         )
     }
 
-    /// Get a compassion status message
-    pub fn status_message(&self) -> String {
+    /// What to say when offering support; empty when no support is warranted
+    pub fn support_message(&self) -> String {
+        let templates = &self.cfg.support;
+        let base = match self.suffering_type {
+            SufferingType::Moderate => &templates.moderate,
+            SufferingType::Severe => &templates.severe,
+            SufferingType::Grief => &templates.grief,
+            _ => return String::new(),
+        };
+        if self.suffering_streak >= templates.long_streak && !templates.long_streak_note.is_empty()
+        {
+            format!("{} {}", base, templates.long_streak_note)
+        } else {
+            base.clone()
+        }
+    }
+
+    /// Get a compassion status message (`ascii` swaps emoji for plain markers)
+    pub fn status_message(&self, ascii: bool) -> String {
+        let glyphs = spark::glyphs(ascii);
         match self.suffering_type {
             SufferingType::None => {
                 format!("Compassion: Observing (suffering={:.2})", self.user_suffering)
@@ -194,8 +419,14 @@ impl CompassionMetrics {
             }
             SufferingType::Severe => {
                 format!(
-                    "Compassion: ❤️  Deep Care (suffering={:.2}, streak={})",
-                    self.user_suffering, self.suffering_streak
+                    "Compassion: {}  Deep Care (suffering={:.2}, streak={})",
+                    glyphs.heart, self.user_suffering, self.suffering_streak
+                )
+            }
+            SufferingType::Grief => {
+                format!(
+                    "Compassion: {}  Holding Space (suffering={:.2}, healing={:.2})",
+                    glyphs.candle, self.user_suffering, self.healing_intent
                 )
             }
         }
@@ -208,6 +439,51 @@ impl Default for CompassionMetrics {
     }
 }
 
+/// Latest parseable compassion seed in a JSONL file
+pub fn load_seed(path: &str) -> Option<CompassionSeed> {
+    let txt = fs::read_to_string(path).ok()?;
+    txt.lines().rev().find_map(parse_seed)
+}
+
+/// Replace the saved compassion state with the current one, stamped with
+/// the current time. The suffering streak is not kept: it only grows on
+/// repeated themes within a session.
+pub fn save_seed(path: &str, metrics: &CompassionMetrics) -> io::Result<()> {
+    let ts_unix = utils::current_unix_secs();
+    utils::write_atomic(
+        path,
+        &format!(
+            "{{\"compassion_level\":{:.6},\"ts\":{}}}\n",
+            clamp01(metrics.compassion_level),
+            ts_unix
+        ),
+    )
+}
+
+/// Fade a seed toward a fresh start with the given half-life, like `emotive::decay`
+pub fn decay_seed(seed: &CompassionSeed, now: i64, half_life_min: u32) -> CompassionSeed {
+    let elapsed_mins = (now.saturating_sub(seed.ts_unix) as f32).max(0.0) / 60.0;
+    let k = if half_life_min == 0 {
+        0.0
+    } else {
+        0.5_f32.powf(elapsed_mins / half_life_min as f32)
+    };
+
+    CompassionSeed {
+        compassion_level: seed.compassion_level * k,
+        ts_unix: seed.ts_unix,
+    }
+}
+
+fn parse_seed(line: &str) -> Option<CompassionSeed> {
+    let fields = parse_json_object(line)?;
+    let num = |key: &str| fields.iter().find(|(k, _)| k == key)?.1.as_f64();
+    Some(CompassionSeed {
+        compassion_level: num("compassion_level")? as f32,
+        ts_unix: num("ts")? as i64,
+    })
+}
+
 /// Compassion adjustments to apply to the system
 #[derive(Debug, Clone, Copy)]
 pub struct CompassionAdjustments {
@@ -229,6 +505,16 @@ impl CompassionAdjustments {
     pub fn from_compassion(metrics: &CompassionMetrics) -> Self {
         let level = metrics.compassion_level;
 
+        // Grief needs room more than calming: longer pauses, barely slower pace
+        if metrics.suffering_type == SufferingType::Grief {
+            return Self {
+                resonance_boost: level * 0.1,
+                pace_adjustment: -level * 0.02,
+                pause_adjustment_ms: (level * 60.0) as i64,
+                drift_reduction: level * 0.04,
+            };
+        }
+
         Self {
             // Higher compassion = more resonance
             resonance_boost: level * 0.1,
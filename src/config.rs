@@ -1,30 +1,94 @@
 use std::env;
+use std::io::IsTerminal;
+
+use crate::device_memory::DEFAULT_MEMORY_ALPHA;
+use crate::emotive::{DEFAULT_PROFILE, EmoteDecayCfg};
+use crate::softguard::RephraseStrategy;
+use crate::stabilizer::AdviceTable;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Config {
+    /// Device mode name; empty when neither `--mode` nor `LIMINAL_MODE` is
+    /// given, in which case `device::auto_detect` picks one.
     pub mode: String,
     pub sample_rate: u32,
     pub channels: u16,
     pub frame_ms: u32,
+    /// Sleep through the simulated ASR/TTS latency. When off the figures
+    /// are still computed and reported, just not waited out.
+    pub simulate_latency: bool,
+    /// Multiplier on every simulated latency.
+    pub latency_scale: f32,
+    /// Print each paced TTS response as SSML.
+    pub ssml: bool,
     pub enable_metrics: bool,
+    /// Show a rolling mean/jitter over this many cycles in the metrics line.
+    pub metrics_window: Option<usize>,
+    /// Write Prometheus-format metrics here at shutdown.
+    pub metrics_out: Option<String>,
+    pub verbosity: u8,
+    /// When set, adaptive QA scores get reproducible seeded jitter.
+    pub seed: Option<u64>,
     pub viz_mode: VizMode,
+    pub color: ColorMode,
+    /// Plain-ASCII glyphs and markers for terminals without Unicode.
+    pub ascii: bool,
+    /// Full-table width in columns; `None` follows the terminal (`COLUMNS`).
+    pub viz_width: Option<usize>,
+    /// Changes smaller than this show as a flat trend arrow in the full table.
+    pub trend_deadband: f32,
     pub cycles: usize,
     pub enable_logging: bool,
     pub log_dir: String,
+    /// Append to an existing session log instead of truncating it.
+    pub log_append: bool,
+    /// Fixed session id, so later runs can append to the same log.
+    pub session_id: Option<String>,
+    /// Rotate the session log once it reaches this many bytes.
+    pub log_max_bytes: Option<u64>,
+    /// Flush the session log after every snapshot.
+    pub log_flush_each: bool,
     pub script: Option<String>,
     pub inputs_path: Option<String>,
+    /// 16-bit PCM WAV whose format overrides `sample_rate` and `channels`.
+    pub audio_path: Option<String>,
+    /// Length of the `audio_path` recording, read from its header at
+    /// startup; drives the ASR latency and measured speaking rate.
+    pub audio_duration_ms: Option<u64>,
+    pub lexicon_path: Option<String>,
     pub baseline_drift: f32,
     pub baseline_res: f32,
+    /// Drift above this counts as a critical alert breach.
+    pub crit_drift: Option<f32>,
+    /// Resonance below this counts as a critical alert breach.
+    pub crit_res: Option<f32>,
     pub alarm: bool,
     pub strict: bool,
+    /// `--strict` fails the run once a breach streak is longer than this.
+    pub strict_streak: usize,
     pub guard: bool,
     pub guard_drift: f32,
     pub guard_res: f32,
     pub guard_factor: f32,
+    pub guard_banned: Vec<String>,
+    pub guard_strategy: RephraseStrategy,
+    pub guard_low_res_margin: f32,
+    pub guard_warn_low_res: bool,
+    pub guard_hard_drift: Option<f32>,
+    pub prosody_calm_wpm: f32,
+    pub prosody_energetic_wpm: f32,
     pub sync: bool,
     pub sync_lr_fast: f32,
     pub sync_lr_slow: f32,
     pub sync_step: f32,
+    pub sync_momentum: f32,
+    pub sync_accum_decay: f32,
+    pub sync_pause_clamp_ms: (i64, i64),
+    pub sync_boost_clamp: Option<f32>,
+    pub sync_lr_warming: Option<f32>,
+    pub sync_lr_overheat: Option<f32>,
+    pub sync_lr_cooldown: Option<f32>,
+    pub sync_seed_path: Option<String>,
     pub stabilizer: bool,
     pub stab_win: usize,
     pub stab_alpha: f32,
@@ -33,27 +97,79 @@ pub struct Config {
     pub stab_low_res: f32,
     pub stab_cool: usize,
     pub stab_calm: f32,
+    pub stab_median: bool,
+    pub stab_hysteresis: f32,
+    pub stab_panic: f32,
+    pub stab_state_path: Option<String>,
+    pub stab_advice: AdviceTable,
     pub astro: bool,
     pub astro_path: String,
     pub astro_cache: usize,
     pub memory: bool,
     pub memory_path: String,
+    /// Forget device memory entries not updated for this many days.
+    pub memory_max_age_days: Option<u64>,
+    /// EMA weight of each new session in device memory; 0 keeps the
+    /// cumulative mean over all sessions.
+    pub memory_alpha: f32,
     pub emote: bool,
     pub emote_path: String,
+    /// Named emote profile to read and write seeds under.
+    pub emote_profile: String,
     pub emote_half_life: u32,
     pub emote_warm: f32,
+    /// Blend this many of the latest emote seeds for the warm start.
+    pub emote_window: usize,
+    /// At shutdown, trim the seed file to this many latest seeds.
+    pub emote_keep: Option<usize>,
+    /// Anchors an aged emote seed relaxes toward.
+    pub emote_decay: EmoteDecayCfg,
     pub awareness: bool,
     pub meta_viz: bool,
     pub meta_stab_alpha: f32,
+    pub awareness_path: Option<String>,
     pub compassion: bool,
     pub compassion_viz: bool,
     pub compassion_threshold: f32,
+    pub compassion_path: Option<String>,
+    pub compassion_overheat_weight: f32,
+    pub compassion_wpm_weight: f32,
 }
 
+/// Suppresses the per-cycle `[voice]`/`[metrics]` chatter.
+pub const VERBOSITY_QUIET: u8 = 0;
+/// Default output level.
+pub const VERBOSITY_NORMAL: u8 = 1;
+/// Adds latency breakdowns to the per-cycle output.
+pub const VERBOSITY_VERBOSE: u8 = 2;
+
+/// Accepted range for `sync_lr_fast` / `sync_lr_slow`.
+pub const SYNC_LR_RANGE: (f32, f32) = (0.0, 1.0);
+/// Accepted range for `sync_step`.
+pub const SYNC_STEP_RANGE: (f32, f32) = (0.0, 0.1);
+/// Accepted range for `sync_accum_decay`.
+pub const SYNC_DECAY_RANGE: (f32, f32) = (0.5, 1.0);
+/// Accepted range for `sync_boost_clamp`.
+pub const SYNC_BOOST_RANGE: (f32, f32) = (0.0, 0.1);
+/// Accepted range for `sync_momentum`.
+pub const SYNC_MOMENTUM_RANGE: (f32, f32) = (0.0, 0.95);
+/// Accepted range for `trend_deadband`.
+pub const TREND_DEADBAND_RANGE: (f32, f32) = (0.0, 0.5);
+/// Accepted range for `memory_alpha`.
+pub const MEMORY_ALPHA_RANGE: (f32, f32) = (0.0, 1.0);
+/// Accepted range for the emote decay drift/resonance anchors and tone cutoff.
+pub const EMOTE_ANCHOR_RANGE: (f32, f32) = (0.0, 1.0);
+/// Accepted range for `emote_decay.neutral_wpm`.
+pub const EMOTE_WPM_RANGE: (f32, f32) = (60.0, 300.0);
+/// Accepted range for `latency_scale`.
+pub const LATENCY_SCALE_RANGE: (f32, f32) = (0.0, 10.0);
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VizMode {
     Compact,
     Full,
+    /// Final table as one JSON object instead of ASCII.
+    Json,
 }
 
 impl VizMode {
@@ -61,37 +177,115 @@ impl VizMode {
         match value.trim().to_ascii_lowercase().as_str() {
             "compact" => Some(VizMode::Compact),
             "full" => Some(VizMode::Full),
+            "json" => Some(VizMode::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Whether viz output uses ANSI colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// Color only when stdout is a terminal and `NO_COLOR` is unset.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    fn from_str(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
             _ => None,
         }
     }
+
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+impl Config {
+    pub fn is_quiet(&self) -> bool {
+        self.verbosity == VERBOSITY_QUIET
+    }
+
+    pub fn is_verbose(&self) -> bool {
+        self.verbosity >= VERBOSITY_VERBOSE
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            mode: "phone".to_string(),
+            mode: String::new(),
             sample_rate: 16_000,
             channels: 1,
             frame_ms: 20,
+            simulate_latency: true,
+            latency_scale: 1.0,
+            ssml: false,
             enable_metrics: true,
+            metrics_window: None,
+            metrics_out: None,
+            verbosity: VERBOSITY_NORMAL,
+            seed: None,
             viz_mode: VizMode::Compact,
+            color: ColorMode::Auto,
+            ascii: false,
+            viz_width: None,
+            trend_deadband: 0.02,
             cycles: 5,
             enable_logging: false,
             log_dir: "logs".to_string(),
+            log_append: false,
+            session_id: None,
+            log_max_bytes: None,
+            log_flush_each: false,
             script: None,
             inputs_path: None,
+            audio_path: None,
+            audio_duration_ms: None,
+            lexicon_path: None,
             baseline_drift: 0.35,
             baseline_res: 0.65,
+            crit_drift: None,
+            crit_res: None,
             alarm: true,
             strict: false,
+            strict_streak: 0,
             guard: true,
             guard_drift: 0.40,
             guard_res: 0.60,
             guard_factor: 0.2,
+            guard_banned: Vec::new(),
+            guard_strategy: RephraseStrategy::Recenter,
+            guard_low_res_margin: 0.15,
+            guard_warn_low_res: false,
+            guard_hard_drift: None,
+            prosody_calm_wpm: 120.0,
+            prosody_energetic_wpm: 180.0,
             sync: true,
             sync_lr_fast: 0.15,
             sync_lr_slow: 0.05,
             sync_step: 0.02,
+            sync_momentum: 0.0,
+            sync_accum_decay: 1.0,
+            sync_pause_clamp_ms: (-20, 40),
+            sync_boost_clamp: None,
+            sync_lr_warming: None,
+            sync_lr_overheat: None,
+            sync_lr_cooldown: None,
+            sync_seed_path: None,
             stabilizer: true,
             stab_win: 5,
             stab_alpha: 0.4,
@@ -100,25 +294,56 @@ impl Default for Config {
             stab_low_res: 0.58,
             stab_cool: 3,
             stab_calm: 0.08,
+            stab_median: false,
+            stab_hysteresis: 0.02,
+            stab_panic: 0.75,
+            stab_state_path: None,
+            stab_advice: AdviceTable::default(),
             astro: true,
             astro_path: "astro_traces.jsonl".to_string(),
             astro_cache: 512,
             memory: true,
             memory_path: "device_memory.jsonl".to_string(),
+            memory_max_age_days: None,
+            memory_alpha: DEFAULT_MEMORY_ALPHA,
             emote: true,
             emote_path: "emote_seed.jsonl".to_string(),
+            emote_profile: DEFAULT_PROFILE.to_string(),
             emote_half_life: 180,
             emote_warm: 0.02,
+            emote_window: 1,
+            emote_keep: None,
+            emote_decay: EmoteDecayCfg::default(),
             awareness: false,
             meta_viz: false,
             meta_stab_alpha: 0.25,
+            awareness_path: None,
             compassion: false,
             compassion_viz: false,
             compassion_threshold: 0.5,
+            compassion_path: None,
+            compassion_overheat_weight: 0.3,
+            compassion_wpm_weight: 0.2,
         }
     }
 }
 
+fn parse_word_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn parse_env_u64(key: &str) -> Option<u64> {
+    env::var(key).ok()?.parse().ok()
+}
+
+fn parse_env_i64(key: &str) -> Option<i64> {
+    env::var(key).ok()?.parse().ok()
+}
+
 fn parse_env_u32(key: &str) -> Option<u32> {
     env::var(key).ok()?.parse().ok()
 }
@@ -147,12 +372,156 @@ fn parse_env_f32(key: &str) -> Option<f32> {
 
 pub fn from_env_or_args() -> Config {
     let mut cfg = Config::default();
+    apply_env(&mut cfg);
+    apply_args(&mut cfg, env::args().skip(1));
+    for notice in validate(&mut cfg) {
+        eprintln!("{}", notice);
+    }
+    cfg
+}
 
-    if let Ok(mode) = env::var("LIMINAL_MODE") {
-        if !mode.trim().is_empty() {
-            cfg.mode = mode.to_ascii_lowercase();
+/// Clamps out-of-range settings in place and returns one `[config]` notice
+/// per adjusted field.
+pub fn validate(cfg: &mut Config) -> Vec<String> {
+    let mut notices = Vec::new();
+
+    clamp_setting(
+        &mut notices,
+        "sync_lr_fast",
+        &mut cfg.sync_lr_fast,
+        SYNC_LR_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "sync_lr_slow",
+        &mut cfg.sync_lr_slow,
+        SYNC_LR_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "sync_step",
+        &mut cfg.sync_step,
+        SYNC_STEP_RANGE,
+    );
+    for (name, lr) in [
+        ("sync_lr_warming", &mut cfg.sync_lr_warming),
+        ("sync_lr_overheat", &mut cfg.sync_lr_overheat),
+        ("sync_lr_cooldown", &mut cfg.sync_lr_cooldown),
+    ] {
+        if let Some(lr) = lr.as_mut() {
+            clamp_setting(&mut notices, name, lr, SYNC_LR_RANGE);
         }
     }
+    clamp_setting(
+        &mut notices,
+        "sync_accum_decay",
+        &mut cfg.sync_accum_decay,
+        SYNC_DECAY_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "sync_momentum",
+        &mut cfg.sync_momentum,
+        SYNC_MOMENTUM_RANGE,
+    );
+    if let Some(boost) = cfg.sync_boost_clamp.as_mut() {
+        clamp_setting(&mut notices, "sync_boost_clamp", boost, SYNC_BOOST_RANGE);
+    }
+    clamp_setting(
+        &mut notices,
+        "trend_deadband",
+        &mut cfg.trend_deadband,
+        TREND_DEADBAND_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "memory_alpha",
+        &mut cfg.memory_alpha,
+        MEMORY_ALPHA_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "emote_neutral_drift",
+        &mut cfg.emote_decay.neutral_drift,
+        EMOTE_ANCHOR_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "emote_neutral_res",
+        &mut cfg.emote_decay.neutral_res,
+        EMOTE_ANCHOR_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "emote_neutral_wpm",
+        &mut cfg.emote_decay.neutral_wpm,
+        EMOTE_WPM_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "emote_tone_keep",
+        &mut cfg.emote_decay.tone_keep,
+        EMOTE_ANCHOR_RANGE,
+    );
+    clamp_setting(
+        &mut notices,
+        "latency_scale",
+        &mut cfg.latency_scale,
+        LATENCY_SCALE_RANGE,
+    );
+
+    let (pause_min, pause_max) = cfg.sync_pause_clamp_ms;
+    if pause_min >= pause_max {
+        let defaults = Config::default();
+        notices.push(format!(
+            "[config] sync_pause_min {} must be below sync_pause_max {}; using {}/{}",
+            pause_min, pause_max, defaults.sync_pause_clamp_ms.0, defaults.sync_pause_clamp_ms.1
+        ));
+        cfg.sync_pause_clamp_ms = defaults.sync_pause_clamp_ms;
+    }
+
+    let ordered = cfg
+        .prosody_calm_wpm
+        .partial_cmp(&cfg.prosody_energetic_wpm)
+        .is_some_and(|ord| ord.is_lt());
+    if !ordered {
+        let defaults = Config::default();
+        notices.push(format!(
+            "[config] prosody_calm_wpm {:?} must be below prosody_energetic_wpm {:?}; using {:?}/{:?}",
+            cfg.prosody_calm_wpm,
+            cfg.prosody_energetic_wpm,
+            defaults.prosody_calm_wpm,
+            defaults.prosody_energetic_wpm
+        ));
+        cfg.prosody_calm_wpm = defaults.prosody_calm_wpm;
+        cfg.prosody_energetic_wpm = defaults.prosody_energetic_wpm;
+    }
+
+    notices
+}
+
+fn clamp_setting(notices: &mut Vec<String>, name: &str, value: &mut f32, range: (f32, f32)) {
+    let (min, max) = range;
+    let clamped = if value.is_nan() {
+        min
+    } else {
+        value.clamp(min, max)
+    };
+    if clamped != *value {
+        notices.push(format!(
+            "[config] clamped {} {:?} -> {:?}",
+            name, *value, clamped
+        ));
+        *value = clamped;
+    }
+}
+
+fn apply_env(cfg: &mut Config) {
+    if let Ok(mode) = env::var("LIMINAL_MODE")
+        && !mode.trim().is_empty()
+    {
+        cfg.mode = mode.to_ascii_lowercase();
+    }
 
     if let Some(sr) = parse_env_u32("LIMINAL_SAMPLE_RATE") {
         cfg.sample_rate = sr;
@@ -166,26 +535,88 @@ pub fn from_env_or_args() -> Config {
         cfg.frame_ms = frame;
     }
 
+    if let Some(simulate) = parse_env_bool("LIMINAL_SIMULATE_LATENCY") {
+        cfg.simulate_latency = simulate;
+    }
+
+    if let Some(scale) = parse_env_f32("LIMINAL_LATENCY_SCALE") {
+        cfg.latency_scale = scale;
+    }
+
     if let Some(enable) = parse_env_bool("LIMINAL_ENABLE_METRICS") {
         cfg.enable_metrics = enable;
     }
 
-    if let Ok(viz) = env::var("LIMINAL_VIZ_MODE") {
-        if let Some(mode) = VizMode::from_str(&viz) {
-            cfg.viz_mode = mode;
-        }
+    if let Some(level) = parse_env_u32("LIMINAL_VERBOSITY") {
+        cfg.verbosity = level.min(VERBOSITY_VERBOSE as u32) as u8;
     }
 
-    if let Some(c) = parse_env_usize("LIMINAL_CYCLES") {
-        if c > 0 {
-            cfg.cycles = c;
-        }
+    if let Some(seed) = parse_env_u64("LIMINAL_SEED") {
+        cfg.seed = Some(seed);
+    }
+
+    if let Ok(viz) = env::var("LIMINAL_VIZ_MODE")
+        && let Some(mode) = VizMode::from_str(&viz)
+    {
+        cfg.viz_mode = mode;
+    }
+
+    if let Ok(color) = env::var("LIMINAL_COLOR")
+        && let Some(mode) = ColorMode::from_str(&color)
+    {
+        cfg.color = mode;
+    }
+
+    if let Some(width) = parse_env_usize("LIMINAL_VIZ_WIDTH") {
+        cfg.viz_width = (width > 0).then_some(width);
+    }
+
+    if let Some(ascii) = parse_env_bool("LIMINAL_ASCII") {
+        cfg.ascii = ascii;
+    }
+
+    if let Some(deadband) = parse_env_f32("LIMINAL_TREND_DEADBAND") {
+        cfg.trend_deadband = deadband;
+    }
+
+    if let Some(c) = parse_env_usize("LIMINAL_CYCLES")
+        && c > 0
+    {
+        cfg.cycles = c;
+    }
+
+    if let Some(window) = parse_env_usize("LIMINAL_METRICS_WINDOW") {
+        cfg.metrics_window = (window > 0).then_some(window);
+    }
+
+    if let Ok(path) = env::var("LIMINAL_METRICS_OUT")
+        && !path.trim().is_empty()
+    {
+        cfg.metrics_out = Some(path);
     }
 
     if let Some(enable_log) = parse_env_bool("LIMINAL_LOG") {
         cfg.enable_logging = enable_log;
     }
 
+    if let Some(append) = parse_env_bool("LIMINAL_LOG_APPEND") {
+        cfg.log_append = append;
+    }
+
+    if let Ok(id) = env::var("LIMINAL_SESSION_ID")
+        && !id.trim().is_empty()
+    {
+        cfg.session_id = Some(id);
+    }
+
+    if let Some(bytes) = parse_env_u64("LIMINAL_LOG_MAX_BYTES") {
+        cfg.log_max_bytes = (bytes > 0).then_some(bytes);
+    }
+
+    if let Some(flush) = parse_env_bool("LIMINAL_LOG_FLUSH_EACH") {
+        cfg.log_flush_each = flush;
+    }
+
     if let Some(memory) = parse_env_bool("LIMINAL_MEMORY") {
         cfg.memory = memory;
     }
@@ -194,22 +625,30 @@ pub fn from_env_or_args() -> Config {
         cfg.astro = astro;
     }
 
-    if let Ok(path) = env::var("LIMINAL_ASTRO_PATH") {
-        if !path.trim().is_empty() {
-            cfg.astro_path = path;
-        }
+    if let Ok(path) = env::var("LIMINAL_ASTRO_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.astro_path = path;
     }
 
-    if let Some(cache) = parse_env_usize("LIMINAL_ASTRO_CACHE") {
-        if cache > 0 {
-            cfg.astro_cache = cache;
-        }
+    if let Some(cache) = parse_env_usize("LIMINAL_ASTRO_CACHE")
+        && cache > 0
+    {
+        cfg.astro_cache = cache;
     }
 
-    if let Ok(path) = env::var("LIMINAL_MEMORY_PATH") {
-        if !path.trim().is_empty() {
-            cfg.memory_path = path;
-        }
+    if let Ok(path) = env::var("LIMINAL_MEMORY_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.memory_path = path;
+    }
+
+    if let Some(days) = parse_env_u64("LIMINAL_MEMORY_MAX_AGE_DAYS") {
+        cfg.memory_max_age_days = (days > 0).then_some(days);
+    }
+
+    if let Some(alpha) = parse_env_f32("LIMINAL_MEMORY_ALPHA") {
+        cfg.memory_alpha = alpha;
     }
 
     if let Some(sync) = parse_env_bool("LIMINAL_SYNC") {
@@ -228,14 +667,80 @@ pub fn from_env_or_args() -> Config {
         cfg.sync_step = step;
     }
 
+    if let Some(crit) = parse_env_f32("LIMINAL_CRIT_DRIFT") {
+        cfg.crit_drift = Some(crit);
+    }
+
+    if let Some(crit) = parse_env_f32("LIMINAL_CRIT_RES") {
+        cfg.crit_res = Some(crit);
+    }
+
+    if let Some(streak) = parse_env_usize("LIMINAL_STRICT_STREAK") {
+        cfg.strict_streak = streak;
+    }
+
+    if let Some(lr) = parse_env_f32("LIMINAL_SYNC_LR_WARMING") {
+        cfg.sync_lr_warming = Some(lr);
+    }
+
+    if let Some(lr) = parse_env_f32("LIMINAL_SYNC_LR_OVERHEAT") {
+        cfg.sync_lr_overheat = Some(lr);
+    }
+
+    if let Some(lr) = parse_env_f32("LIMINAL_SYNC_LR_COOLDOWN") {
+        cfg.sync_lr_cooldown = Some(lr);
+    }
+
+    if let Some(decay) = parse_env_f32("LIMINAL_SYNC_ACCUM_DECAY") {
+        cfg.sync_accum_decay = decay;
+    }
+
+    if let Some(boost) = parse_env_f32("LIMINAL_SYNC_BOOST_CLAMP") {
+        cfg.sync_boost_clamp = Some(boost);
+    }
+
+    if let Some(ms) = parse_env_i64("LIMINAL_SYNC_PAUSE_MIN") {
+        cfg.sync_pause_clamp_ms.0 = ms;
+    }
+
+    if let Some(ms) = parse_env_i64("LIMINAL_SYNC_PAUSE_MAX") {
+        cfg.sync_pause_clamp_ms.1 = ms;
+    }
+
+    if let Some(momentum) = parse_env_f32("LIMINAL_SYNC_MOMENTUM") {
+        cfg.sync_momentum = momentum;
+    }
+
+    if let Ok(words) = env::var("LIMINAL_GUARD_BANNED") {
+        cfg.guard_banned = parse_word_list(&words);
+    }
+
+    if let Ok(strategy) = env::var("LIMINAL_GUARD_STRATEGY")
+        && let Some(strategy) = RephraseStrategy::parse(&strategy)
+    {
+        cfg.guard_strategy = strategy;
+    }
+
+    if let Ok(path) = env::var("LIMINAL_SYNC_SEED_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.sync_seed_path = Some(path);
+    }
+
     if let Some(emote) = parse_env_bool("LIMINAL_EMOTE") {
         cfg.emote = emote;
     }
 
-    if let Ok(path) = env::var("LIMINAL_EMOTE_PATH") {
-        if !path.trim().is_empty() {
-            cfg.emote_path = path;
-        }
+    if let Ok(path) = env::var("LIMINAL_EMOTE_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.emote_path = path;
+    }
+
+    if let Ok(profile) = env::var("LIMINAL_EMOTE_PROFILE")
+        && !profile.trim().is_empty()
+    {
+        cfg.emote_profile = profile;
     }
 
     if let Some(half_life) = parse_env_u32("LIMINAL_EMOTE_HALF_LIFE") {
@@ -246,6 +751,14 @@ pub fn from_env_or_args() -> Config {
         cfg.emote_warm = warm;
     }
 
+    if let Some(window) = parse_env_usize("LIMINAL_EMOTE_WINDOW") {
+        cfg.emote_window = window.max(1);
+    }
+
+    if let Some(keep) = parse_env_usize("LIMINAL_EMOTE_KEEP") {
+        cfg.emote_keep = (keep > 0).then_some(keep);
+    }
+
     if let Some(awareness) = parse_env_bool("LIMINAL_AWARENESS") {
         cfg.awareness = awareness;
     }
@@ -258,6 +771,12 @@ pub fn from_env_or_args() -> Config {
         cfg.meta_stab_alpha = alpha;
     }
 
+    if let Ok(path) = env::var("LIMINAL_AWARENESS_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.awareness_path = Some(path);
+    }
+
     if let Some(compassion) = parse_env_bool("LIMINAL_COMPASSION") {
         cfg.compassion = compassion;
     }
@@ -270,70 +789,165 @@ pub fn from_env_or_args() -> Config {
         cfg.compassion_threshold = thresh;
     }
 
-    if let Ok(dir) = env::var("LIMINAL_LOG_DIR") {
-        if !dir.trim().is_empty() {
-            cfg.log_dir = dir;
-        }
+    if let Some(weight) = parse_env_f32("LIMINAL_COMPASSION_OVERHEAT_WEIGHT") {
+        cfg.compassion_overheat_weight = weight;
+    }
+
+    if let Some(weight) = parse_env_f32("LIMINAL_COMPASSION_WPM_WEIGHT") {
+        cfg.compassion_wpm_weight = weight;
+    }
+
+    if let Ok(path) = env::var("LIMINAL_COMPASSION_PATH")
+        && !path.trim().is_empty()
+    {
+        cfg.compassion_path = Some(path);
+    }
+
+    if let Ok(path) = env::var("LIMINAL_LEXICON")
+        && !path.trim().is_empty()
+    {
+        cfg.lexicon_path = Some(path);
     }
 
-    let mut args = env::args().skip(1);
+    if let Ok(dir) = env::var("LIMINAL_LOG_DIR")
+        && !dir.trim().is_empty()
+    {
+        cfg.log_dir = dir;
+    }
+}
+
+fn parse_next<I, T>(args: &mut I, slot: &mut T)
+where
+    I: Iterator<Item = String>,
+    T: std::str::FromStr,
+{
+    parse_next_with(args, |v| *slot = v);
+}
+
+/// Parses the next argument as `T` and hands it to `apply`; a missing or
+/// unparseable value leaves the setting untouched.
+fn parse_next_with<I, T>(args: &mut I, apply: impl FnOnce(T))
+where
+    I: Iterator<Item = String>,
+    T: std::str::FromStr,
+{
+    if let Some(val) = args.next()
+        && let Ok(v) = val.parse::<T>()
+    {
+        apply(v);
+    }
+}
+
+pub fn apply_args<I>(cfg: &mut Config, args: I)
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
     while let Some(arg) = args.next() {
         match arg.as_str() {
-            "--mode" => {
+            "--mode" | "-m" => {
                 if let Some(val) = args.next() {
                     cfg.mode = val.to_ascii_lowercase();
                 }
             }
             "--sample-rate" => {
-                if let Some(val) = args.next() {
-                    if let Ok(sr) = val.parse() {
-                        cfg.sample_rate = sr;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.sample_rate);
             }
             "--channels" => {
-                if let Some(val) = args.next() {
-                    if let Ok(channels) = val.parse() {
-                        cfg.channels = channels;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.channels);
             }
             "--frame-ms" => {
-                if let Some(val) = args.next() {
-                    if let Ok(frame) = val.parse() {
-                        cfg.frame_ms = frame;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.frame_ms);
+            }
+            "--no-latency" => {
+                cfg.simulate_latency = false;
+            }
+            "--latency-scale" => {
+                parse_next(&mut args, &mut cfg.latency_scale);
+            }
+            "--ssml" => {
+                cfg.ssml = true;
             }
             "--no-metrics" => {
                 cfg.enable_metrics = false;
             }
+            "--metrics-out" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.metrics_out = Some(val);
+                }
+            }
+            "--metrics-window" => {
+                parse_next_with(&mut args, |v: usize| {
+                    cfg.metrics_window = (v > 0).then_some(v)
+                });
+            }
+            "--quiet" | "-q" => {
+                cfg.verbosity = VERBOSITY_QUIET;
+            }
+            "--verbose" | "-v" => {
+                cfg.verbosity = VERBOSITY_VERBOSE;
+            }
+            "--seed" => {
+                parse_next_with(&mut args, |v: u64| cfg.seed = Some(v));
+            }
             "--viz" => {
-                if let Some(val) = args.next() {
-                    if let Some(mode) = VizMode::from_str(&val) {
-                        cfg.viz_mode = mode;
-                    }
+                if let Some(val) = args.next()
+                    && let Some(mode) = VizMode::from_str(&val)
+                {
+                    cfg.viz_mode = mode;
                 }
             }
-            "--cycles" => {
-                if let Some(val) = args.next() {
-                    if let Ok(c) = val.parse::<usize>() {
-                        if c > 0 {
-                            cfg.cycles = c;
-                        }
-                    }
+            "--color" => {
+                if let Some(val) = args.next()
+                    && let Some(mode) = ColorMode::from_str(&val)
+                {
+                    cfg.color = mode;
                 }
             }
+            "--viz-width" => {
+                parse_next_with(&mut args, |v: usize| cfg.viz_width = (v > 0).then_some(v));
+            }
+            "--ascii" => {
+                cfg.ascii = true;
+            }
+            "--trend-deadband" => {
+                parse_next(&mut args, &mut cfg.trend_deadband);
+            }
+            "--cycles" | "-c" => {
+                parse_next_with(&mut args, |v: usize| {
+                    if v > 0 {
+                        cfg.cycles = v;
+                    }
+                });
+            }
             "--log" => {
                 cfg.enable_logging = true;
             }
             "--log-dir" => {
-                if let Some(val) = args.next() {
-                    if !val.trim().is_empty() {
-                        cfg.log_dir = val;
-                    }
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.log_dir = val;
                 }
             }
+            "--log-append" => {
+                cfg.log_append = true;
+            }
+            "--session-id" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.session_id = Some(val);
+                }
+            }
+            "--log-flush-each" => {
+                cfg.log_flush_each = true;
+            }
+            "--log-max-bytes" => {
+                parse_next_with(&mut args, |v: u64| cfg.log_max_bytes = (v > 0).then_some(v));
+            }
             "--memory" => {
                 cfg.memory = true;
             }
@@ -341,12 +955,20 @@ pub fn from_env_or_args() -> Config {
                 cfg.memory = false;
             }
             "--memory-path" => {
-                if let Some(val) = args.next() {
-                    if !val.trim().is_empty() {
-                        cfg.memory_path = val;
-                    }
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.memory_path = val;
                 }
             }
+            "--memory-alpha" => {
+                parse_next(&mut args, &mut cfg.memory_alpha);
+            }
+            "--memory-max-age-days" => {
+                parse_next_with(&mut args, |v: u64| {
+                    cfg.memory_max_age_days = (v > 0).then_some(v)
+                });
+            }
             "--sync" => {
                 cfg.sync = true;
             }
@@ -354,24 +976,43 @@ pub fn from_env_or_args() -> Config {
                 cfg.sync = false;
             }
             "--sync-lr-fast" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.sync_lr_fast = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.sync_lr_fast);
             }
             "--sync-lr-slow" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.sync_lr_slow = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.sync_lr_slow);
             }
             "--sync-step" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.sync_step = v;
-                    }
+                parse_next(&mut args, &mut cfg.sync_step);
+            }
+            "--sync-lr-warming" => {
+                parse_next_with(&mut args, |v: f32| cfg.sync_lr_warming = Some(v));
+            }
+            "--sync-lr-overheat" => {
+                parse_next_with(&mut args, |v: f32| cfg.sync_lr_overheat = Some(v));
+            }
+            "--sync-lr-cooldown" => {
+                parse_next_with(&mut args, |v: f32| cfg.sync_lr_cooldown = Some(v));
+            }
+            "--sync-accum-decay" => {
+                parse_next(&mut args, &mut cfg.sync_accum_decay);
+            }
+            "--sync-pause-min" => {
+                parse_next(&mut args, &mut cfg.sync_pause_clamp_ms.0);
+            }
+            "--sync-pause-max" => {
+                parse_next(&mut args, &mut cfg.sync_pause_clamp_ms.1);
+            }
+            "--sync-boost-clamp" => {
+                parse_next_with(&mut args, |v: f32| cfg.sync_boost_clamp = Some(v));
+            }
+            "--sync-momentum" => {
+                parse_next(&mut args, &mut cfg.sync_momentum);
+            }
+            "--sync-seed-path" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.sync_seed_path = Some(val);
                 }
             }
             "--emote" => {
@@ -381,25 +1022,42 @@ pub fn from_env_or_args() -> Config {
                 cfg.emote = false;
             }
             "--emote-path" => {
-                if let Some(val) = args.next() {
-                    if !val.trim().is_empty() {
-                        cfg.emote_path = val;
-                    }
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.emote_path = val;
                 }
             }
-            "--emote-half-life" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<u32>() {
-                        cfg.emote_half_life = v;
-                    }
+            "--emote-profile" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.emote_profile = val;
                 }
             }
+            "--emote-half-life" => {
+                parse_next(&mut args, &mut cfg.emote_half_life);
+            }
             "--emote-warm" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.emote_warm = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.emote_warm);
+            }
+            "--emote-neutral-drift" => {
+                parse_next(&mut args, &mut cfg.emote_decay.neutral_drift);
+            }
+            "--emote-neutral-res" => {
+                parse_next(&mut args, &mut cfg.emote_decay.neutral_res);
+            }
+            "--emote-neutral-wpm" => {
+                parse_next(&mut args, &mut cfg.emote_decay.neutral_wpm);
+            }
+            "--emote-tone-keep" => {
+                parse_next(&mut args, &mut cfg.emote_decay.tone_keep);
+            }
+            "--emote-keep" => {
+                parse_next_with(&mut args, |v: usize| cfg.emote_keep = (v > 0).then_some(v));
+            }
+            "--emote-window" => {
+                parse_next_with(&mut args, |v: usize| cfg.emote_window = v.max(1));
             }
             "--awareness" => {
                 cfg.awareness = true;
@@ -407,15 +1065,18 @@ pub fn from_env_or_args() -> Config {
             "--no-awareness" => {
                 cfg.awareness = false;
             }
+            "--awareness-path" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.awareness_path = Some(val);
+                }
+            }
             "--meta-viz" => {
                 cfg.meta_viz = true;
             }
             "--meta-stab-alpha" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.meta_stab_alpha = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.meta_stab_alpha);
             }
             "--compassion" => {
                 cfg.compassion = true;
@@ -427,38 +1088,65 @@ pub fn from_env_or_args() -> Config {
                 cfg.compassion_viz = true;
             }
             "--compassion-threshold" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.compassion_threshold = v;
-                    }
+                parse_next(&mut args, &mut cfg.compassion_threshold);
+            }
+            "--compassion-overheat-weight" => {
+                parse_next(&mut args, &mut cfg.compassion_overheat_weight);
+            }
+            "--compassion-wpm-weight" => {
+                parse_next(&mut args, &mut cfg.compassion_wpm_weight);
+            }
+            "--compassion-path" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.compassion_path = Some(val);
                 }
             }
+            "--prosody-calm-wpm" => {
+                parse_next(&mut args, &mut cfg.prosody_calm_wpm);
+            }
+            "--prosody-energetic-wpm" => {
+                parse_next(&mut args, &mut cfg.prosody_energetic_wpm);
+            }
             "--script" => {
                 if let Some(val) = args.next() {
                     cfg.script = Some(val);
                 }
             }
             "--inputs" => {
-                if let Some(val) = args.next() {
-                    if !val.trim().is_empty() {
-                        cfg.inputs_path = Some(val);
-                    }
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.inputs_path = Some(val);
                 }
             }
-            "--baseline-drift" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.baseline_drift = v;
-                    }
+            "--audio" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.audio_path = Some(val);
                 }
             }
-            "--baseline-res" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.baseline_res = v;
-                    }
+            "--lexicon" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.lexicon_path = Some(val);
                 }
             }
+            "--baseline-drift" => {
+                parse_next(&mut args, &mut cfg.baseline_drift);
+            }
+            "--baseline-res" => {
+                parse_next(&mut args, &mut cfg.baseline_res);
+            }
+            "--crit-drift" => {
+                parse_next_with(&mut args, |v: f32| cfg.crit_drift = Some(v));
+            }
+            "--crit-res" => {
+                parse_next_with(&mut args, |v: f32| cfg.crit_res = Some(v));
+            }
             "--alarm" => {
                 cfg.alarm = true;
             }
@@ -468,6 +1156,9 @@ pub fn from_env_or_args() -> Config {
             "--strict" => {
                 cfg.strict = true;
             }
+            "--strict-streak" => {
+                parse_next(&mut args, &mut cfg.strict_streak);
+            }
             "--guard" => {
                 cfg.guard = true;
             }
@@ -475,24 +1166,33 @@ pub fn from_env_or_args() -> Config {
                 cfg.guard = false;
             }
             "--guard-drift" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.guard_drift = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.guard_drift);
             }
             "--guard-res" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.guard_res = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.guard_res);
             }
             "--guard-factor" => {
+                parse_next(&mut args, &mut cfg.guard_factor);
+            }
+            "--guard-banned" => {
                 if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.guard_factor = v;
-                    }
+                    cfg.guard_banned = parse_word_list(&val);
+                }
+            }
+            "--guard-hard-drift" => {
+                parse_next_with(&mut args, |v: f32| cfg.guard_hard_drift = Some(v));
+            }
+            "--guard-low-res-margin" => {
+                parse_next(&mut args, &mut cfg.guard_low_res_margin);
+            }
+            "--guard-warn-low-res" => {
+                cfg.guard_warn_low_res = true;
+            }
+            "--guard-strategy" => {
+                if let Some(val) = args.next()
+                    && let Some(strategy) = RephraseStrategy::parse(&val)
+                {
+                    cfg.guard_strategy = strategy;
                 }
             }
             "--stabilizer" => {
@@ -502,61 +1202,75 @@ pub fn from_env_or_args() -> Config {
                 cfg.stabilizer = false;
             }
             "--stab-win" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<usize>() {
-                        if v > 0 {
-                            cfg.stab_win = v;
-                        }
+                parse_next_with(&mut args, |v: usize| {
+                    if v > 0 {
+                        cfg.stab_win = v;
                     }
-                }
+                });
             }
             "--stab-alpha" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.stab_alpha = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.stab_alpha);
             }
             "--stab-warm" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.stab_warm = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.stab_warm);
             }
             "--stab-hot" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.stab_hot = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.stab_hot);
             }
             "--stab-lowres" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.stab_low_res = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.stab_low_res);
             }
             "--stab-cool" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<usize>() {
-                        if v > 0 {
-                            cfg.stab_cool = v;
-                        }
+                parse_next_with(&mut args, |v: usize| {
+                    if v > 0 {
+                        cfg.stab_cool = v;
                     }
+                });
+            }
+            "--stab-panic" => {
+                parse_next(&mut args, &mut cfg.stab_panic);
+            }
+            "--stab-state-path" => {
+                if let Some(val) = args.next()
+                    && !val.trim().is_empty()
+                {
+                    cfg.stab_state_path = Some(val);
                 }
             }
+            "--stab-warming-pace" => {
+                parse_next(&mut args, &mut cfg.stab_advice.warming.pace_delta);
+            }
+            "--stab-warming-pause" => {
+                parse_next(&mut args, &mut cfg.stab_advice.warming.pause_delta_ms);
+            }
+            "--stab-overheat-pace" => {
+                parse_next(&mut args, &mut cfg.stab_advice.overheat.pace_delta);
+            }
+            "--stab-overheat-pause" => {
+                parse_next(&mut args, &mut cfg.stab_advice.overheat.pause_delta_ms);
+            }
+            "--stab-cooldown-pace" => {
+                parse_next(&mut args, &mut cfg.stab_advice.cooldown.pace_delta);
+            }
+            "--stab-cooldown-pause" => {
+                parse_next(&mut args, &mut cfg.stab_advice.cooldown.pause_delta_ms);
+            }
+            "--stab-panic-pace" => {
+                parse_next(&mut args, &mut cfg.stab_advice.panic.pace_delta);
+            }
+            "--stab-panic-pause" => {
+                parse_next(&mut args, &mut cfg.stab_advice.panic.pause_delta_ms);
+            }
+            "--stab-hysteresis" => {
+                parse_next(&mut args, &mut cfg.stab_hysteresis);
+            }
+            "--stab-median" => {
+                cfg.stab_median = true;
+            }
             "--stab-calm" => {
-                if let Some(val) = args.next() {
-                    if let Ok(v) = val.parse::<f32>() {
-                        cfg.stab_calm = v;
-                    }
-                }
+                parse_next(&mut args, &mut cfg.stab_calm);
             }
             _ => {}
         }
     }
-
-    cfg
 }
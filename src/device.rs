@@ -1,8 +1,18 @@
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeviceMode {
     Phone,
     Headset,
     Terminal,
+    /// Cabin audio over road noise: louder, slower, longer pauses.
+    Car,
+    /// Far-field playback across a room.
+    SmartSpeaker,
+    /// Unknown or mixed hardware: halfway between Phone and Headset.
+    Auto,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -10,14 +20,88 @@ pub struct DeviceProfile {
     pub gain_db: f32,
     pub pace_factor: f32,
     pub pause_ms: u64,
+    /// Alert baselines for this device; `None` defers to the config.
+    pub baseline_drift: Option<f32>,
+    pub baseline_res: Option<f32>,
+}
+
+impl DeviceProfile {
+    /// The profile's drift/resonance baselines, falling back to the given
+    /// config values for any the profile leaves unset.
+    pub fn baselines(&self, drift: f32, res: f32) -> (f32, f32) {
+        (
+            self.baseline_drift.unwrap_or(drift),
+            self.baseline_res.unwrap_or(res),
+        )
+    }
 }
 
+pub const GAIN_DB_RANGE: (f32, f32) = (-12.0, 12.0);
+pub const PACE_FACTOR_RANGE: (f32, f32) = (0.8, 1.2);
+pub const PAUSE_MS_RANGE: (u64, u64) = (20, 200);
+
+impl FromStr for DeviceMode {
+    type Err = String;
+
+    /// Case-insensitive; accepts the names `Display` prints plus a few aliases.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "phone" => Ok(DeviceMode::Phone),
+            "headset" => Ok(DeviceMode::Headset),
+            "terminal" => Ok(DeviceMode::Terminal),
+            "car" => Ok(DeviceMode::Car),
+            "smartspeaker" | "smart-speaker" | "smart_speaker" => Ok(DeviceMode::SmartSpeaker),
+            "auto" => Ok(DeviceMode::Auto),
+            other => Err(format!("unknown device mode '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for DeviceMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeviceMode::Phone => "phone",
+            DeviceMode::Headset => "headset",
+            DeviceMode::Terminal => "terminal",
+            DeviceMode::Car => "car",
+            DeviceMode::SmartSpeaker => "smartspeaker",
+            DeviceMode::Auto => "auto",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Parses a mode name, falling back to `Phone` for anything unrecognised.
 pub fn detect(mode_str: &str) -> DeviceMode {
-    match mode_str.to_ascii_lowercase().as_str() {
-        "phone" => DeviceMode::Phone,
-        "headset" => DeviceMode::Headset,
-        "terminal" => DeviceMode::Terminal,
-        _ => DeviceMode::Phone,
+    mode_str.parse().unwrap_or(DeviceMode::Phone)
+}
+
+/// Guesses the mode when none was given: an explicit `LIMINAL_AUDIO_DEVICE`
+/// hint wins, then an SSH session or a bare console means Terminal, and
+/// anything else stays Phone.
+pub fn auto_detect() -> DeviceMode {
+    auto_detect_from(|key| env::var(key).ok())
+}
+
+/// `auto_detect` with the environment lookup supplied by the caller.
+pub fn auto_detect_from<F>(var: F) -> DeviceMode
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if let Some(mode) = var("LIMINAL_AUDIO_DEVICE").and_then(|hint| hint.parse().ok()) {
+        return mode;
+    }
+    if var("SSH_TTY").is_some_and(|tty| !tty.is_empty()) {
+        return DeviceMode::Terminal;
+    }
+    let bare_console = var("TERM").is_some_and(|term| {
+        let term = term.to_ascii_lowercase();
+        term == "dumb" || term == "linux" || term.starts_with("vt")
+    });
+    if bare_console {
+        DeviceMode::Terminal
+    } else {
+        DeviceMode::Phone
     }
 }
 
@@ -27,16 +111,65 @@ pub fn profile(mode: &DeviceMode) -> DeviceProfile {
             gain_db: -2.0,
             pace_factor: 1.05,
             pause_ms: 60,
+            baseline_drift: None,
+            baseline_res: None,
         },
         DeviceMode::Headset => DeviceProfile {
             gain_db: 0.0,
             pace_factor: 1.00,
             pause_ms: 40,
+            baseline_drift: None,
+            baseline_res: None,
         },
         DeviceMode::Terminal => DeviceProfile {
             gain_db: 1.5,
             pace_factor: 0.95,
             pause_ms: 80,
+            baseline_drift: None,
+            baseline_res: None,
+        },
+        DeviceMode::Car => DeviceProfile {
+            gain_db: 4.0,
+            pace_factor: 0.90,
+            pause_ms: 110,
+            baseline_drift: Some(0.40),
+            baseline_res: None,
+        },
+        DeviceMode::SmartSpeaker => DeviceProfile {
+            gain_db: 3.0,
+            pace_factor: 0.92,
+            pause_ms: 120,
+            baseline_drift: Some(0.40),
+            baseline_res: Some(0.60),
         },
+        DeviceMode::Auto => blend(
+            &profile(&DeviceMode::Phone),
+            &profile(&DeviceMode::Headset),
+            0.5,
+        ),
+    }
+}
+
+/// Interpolates two baselines; when only one side sets it, that one wins.
+fn blend_baseline(a: Option<f32>, b: Option<f32>, t: f32) -> Option<f32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a + (b - a) * t).clamp(0.0, 1.0)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Interpolates every field from `a` (`t = 0`) to `b` (`t = 1`), clamped to
+/// the valid profile ranges.
+pub fn blend(a: &DeviceProfile, b: &DeviceProfile, t: f32) -> DeviceProfile {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |x: f32, y: f32| x + (y - x) * t;
+    let pause = lerp(a.pause_ms as f32, b.pause_ms as f32).round() as u64;
+    DeviceProfile {
+        gain_db: lerp(a.gain_db, b.gain_db).clamp(GAIN_DB_RANGE.0, GAIN_DB_RANGE.1),
+        pace_factor: lerp(a.pace_factor, b.pace_factor)
+            .clamp(PACE_FACTOR_RANGE.0, PACE_FACTOR_RANGE.1),
+        pause_ms: pause.clamp(PAUSE_MS_RANGE.0, PAUSE_MS_RANGE.1),
+        baseline_drift: blend_baseline(a.baseline_drift, b.baseline_drift, t),
+        baseline_res: blend_baseline(a.baseline_res, b.baseline_res, t),
     }
 }
@@ -1,5 +1,8 @@
 use std::{collections::HashMap, fs, path::Path};
 
+use crate::session::{escape_json, parse_json_object};
+use crate::utils;
+
 #[derive(Clone, Debug, Default)]
 pub struct DeviceMemory {
     pub avg_pace: f32,
@@ -8,59 +11,58 @@ pub struct DeviceMemory {
     pub avg_drift: f32,
     pub avg_res: f32,
     pub sessions: u32,
+    /// Unix seconds of the last update; 0 when unknown (older files).
+    pub last_seen: i64,
 }
 
-#[derive(Debug)]
+const SECS_PER_DAY: i64 = 86_400;
+
+/// EMA weight the CLI gives each new session unless `--memory-alpha` says
+/// otherwise.
+pub const DEFAULT_MEMORY_ALPHA: f32 = 0.3;
+
+#[derive(Debug, Default)]
 pub struct DeviceMemoryStore {
     pub path: String,
     pub data: HashMap<String, DeviceMemory>,
-}
-
-impl Default for DeviceMemoryStore {
-    fn default() -> Self {
-        Self {
-            path: String::new(),
-            data: HashMap::new(),
-        }
-    }
+    /// Weight of each new session in an EMA update; `None` keeps the
+    /// cumulative mean over all sessions.
+    pub alpha: Option<f32>,
 }
 
 impl DeviceMemoryStore {
+    /// Reads one JSON object per line. Lines in the legacy pipe format
+    /// (`key|pace|pause|art|drift|res|sessions`) are still accepted, and the
+    /// next `save` rewrites them as JSON. Malformed lines are skipped with a
+    /// warning on stderr so the remaining devices still load.
     pub fn load(path: &str) -> Self {
         let mut store = Self {
             path: path.to_string(),
-            data: HashMap::new(),
+            ..Self::default()
         };
 
-        if Path::new(path).exists() {
-            if let Ok(txt) = fs::read_to_string(path) {
-                for line in txt.lines() {
-                    if line.trim().is_empty() {
-                        continue;
-                    }
-                    let parts: Vec<&str> = line.split('|').collect();
-                    if parts.len() == 7 {
-                        if let (Ok(pace), Ok(pause), Ok(art), Ok(drift), Ok(res), Ok(sess)) = (
-                            parts[1].parse::<f32>(),
-                            parts[2].parse::<f32>(),
-                            parts[3].parse::<f32>(),
-                            parts[4].parse::<f32>(),
-                            parts[5].parse::<f32>(),
-                            parts[6].parse::<u32>(),
-                        ) {
-                            store.data.insert(
-                                parts[0].to_string(),
-                                DeviceMemory {
-                                    avg_pace: pace,
-                                    avg_pause: pause,
-                                    avg_articulation: art,
-                                    avg_drift: drift,
-                                    avg_res: res,
-                                    sessions: sess,
-                                },
-                            );
-                        }
+        if Path::new(path).exists()
+            && let Ok(txt) = fs::read_to_string(path)
+        {
+            for (lineno, line) in txt.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let parsed = if line.starts_with('{') {
+                    parse_json_line(line)
+                } else {
+                    parse_legacy_line(line)
+                };
+                match parsed {
+                    Some((key, memory)) => {
+                        store.data.insert(key, memory);
                     }
+                    None => eprintln!(
+                        "[memory] skipping malformed line {} in {}",
+                        lineno + 1,
+                        path
+                    ),
                 }
             }
         }
@@ -69,28 +71,85 @@ impl DeviceMemoryStore {
     }
 
     pub fn update(&mut self, device: &str, pace: f32, pause: f32, art: f32, drift: f32, res: f32) {
+        let alpha = self.alpha;
         let entry = self.data.entry(device.to_string()).or_default();
         entry.sessions += 1;
-        let n = entry.sessions as f32;
-        entry.avg_pace = (entry.avg_pace * (n - 1.0) + pace) / n;
-        entry.avg_pause = (entry.avg_pause * (n - 1.0) + pause) / n;
-        entry.avg_articulation = (entry.avg_articulation * (n - 1.0) + art) / n;
-        entry.avg_drift = (entry.avg_drift * (n - 1.0) + drift) / n;
-        entry.avg_res = (entry.avg_res * (n - 1.0) + res) / n;
+        entry.last_seen = utils::current_unix_secs();
+        // The first session always seeds the averages outright.
+        let weight = match alpha {
+            Some(alpha) if entry.sessions > 1 => alpha.clamp(0.0, 1.0),
+            _ => 1.0 / entry.sessions as f32,
+        };
+        let step = |avg: &mut f32, value: f32| *avg += (value - *avg) * weight;
+        step(&mut entry.avg_pace, pace);
+        step(&mut entry.avg_pause, pause);
+        step(&mut entry.avg_articulation, art);
+        step(&mut entry.avg_drift, drift);
+        step(&mut entry.avg_res, res);
+    }
+
+    /// Drops entries not updated within `max_age_days` of `now`, returning
+    /// how many were removed. Entries with an unknown age are kept.
+    pub fn prune(&mut self, now: i64, max_age_days: u64) -> usize {
+        let max_age = (max_age_days as i64).saturating_mul(SECS_PER_DAY);
+        let before = self.data.len();
+        self.data
+            .retain(|_, mem| mem.last_seen == 0 || now - mem.last_seen <= max_age);
+        before - self.data.len()
+    }
+
+    /// Forgets `device` together with its tone buckets and saves. Returns
+    /// whether anything was stored for it.
+    pub fn remove(&mut self, device: &str) -> bool {
+        let bucket_prefix = tone_key(device, "");
+        let before = self.data.len();
+        self.data
+            .retain(|key, _| key != device && !key.starts_with(&bucket_prefix));
+        let removed = self.data.len() != before;
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Forgets every device and saves the now-empty store.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.save();
+    }
+
+    /// Records the averages of one session's `sample` under both the
+    /// `{device}:{tone}` bucket and the device-wide entry, so tone lookups can
+    /// fall back to the latter. Its `sessions` and `last_seen` are ignored.
+    pub fn update_tone(&mut self, device: &str, tone: &str, sample: &DeviceMemory) {
+        for key in [tone_key(device, tone).as_str(), device] {
+            self.update(
+                key,
+                sample.avg_pace,
+                sample.avg_pause,
+                sample.avg_articulation,
+                sample.avg_drift,
+                sample.avg_res,
+            );
+        }
     }
 
     pub fn save(&self) {
+        let mut keys: Vec<&String> = self.data.keys().collect();
+        keys.sort();
         let mut out = String::new();
-        for (key, value) in &self.data {
+        for key in keys {
+            let value = &self.data[key];
             out.push_str(&format!(
-                "{}|{:.3}|{:.1}|{:.3}|{:.3}|{:.3}|{}\n",
-                key,
+                "{{\"device\":\"{}\",\"avg_pace\":{:.3},\"avg_pause\":{:.1},\"avg_articulation\":{:.3},\"avg_drift\":{:.3},\"avg_res\":{:.3},\"sessions\":{},\"last_seen\":{}}}\n",
+                escape_json(key),
                 value.avg_pace,
                 value.avg_pause,
                 value.avg_articulation,
                 value.avg_drift,
                 value.avg_res,
-                value.sessions
+                value.sessions,
+                value.last_seen
             ));
         }
         if !self.path.is_empty() {
@@ -99,6 +158,58 @@ impl DeviceMemoryStore {
     }
 }
 
+fn parse_json_line(line: &str) -> Option<(String, DeviceMemory)> {
+    let fields = parse_json_object(line)?;
+    let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+    let num = |key: &str| get(key)?.as_f64();
+    Some((
+        get("device")?.as_str()?.to_string(),
+        DeviceMemory {
+            avg_pace: num("avg_pace")? as f32,
+            avg_pause: num("avg_pause")? as f32,
+            avg_articulation: num("avg_articulation")? as f32,
+            avg_drift: num("avg_drift")? as f32,
+            avg_res: num("avg_res")? as f32,
+            sessions: num("sessions")? as u32,
+            last_seen: num("last_seen").map_or(0, |v| v as i64),
+        },
+    ))
+}
+
+fn parse_legacy_line(line: &str) -> Option<(String, DeviceMemory)> {
+    let parts: Vec<&str> = line.split('|').collect();
+    if parts.len() != 7 {
+        return None;
+    }
+    Some((
+        parts[0].to_string(),
+        DeviceMemory {
+            avg_pace: parts[1].parse().ok()?,
+            avg_pause: parts[2].parse().ok()?,
+            avg_articulation: parts[3].parse().ok()?,
+            avg_drift: parts[4].parse().ok()?,
+            avg_res: parts[5].parse().ok()?,
+            sessions: parts[6].parse().ok()?,
+            last_seen: 0,
+        },
+    ))
+}
+
 pub fn suggest_profile(store: &DeviceMemoryStore, device: &str) -> Option<DeviceMemory> {
     store.data.get(device).cloned()
 }
+
+/// Store key for one device's tone bucket, e.g. `Phone:Calm`.
+pub fn tone_key(device: &str, tone: &str) -> String {
+    format!("{}:{}", device, tone)
+}
+
+/// The memory for `device` in the given tone bucket, or the device-wide
+/// average when that bucket has no sessions yet.
+pub fn suggest_tone_profile(
+    store: &DeviceMemoryStore,
+    device: &str,
+    tone: &str,
+) -> Option<DeviceMemory> {
+    suggest_profile(store, &tone_key(device, tone)).or_else(|| suggest_profile(store, device))
+}
@@ -1,15 +1,36 @@
+use std::collections::HashMap;
 use std::fs::OpenOptions;
-use std::io::{self, BufRead, BufReader, Write};
-use std::path::Path;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::prosody::ToneTag;
+use crate::session::{escape_json, parse_json_object};
+use crate::utils;
+
+/// Profile of seeds written before profiles existed.
+pub const DEFAULT_PROFILE: &str = "default";
 
 // Persisted seed of emotional state
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct EmoteSeed {
-    pub ema_drift: f32, // 0..1
-    pub ema_res: f32,   // 0..1
-    pub tone: String,   // "Calm" | "Neutral" | "Energetic"
-    pub wpm: f32,       // last observed
-    pub ts_unix: i64,   // seconds
+    pub ema_drift: f32,  // 0..1
+    pub ema_res: f32,    // 0..1
+    pub tone: ToneTag,   // stored as its lowercase name
+    pub wpm: f32,        // last observed
+    pub ts_unix: i64,    // seconds
+    pub profile: String, // whose seed this is; "default" when absent
+}
+
+impl Default for EmoteSeed {
+    fn default() -> Self {
+        Self {
+            ema_drift: 0.0,
+            ema_res: 0.0,
+            tone: ToneTag::default(),
+            wpm: 0.0,
+            ts_unix: 0,
+            profile: DEFAULT_PROFILE.to_string(),
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -19,6 +40,7 @@ pub struct EmoteCfg {
     pub enable: bool,       // default true
     pub half_life_min: u32, // default 180 (3h)
     pub warm_bias: f32,     // default 0.02
+    pub decay: EmoteDecayCfg,
 }
 
 impl Default for EmoteCfg {
@@ -28,49 +50,230 @@ impl Default for EmoteCfg {
             enable: true,
             half_life_min: 180,
             warm_bias: 0.02,
+            decay: EmoteDecayCfg::default(),
+        }
+    }
+}
+
+/// Pace a seed relaxes toward, and the stand-in when a seed lacks one.
+const NEUTRAL_WPM: f32 = 160.0;
+
+// Neutral anchors a seed relaxes toward as it ages
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmoteDecayCfg {
+    pub neutral_drift: f32, // default 0.30
+    pub neutral_res: f32,   // default 0.70
+    pub neutral_wpm: f32,   // default 160
+    pub tone_keep: f32,     // default 0.3; below this retention the tone resets to Neutral
+}
+
+impl Default for EmoteDecayCfg {
+    fn default() -> Self {
+        Self {
+            neutral_drift: 0.30,
+            neutral_res: 0.70,
+            neutral_wpm: NEUTRAL_WPM,
+            tone_keep: 0.3,
         }
     }
 }
 
+/// The most recent seed that parses, skipping a partial final line. The
+/// file is read backward, so only its tail is touched however long it grows.
 pub fn load_latest(path: &str) -> Option<EmoteSeed> {
-    let file = OpenOptions::new().read(true).open(path).ok()?;
-    let reader = BufReader::new(file);
-    let mut lines: Vec<String> = reader.lines().filter_map(Result::ok).collect();
-    while let Some(line) = lines.pop() {
+    load_latest_for(path, DEFAULT_PROFILE)
+}
+
+/// `load_latest`, considering only seeds saved under `profile`.
+pub fn load_latest_for(path: &str, profile: &str) -> Option<EmoteSeed> {
+    let mut file = OpenOptions::new().read(true).open(path).ok()?;
+    load_latest_from(&mut file, profile)
+}
+
+/// `load_latest_for` over any seekable reader.
+pub fn load_latest_from<R: Read + Seek>(reader: &mut R, profile: &str) -> Option<EmoteSeed> {
+    ReverseLines::new(reader)?.find_map(|line| {
         let trimmed = line.trim();
         if trimmed.is_empty() {
-            continue;
-        }
-        if let Some(seed) = parse_seed(trimmed) {
-            return Some(seed);
+            None
+        } else {
+            parse_seed(trimmed).filter(|seed| seed.profile == profile)
         }
+    })
+}
+
+/// Bytes pulled per backward read.
+const REVERSE_CHUNK: u64 = 4096;
+
+/// Yields a reader's lines last to first, reading `REVERSE_CHUNK` bytes at
+/// a time from the end.
+struct ReverseLines<'a, R> {
+    reader: &'a mut R,
+    /// Start of the bytes already read.
+    pos: u64,
+    /// Read bytes not yet handed out as lines.
+    pending: Vec<u8>,
+}
+
+impl<'a, R: Read + Seek> ReverseLines<'a, R> {
+    fn new(reader: &'a mut R) -> Option<Self> {
+        let pos = reader.seek(SeekFrom::End(0)).ok()?;
+        Some(Self {
+            reader,
+            pos,
+            pending: Vec::new(),
+        })
     }
-    None
 }
 
-pub fn save_append(path: &str, seed: &EmoteSeed) -> io::Result<()> {
-    let parent = Path::new(path).parent();
-    if let Some(dir) = parent {
-        if !dir.as_os_str().is_empty() {
-            std::fs::create_dir_all(dir)?;
+impl<R: Read + Seek> Iterator for ReverseLines<'_, R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(nl) = self.pending.iter().rposition(|&b| b == b'\n') {
+                let line = self.pending.split_off(nl + 1);
+                self.pending.truncate(nl);
+                return Some(String::from_utf8_lossy(&line).into_owned());
+            }
+            if self.pos == 0 {
+                if self.pending.is_empty() {
+                    return None;
+                }
+                let line = std::mem::take(&mut self.pending);
+                return Some(String::from_utf8_lossy(&line).into_owned());
+            }
+            let len = REVERSE_CHUNK.min(self.pos);
+            self.pos -= len;
+            let mut chunk = vec![0; len as usize];
+            self.reader.seek(SeekFrom::Start(self.pos)).ok()?;
+            self.reader.read_exact(&mut chunk).ok()?;
+            chunk.append(&mut self.pending);
+            self.pending = chunk;
         }
     }
+}
 
-    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+/// The last `n` valid seeds in the file, oldest first. Like `load_latest`
+/// the file is read backward, stopping once `n` seeds are found.
+pub fn load_recent(path: &str, n: usize) -> Vec<EmoteSeed> {
+    load_recent_for(path, DEFAULT_PROFILE, n)
+}
 
-    let line = format!(
-        "{{\"ema_drift\":{:.6},\"ema_res\":{:.6},\"tone\":\"{}\",\"wpm\":{:.3},\"ts\":{}}}\n",
-        seed.ema_drift.clamp(0.0, 1.0),
-        seed.ema_res.clamp(0.0, 1.0),
-        escape_json(&seed.tone),
-        seed.wpm,
-        seed.ts_unix
-    );
+/// `load_recent`, considering only seeds saved under `profile`.
+pub fn load_recent_for(path: &str, profile: &str, n: usize) -> Vec<EmoteSeed> {
+    match OpenOptions::new().read(true).open(path) {
+        Ok(mut file) => load_recent_from(&mut file, profile, n),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// `load_recent_for` over any seekable reader.
+pub fn load_recent_from<R: Read + Seek>(reader: &mut R, profile: &str, n: usize) -> Vec<EmoteSeed> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let Some(lines) = ReverseLines::new(reader) else {
+        return Vec::new();
+    };
+    let mut recent: Vec<EmoteSeed> = lines
+        .filter_map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                parse_seed(trimmed).filter(|seed| seed.profile == profile)
+            }
+        })
+        .take(n)
+        .collect();
+    recent.reverse();
+    recent
+}
+
+/// Weight of each newer seed when blending a window of seeds.
+pub const SEED_BLEND_ALPHA: f32 = 0.5;
+
+/// EMA over `seeds` (oldest first) so the most recent dominates without a
+/// single odd session deciding the warm start alone. Tone, timestamp and
+/// profile come from the newest seed.
+pub fn blend_seeds(seeds: &[EmoteSeed]) -> EmoteSeed {
+    let Some((first, rest)) = seeds.split_first() else {
+        return EmoteSeed::default();
+    };
+    rest.iter().fold(first.clone(), |acc, seed| EmoteSeed {
+        ema_drift: lerp(acc.ema_drift, seed.ema_drift, SEED_BLEND_ALPHA),
+        ema_res: lerp(acc.ema_res, seed.ema_res, SEED_BLEND_ALPHA),
+        wpm: lerp(acc.wpm, seed.wpm, SEED_BLEND_ALPHA),
+        tone: seed.tone,
+        ts_unix: seed.ts_unix,
+        profile: seed.profile.clone(),
+    })
+}
+
+pub fn save_append(path: &str, seed: &EmoteSeed) -> io::Result<()> {
+    utils::append_line(
+        path,
+        &format!(
+            "{{\"ema_drift\":{:.6},\"ema_res\":{:.6},\"tone\":\"{}\",\"wpm\":{:.3},\"ts\":{},\"profile\":\"{}\"}}",
+            seed.ema_drift.clamp(0.0, 1.0),
+            seed.ema_res.clamp(0.0, 1.0),
+            seed.tone.as_str(),
+            seed.wpm,
+            seed.ts_unix,
+            escape_json(&seed.profile)
+        ),
+    )
+}
+
+/// Rewrites the seed file with only the most recent `keep` valid seeds of
+/// each profile, in their original order, and returns how many lines were
+/// dropped. The new contents go to a sibling temp file first so a crash
+/// cannot truncate the history.
+pub fn prune(path: &str, keep: usize) -> io::Result<usize> {
+    let contents = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+    let valid: Vec<(&str, String)> = lines
+        .iter()
+        .filter_map(|line| parse_seed(line.trim()).map(|seed| (*line, seed.profile)))
+        .collect();
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for (_, profile) in &valid {
+        *remaining.entry(profile).or_default() += 1;
+    }
+    let mut kept = Vec::new();
+    for (line, profile) in &valid {
+        let left = remaining.get_mut(profile.as_str()).expect("counted above");
+        if *left <= keep {
+            kept.push(*line);
+        }
+        *left -= 1;
+    }
+    let dropped = lines.len() - kept.len();
+    if dropped == 0 {
+        return Ok(0);
+    }
 
-    file.write_all(line.as_bytes())
+    let mut out = String::new();
+    for line in kept {
+        out.push_str(line);
+        out.push('\n');
+    }
+    utils::write_atomic(path, &out)?;
+    Ok(dropped)
 }
 
 pub fn decay(seed: &EmoteSeed, now: i64, half_life_min: u32) -> EmoteSeed {
+    decay_with(seed, now, half_life_min, &EmoteDecayCfg::default())
+}
+
+/// `decay` toward the anchors in `cfg` instead of the defaults.
+pub fn decay_with(
+    seed: &EmoteSeed,
+    now: i64,
+    half_life_min: u32,
+    cfg: &EmoteDecayCfg,
+) -> EmoteSeed {
     let elapsed_secs = now.saturating_sub(seed.ts_unix);
     let elapsed_mins = (elapsed_secs as f32).max(0.0) / 60.0;
     let k = if half_life_min == 0 {
@@ -80,13 +283,13 @@ pub fn decay(seed: &EmoteSeed, now: i64, half_life_min: u32) -> EmoteSeed {
         0.5_f32.powf((elapsed_mins / hl).max(0.0))
     };
 
-    let ema_drift = lerp(0.30, seed.ema_drift, k);
-    let ema_res = lerp(0.70, seed.ema_res, k);
-    let wpm = lerp(160.0, seed.wpm, k);
-    let tone = if k > 0.3 {
-        seed.tone.clone()
+    let ema_drift = lerp(cfg.neutral_drift, seed.ema_drift, k);
+    let ema_res = lerp(cfg.neutral_res, seed.ema_res, k);
+    let wpm = lerp(cfg.neutral_wpm, seed.wpm, k);
+    let tone = if k > cfg.tone_keep {
+        seed.tone
     } else {
-        "Neutral".to_string()
+        ToneTag::Neutral
     };
 
     EmoteSeed {
@@ -95,6 +298,7 @@ pub fn decay(seed: &EmoteSeed, now: i64, half_life_min: u32) -> EmoteSeed {
         tone,
         wpm,
         ts_unix: seed.ts_unix,
+        profile: seed.profile.clone(),
     }
 }
 
@@ -102,12 +306,56 @@ pub fn apply_boot_bias(ema_res: &mut f32, warm_bias: f32) {
     *ema_res = (*ema_res + warm_bias).min(1.0);
 }
 
+/// Parses one seed line. A line that is not a complete JSON object (a write
+/// cut short by a crash) or lacks a usable `ema_drift`/`ema_res` is rejected;
+/// a missing or bad tone, wpm or timestamp falls back to a neutral default.
+/// Every field problem is reported on stderr.
 fn parse_seed(line: &str) -> Option<EmoteSeed> {
-    let ema_drift = parse_f32_field(line, "\"ema_drift\":")?;
-    let ema_res = parse_f32_field(line, "\"ema_res\":")?;
-    let tone = parse_string_field(line, "\"tone\":")?;
-    let wpm = parse_f32_field(line, "\"wpm\":")?;
-    let ts = parse_i64_field(line, "\"ts\":")?;
+    let Some(fields) = parse_json_object(line) else {
+        eprintln!("[emote] skipping truncated seed line");
+        return None;
+    };
+    let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+    let num = |key: &str| get(key)?.as_f64();
+    let text = |key: &str| get(key)?.as_str().map(str::to_string);
+    let required = |key: &str| {
+        let value = num(key).map(|v| v as f32);
+        if value.is_none() {
+            eprintln!("[emote] skipping seed: missing or invalid {}", key);
+        }
+        value
+    };
+    let ema_drift = required("ema_drift")?;
+    let ema_res = required("ema_res")?;
+
+    let tone = match text("tone") {
+        Some(raw) => raw.parse().unwrap_or_else(|err| {
+            eprintln!("[emote] {} in seed, using neutral", err);
+            ToneTag::Neutral
+        }),
+        None => {
+            eprintln!("[emote] seed missing tone, using neutral");
+            ToneTag::Neutral
+        }
+    };
+    let wpm = num("wpm").map_or_else(
+        || {
+            eprintln!("[emote] seed missing or invalid wpm, using {}", NEUTRAL_WPM);
+            NEUTRAL_WPM
+        },
+        |v| v as f32,
+    );
+    // Without a timestamp the seed is treated as long idle and decays fully.
+    let ts = num("ts").map_or_else(
+        || {
+            eprintln!("[emote] seed missing or invalid ts, treating it as stale");
+            0
+        },
+        |v| v as i64,
+    );
+
+    // Seeds from before profiles existed belong to the default one.
+    let profile = text("profile").unwrap_or_else(|| DEFAULT_PROFILE.to_string());
 
     Some(EmoteSeed {
         ema_drift,
@@ -115,80 +363,14 @@ fn parse_seed(line: &str) -> Option<EmoteSeed> {
         tone,
         wpm,
         ts_unix: ts,
+        profile,
     })
 }
 
-fn parse_f32_field(line: &str, key: &str) -> Option<f32> {
-    let raw = parse_raw_field(line, key)?;
-    raw.parse::<f32>().ok()
-}
-
-fn parse_i64_field(line: &str, key: &str) -> Option<i64> {
-    let raw = parse_raw_field(line, key)?;
-    raw.parse::<i64>().ok()
-}
-
-fn parse_raw_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
-    let idx = line.find(key)?;
-    let start = idx + key.len();
-    let rest = line[start..].trim_start();
-    let end = rest
-        .find(|c| c == ',' || c == '}')
-        .unwrap_or_else(|| rest.len());
-    let value = rest[..end].trim();
-    if value.is_empty() { None } else { Some(value) }
-}
-
-fn parse_string_field(line: &str, key: &str) -> Option<String> {
-    let idx = line.find(key)?;
-    let start = idx + key.len();
-    let rest = line[start..].trim_start();
-    let rest = rest.strip_prefix('"')?;
-    let end = rest.find('"')?;
-    Some(unescape_json(&rest[..end]))
-}
-
 fn lerp(target: f32, value: f32, k: f32) -> f32 {
     target + (value - target) * k
 }
 
-fn escape_json(value: &str) -> String {
-    let mut escaped = String::with_capacity(value.len());
-    for ch in value.chars() {
-        match ch {
-            '"' => escaped.push_str("\\\""),
-            '\\' => escaped.push_str("\\\\"),
-            '\n' => escaped.push_str("\\n"),
-            '\r' => escaped.push_str("\\r"),
-            '\t' => escaped.push_str("\\t"),
-            _ => escaped.push(ch),
-        }
-    }
-    escaped
-}
-
-fn unescape_json(value: &str) -> String {
-    let mut result = String::with_capacity(value.len());
-    let mut chars = value.chars();
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(next) = chars.next() {
-                match next {
-                    '"' => result.push('"'),
-                    '\\' => result.push('\\'),
-                    'n' => result.push('\n'),
-                    'r' => result.push('\r'),
-                    't' => result.push('\t'),
-                    other => result.push(other),
-                }
-            }
-        } else {
-            result.push(ch);
-        }
-    }
-    result
-}
-
 #[cfg(test)]
 mod tests {
     use super::lerp;
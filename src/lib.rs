@@ -9,6 +9,7 @@ pub mod device_memory;
 pub mod dialog;
 pub mod emotive;
 pub mod metrics;
+pub mod pipeline;
 pub mod prosody;
 pub mod session;
 pub mod softguard;
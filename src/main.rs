@@ -1,34 +1,13 @@
-mod adaptive_qa;
-mod alerts;
-mod astro;
-mod awareness;
-mod compassion;
-mod config;
-mod device;
-mod device_memory;
-mod dialog;
-mod emotive;
-mod metrics;
-mod prosody;
-mod session;
-mod softguard;
-mod spark;
-mod stabilizer;
-mod sync;
-mod utils;
-mod viz;
-mod voice_io;
-
-use std::time::Instant;
+use liminal_voice_core::{
+    alerts, astro, awareness, compassion, config, device, device_memory, dialog, emotive, metrics,
+    pipeline, session, spark, stabilizer, sync, viz, voice_io,
+};
 
 use alerts::AlertStats;
-use astro::AstroSessionStats;
-use awareness::{MetaCognition, MetaStabilizer};
-use compassion::{CompassionAdjustments, CompassionMetrics};
 use config::VizMode;
-use session::SyncDelta;
-use softguard::{GuardAction, GuardConfig};
-use sync::{Baselines as SyncBaselines, SyncCfg, SyncState};
+use liminal_voice_core::utils::{current_unix_secs, write_atomic};
+use pipeline::Pipeline;
+use sync::Baselines as SyncBaselines;
 
 fn main() {
     let mut cfg = config::from_env_or_args();
@@ -50,33 +29,75 @@ fn main() {
         utterances = padded;
     }
 
-    // let astro_theme = astro::normalize_theme(cfg.script.as_deref(), &utterances);
+    let astro_theme = astro::normalize_theme(cfg.script.as_deref(), &utterances);
 
-    let mode = device::detect(&cfg.mode);
-    cfg.mode = match mode {
-        device::DeviceMode::Phone => "phone".to_string(),
-        device::DeviceMode::Headset => "headset".to_string(),
-        device::DeviceMode::Terminal => "terminal".to_string(),
+    let mode = if cfg.mode.is_empty() {
+        let guessed = device::auto_detect();
+        if cfg.is_verbose() {
+            println!("[device] no --mode given, auto-detected {}", guessed);
+        }
+        guessed
+    } else {
+        device::detect(&cfg.mode)
     };
+    cfg.mode = mode.to_string();
     let mut prof = device::profile(&mode);
-    let mut astro_store = if cfg.astro {
+    (cfg.baseline_drift, cfg.baseline_res) = prof.baselines(cfg.baseline_drift, cfg.baseline_res);
+
+    if let Some(path) = cfg.audio_path.clone() {
+        match voice_io::read_wav(&path) {
+            Ok(info) => {
+                cfg.sample_rate = info.sample_rate;
+                cfg.channels = info.channels;
+                cfg.audio_duration_ms = Some(info.duration_ms());
+                println!(
+                    "[voice] audio {} sr={} ch={} duration={}ms",
+                    path,
+                    info.sample_rate,
+                    info.channels,
+                    info.duration_ms()
+                );
+            }
+            Err(err) => eprintln!("[voice] failed to read audio {}: {}", path, err),
+        }
+    }
+    let astro_store = if cfg.astro {
         Some(astro::AstroStore::load(&cfg.astro_path, cfg.astro_cache))
     } else {
         None
     };
-    let mut astro_session_stats = AstroSessionStats::default();
 
-    let mut astro_seed_res = 0.0;
-    let mut astro_seed_drift = 0.0;
-    if cfg.sync && cfg.astro && !astro_theme.is_empty() {
-        if let Some(store) = astro_store.as_ref() {
-            if let Some(bias) = store.suggest_sync(&astro_theme) {
-                let stability = bias.stability.clamp(0.0, 1.0);
-                let visits_factor = (bias.visits as f32).min(12.0) / 12.0;
-                let weight = 0.4 + 0.6 * ((stability + visits_factor) * 0.5);
-                astro_seed_res = (bias.res_bias * weight).clamp(-0.05, 0.05);
-                astro_seed_drift = ((-bias.drift_bias) * weight).clamp(0.0, 0.05);
-            }
+    let mut emote_seed_opt: Option<emotive::EmoteSeed> = None;
+    let mut emote_seed_display: Option<String> = None;
+    let mut emotive_seed_res = 0.0;
+    let mut emotive_seed_drift = 0.0;
+    if cfg.emote {
+        let seed = if cfg.emote_window > 1 {
+            let recent =
+                emotive::load_recent_for(&cfg.emote_path, &cfg.emote_profile, cfg.emote_window);
+            (!recent.is_empty()).then(|| emotive::blend_seeds(&recent))
+        } else {
+            emotive::load_latest_for(&cfg.emote_path, &cfg.emote_profile)
+        };
+        if let Some(seed) = seed {
+            let mut dec = emotive::decay_with(
+                &seed,
+                current_unix_secs(),
+                cfg.emote_half_life,
+                &cfg.emote_decay,
+            );
+            emotive::apply_boot_bias(&mut dec.ema_res, cfg.emote_warm);
+            println!(
+                "[emote] seed loaded tone={:?} ema_drift={:.2} ema_res={:.2} wpm={:.0}",
+                dec.tone, dec.ema_drift, dec.ema_res, dec.wpm
+            );
+            emote_seed_display = Some(format!(
+                "tone={:?} ema_d={:.2} ema_r={:.2} wpm={:.0}",
+                dec.tone, dec.ema_drift, dec.ema_res, dec.wpm
+            ));
+            emotive_seed_res = (dec.ema_res - cfg.baseline_res).clamp(0.0, 0.05);
+            emotive_seed_drift = (cfg.baseline_drift - dec.ema_drift).clamp(0.0, 0.05);
+            emote_seed_opt = Some(dec);
         }
     }
 
@@ -86,11 +107,31 @@ fn main() {
     } else {
         device_memory::DeviceMemoryStore::default()
     };
+    mem_store.alpha = (cfg.memory_alpha > 0.0).then_some(cfg.memory_alpha);
+    if let (true, Some(days)) = (cfg.memory, cfg.memory_max_age_days) {
+        let pruned = mem_store.prune(current_unix_secs(), days);
+        if pruned > 0 && !cfg.is_quiet() {
+            println!(
+                "[memory] pruned {} entries older than {} days",
+                pruned, days
+            );
+        }
+    }
     let base_pace = prof.pace_factor;
     let base_pause = prof.pause_ms as f32;
     let mut device_seed_pace = 0.0;
     let mut device_seed_pause: i64 = 0;
-    if let Some(memory) = device_memory::suggest_profile(&mem_store, &device_key) {
+    // The emotive seed carries the last session's tone, the best guess at
+    // which tone bucket this session starts in.
+    let memory = match emote_seed_opt.as_ref() {
+        Some(seed) => device_memory::suggest_tone_profile(
+            &mem_store,
+            &device_key,
+            &format!("{:?}", seed.tone),
+        ),
+        None => device_memory::suggest_profile(&mem_store, &device_key),
+    };
+    if let Some(memory) = memory {
         println!(
             "[memory] loaded avg_pace={:.2} pause={:.1} art={:.2}",
             memory.avg_pace, memory.avg_pause, memory.avg_articulation
@@ -101,40 +142,18 @@ fn main() {
         device_seed_pause = pause_bias.clamp(-40, 60);
     }
 
-    let mut emote_seed_opt: Option<emotive::EmoteSeed> = None;
-    let mut emote_seed_display: Option<String> = None;
-    let mut emotive_seed_res = 0.0;
-    let mut emotive_seed_drift = 0.0;
-    if cfg.emote {
-        if let Some(seed) = emotive::load_latest(&cfg.emote_path) {
-            let mut dec = emotive::decay(&seed, current_unix_secs(), cfg.emote_half_life);
-            emotive::apply_boot_bias(&mut dec.ema_res, cfg.emote_warm);
-            println!(
-                "[emote] seed loaded tone={} ema_drift={:.2} ema_res={:.2} wpm={:.0}",
-                dec.tone, dec.ema_drift, dec.ema_res, dec.wpm
-            );
-            emote_seed_display = Some(format!(
-                "tone={} ema_d={:.2} ema_r={:.2} wpm={:.0}",
-                dec.tone, dec.ema_drift, dec.ema_res, dec.wpm
-            ));
-            emotive_seed_res = (dec.ema_res - cfg.baseline_res).max(0.0).min(0.05);
-            emotive_seed_drift = (cfg.baseline_drift - dec.ema_drift).max(0.0).min(0.05);
-            emote_seed_opt = Some(dec);
-        }
-    }
+    let mut pipe = Pipeline::new(&cfg, &prof, utterances.len());
+    pipe.astro_store = astro_store;
 
-    let mut session_handle = if cfg.enable_logging {
-        let mut sess = session::start(cfg.cycles, &cfg.log_dir);
+    if cfg.enable_logging {
+        let mut sess = session::start_from(&cfg);
         match session::open_file(&mut sess) {
-            Ok(()) => Some(sess),
+            Ok(()) => pipe.session = Some(sess),
             Err(err) => {
                 eprintln!("[log] failed to open session log: {}", err);
-                None
             }
         }
-    } else {
-        None
-    };
+    }
 
     // Astro seeds (for now just default to 0.0, could be loaded from astro store)
     let astro_seed_res = 0.0;
@@ -144,12 +163,6 @@ fn main() {
         drift: cfg.baseline_drift,
         res: cfg.baseline_res,
     };
-    let sync_cfg = SyncCfg {
-        lr_fast: cfg.sync_lr_fast,
-        lr_slow: cfg.sync_lr_slow,
-        clamp_step: cfg.sync_step,
-    };
-    let mut sync_state = SyncState::default();
     if cfg.sync {
         let seeds = sync::merge_seeds(
             emotive_seed_res,
@@ -159,473 +172,200 @@ fn main() {
             astro_seed_res,
             astro_seed_drift,
         );
-        sync_state.warm_start(seeds, sync_baselines);
+        let seeds = match cfg.sync_seed_path.as_deref().and_then(sync::load_seeds) {
+            Some(saved) => {
+                let blended = seeds.blend(&saved, 0.5);
+                println!(
+                    "[sync] seeds loaded pace_bias={:+.3} pause_bias={}ms res_warm={:+.3} drift_soft={:+.3}",
+                    blended.pace_bias, blended.pause_bias_ms, blended.res_warm, blended.drift_soft
+                );
+                blended
+            }
+            None => seeds,
+        };
+        pipe.sync_state.warm_start(seeds, sync_baselines);
     }
 
-    let mut drift_history = Vec::with_capacity(cfg.cycles);
-    let mut resonance_history = Vec::with_capacity(cfg.cycles);
-    let mut last_snapshot: Option<session::Snapshot> = None;
-    let mut alert_stats = if cfg.alarm {
-        Some(AlertStats::default())
-    } else {
-        None
-    };
-
-    let guard_cfg = GuardConfig {
-        drift_limit: cfg.guard_drift,
-        res_limit: cfg.guard_res,
-        rephrase_factor: cfg.guard_factor,
-    };
-
-    let mut stabilizer = if cfg.stabilizer {
-        Some(stabilizer::Stabilizer::new(stabilizer::StabilizerCfg {
-            win: cfg.stab_win,
-            ema_alpha: cfg.stab_alpha,
-            warm_drift: cfg.stab_warm,
-            hot_drift: cfg.stab_hot,
-            low_res: cfg.stab_low_res,
-            cool_steps: cfg.stab_cool,
-            calm_boost: cfg.stab_calm,
-        }))
+    let stab_snapshot = if cfg.stabilizer {
+        cfg.stab_state_path
+            .as_deref()
+            .and_then(stabilizer::load_snapshot)
     } else {
         None
     };
+    if let (Some(stab), Some(snap)) = (pipe.stabilizer.as_mut(), stab_snapshot.as_ref()) {
+        println!(
+            "[stabilizer] resumed state={:?} ema_drift={:.2} ema_res={:.2}",
+            snap.state, snap.ema_drift, snap.ema_res
+        );
+        *stab = stabilizer::Stabilizer::restore(pipeline::stabilizer_cfg(&cfg), snap);
+        stab.advice_table = cfg.stab_advice;
+    }
 
     // Meta-cognition layer
-    let mut meta_cognition = if cfg.awareness {
-        Some(MetaCognition::new())
-    } else {
-        None
-    };
-
-    let mut meta_stabilizer = if cfg.awareness {
-        Some(MetaStabilizer::new(cfg.meta_stab_alpha))
-    } else {
-        None
-    };
+    if let (Some(meta), Some(state)) = (
+        pipe.meta_cognition.as_mut(),
+        cfg.awareness_path
+            .as_deref()
+            .and_then(awareness::load_state),
+    ) {
+        meta.warm_start(&state);
+        println!(
+            "[meta] state loaded observations={} conf={:.2} clarity={:.2}",
+            state.observation_count, state.confidence, state.clarity
+        );
+    }
 
     // Compassion layer
-    let mut compassion_metrics = if cfg.compassion {
-        Some(CompassionMetrics::new())
-    } else {
-        None
-    };
-
-    let mut last_articulation: Option<f32> = None;
-    let mut last_drift: Option<f32> = None;
-    let mut last_res: Option<f32> = None;
-    let mut last_tone: Option<prosody::ToneTag> = None;
-    let mut last_wpm: Option<f32> = None;
-    let mut seed_bias_applied = false;
-
-    if let (Some(stab), Some(seed)) = (stabilizer.as_mut(), emote_seed_opt.as_ref()) {
-        stab.push(seed.ema_drift, seed.ema_res);
+    if let (Some(comp), Some(seed)) = (
+        pipe.compassion_metrics.as_mut(),
+        cfg.compassion_path
+            .as_deref()
+            .and_then(compassion::load_seed),
+    ) {
+        let seed = compassion::decay_seed(&seed, current_unix_secs(), cfg.emote_half_life);
+        comp.warm_start(&seed);
+        println!(
+            "[compassion] seed loaded level={:.2}",
+            seed.compassion_level
+        );
     }
 
-    for (idx, utterance) in utterances.iter().enumerate() {
-        let mut vm = metrics::start();
-
-        let asr_start = Instant::now();
-        let text = voice_io::transcribe_audio_like(&cfg, &prof, utterance);
-        vm.asr_ms = asr_start.elapsed().as_millis();
-
-        let prosody = prosody::analyze(&text, prof.pace_factor, prof.pause_ms);
-        let (mut drift, mut res) = adaptive_qa::analyze_prompt(&text);
-        (drift, res) = adaptive_qa::apply_prosody_bias(drift, res, &prosody.tone);
-        drift = metrics::clamp01(drift);
-        res = metrics::clamp01(res);
-        let measured_drift = drift;
-        let measured_res = res;
-
-        let mut astro_advice: Option<astro::AstroAdvice> = None;
-        let mut astro_key: Option<String> = None;
-        let mut astro_recall_ts: Option<i64> = None;
-        if cfg.astro {
-            astro_key = Some(astro::topic_key(&text, prosody.tone));
-        }
-        if let (Some(store), Some(ref key)) = (astro_store.as_mut(), astro_key.as_ref()) {
-            let now_ts = current_unix_secs();
-            if let Some(mut advice) = store.recall(key, now_ts) {
-                if let Some(seed) = emote_seed_opt.as_ref() {
-                    if idx < 2
-                        && seed
-                            .tone
-                            .eq_ignore_ascii_case(&format!("{:?}", prosody.tone))
-                    {
-                        let extra = 0.02 + (advice.res_bias.abs().min(0.06) * 0.5);
-                        advice.res_bias += extra;
-                        advice.drift_bias -= extra * 0.6;
-                    }
-                }
-                drift = metrics::clamp01(drift + advice.drift_bias);
-                res = metrics::clamp01(res + advice.res_bias);
-                astro_session_stats.hits = astro_session_stats.hits.saturating_add(1);
-                astro_session_stats.boost_res += advice.res_bias;
-                astro_session_stats.bias_drift += advice.drift_bias;
-                astro_recall_ts = Some(now_ts);
-                astro_advice = Some(advice);
-            }
-        }
-
-        let emo_flag = matches!(prosody.tone, prosody::ToneTag::Energetic)
-            && (measured_drift > cfg.baseline_drift || measured_res > 0.75);
-
-        let mut articulation = prosody.articulation;
-        let mut effective_pace = prof.pace_factor;
-        let mut effective_pause_ms = prof.pause_ms as i64;
-        let mut stab_state_label: Option<String> = None;
-        let mut current_state = stabilizer::EmoState::Normal;
-
-        if !seed_bias_applied {
-            if let Some(seed) = emote_seed_opt.as_ref() {
-                let pace_bias = (seed.wpm / 160.0).clamp(0.8, 1.2);
-                effective_pace = (effective_pace * pace_bias).clamp(0.7, 1.3);
-            }
-            if cfg.sync {
-                effective_pace = (effective_pace + sync_state.seeds.pace_bias).clamp(0.7, 1.3);
-                effective_pause_ms =
-                    (effective_pause_ms + sync_state.seeds.pause_bias_ms).clamp(20, 250);
-                res = metrics::clamp01(res + sync_state.seeds.res_warm);
-                drift = metrics::clamp01(drift - sync_state.seeds.drift_soft);
-            }
-            seed_bias_applied = true;
-        }
-
-        if let Some(stab) = stabilizer.as_mut() {
-            stab.push(drift, res);
-            let advice = stab.advice();
-            effective_pace = (prof.pace_factor + advice.pace_delta).clamp(0.7, 1.3);
-            effective_pause_ms = (prof.pause_ms as i64 + advice.pause_delta_ms).clamp(20, 250);
-            articulation =
-                prosody::apply_articulation_hint(prosody.articulation, advice.articulation_hint);
-            println!(
-                "{}",
-                stabilizer::format_status(stab.state, stab.ema_drift, stab.ema_res)
-            );
-            if let VizMode::Compact = cfg.viz_mode {
-                viz::print_compact_stabilizer(stab.state, stab.ema_drift, stab.ema_res);
-            }
-            stab_state_label = Some(format!("{:?}", stab.state));
-            current_state = stab.state;
-        }
-
-        if let Some(mut advice) = astro_advice {
-            if let Some(stab) = stabilizer.as_ref() {
-                if matches!(stab.state, stabilizer::EmoState::Overheat) {
-                    advice.pace_delta -= 0.02;
-                    advice.pause_delta_ms += 15;
-                }
-            }
-            effective_pace = (effective_pace + advice.pace_delta).clamp(0.7, 1.3);
-            effective_pause_ms = (effective_pause_ms + advice.pause_delta_ms).clamp(20, 250);
-        }
-
-        let mut sync_delta: Option<SyncDelta> = None;
-        if cfg.sync {
-            let (pace_delta, pause_delta_ms, res_boost, drift_relief) =
-                sync_state.step(drift, res, current_state, &sync_cfg);
-            effective_pace += pace_delta;
-            effective_pause_ms += pause_delta_ms;
-            res = metrics::clamp01(res + res_boost);
-            drift = metrics::clamp01(drift - drift_relief);
-            sync_delta = Some(SyncDelta {
-                pace_delta,
-                pause_delta_ms,
-                res_boost,
-                drift_relief,
-            });
-        }
-
-        // Meta-cognition observation
-        if let Some(ref mut meta) = meta_cognition {
-            let sync_correction = if let Some(ref delta) = sync_delta {
-                delta.pace_delta.abs() + (delta.pause_delta_ms as f32 / 100.0)
-            } else {
-                0.0
-            };
-
-            let stab_state_str = stab_state_label.as_deref().unwrap_or("None");
-            meta.observe(measured_drift, measured_res, stab_state_str, sync_correction);
-
-            // Update meta-stabilizer
-            if let Some(ref mut meta_stab) = meta_stabilizer {
-                meta_stab.update(meta);
-            }
-
-            // Log meta-cognition state
-            if cfg.meta_viz {
-                println!("[meta] {}", meta.self_assess());
-
-                if meta.should_express_doubt() {
-                    println!("[meta] ⚠️  System is uncertain about measurements");
-                }
-            }
-        }
-
-        // Compassion detection and response
-        if let Some(ref mut comp) = compassion_metrics {
-            // Check if theme is repeated (from astro)
-            let repeated_theme = if let Some(ref key) = astro_key {
-                // Simple heuristic: if we've seen this theme before (would need astro store method)
-                false // TODO: implement has_trace() in astro
-            } else {
-                false
-            };
-
-            let stab_state_str = stab_state_label.as_deref().unwrap_or("Normal");
-            comp.detect_suffering(
-                measured_drift,
-                measured_res,
-                prosody.tone,
-                prosody.wpm,
-                stab_state_str,
-                repeated_theme,
-            );
-
-            // Calculate kindness based on actions taken
-            // Note: guard checking happens later, so we check previous cycle for now
-            let was_rephrased = false;
-            let pace_delta = if let Some(ref delta) = sync_delta {
-                delta.pace_delta
-            } else {
-                0.0
-            };
-            let pause_delta = if let Some(ref delta) = sync_delta {
-                delta.pause_delta_ms
-            } else {
-                0
-            };
-            let res_boost = if let Some(ref delta) = sync_delta {
-                delta.res_boost
-            } else {
-                0.0
-            };
-
-            comp.calculate_kindness(was_rephrased, pace_delta, pause_delta, res_boost);
-            comp.update_compassion_level();
-
-            // Apply compassion adjustments if activated
-            if comp.should_activate_compassion() {
-                let adj = CompassionAdjustments::from_compassion(comp);
-
-                // Apply adjustments
-                res = metrics::clamp01(res + adj.resonance_boost);
-                drift = metrics::clamp01(drift - adj.drift_reduction);
-                effective_pace = (effective_pace + adj.pace_adjustment).clamp(0.7, 1.3);
-                effective_pause_ms = (effective_pause_ms + adj.pause_adjustment_ms).clamp(20, 250);
-            }
-
-            // Log compassion state
-            if cfg.compassion_viz {
-                println!("[compassion] {}", comp.status_message());
-
-                if comp.should_offer_support() {
-                    println!("[compassion] 💝 Offering support to user");
-                }
-            }
-        }
-
-        if let Some(mut advice) = astro_advice {
-            if let Some(stab) = stabilizer.as_ref() {
-                if matches!(stab.state, stabilizer::EmoState::Overheat) {
-                    advice.pace_delta -= 0.02;
-                    advice.pause_delta_ms += 15;
-                }
-            }
-            effective_pace = (effective_pace + advice.pace_delta).clamp(0.7, 1.3);
-            effective_pause_ms = (effective_pause_ms + advice.pause_delta_ms).clamp(20, 250);
-        }
-
-        let effective_pause_ms = effective_pause_ms.clamp(20, 250);
-        let effective_pause_u64 = effective_pause_ms as u64;
-        effective_pace = effective_pace.clamp(0.7, 1.3);
-
-        let mut guard_flag = None;
-        if cfg.guard {
-            match softguard::check_and_rephrase(&text, drift, res, &guard_cfg) {
-                GuardAction::None => {}
-                GuardAction::Warn(msg) => {
-                    println!("{}", msg);
-                    guard_flag = Some("warn".to_string());
-                }
-                GuardAction::Rephrased(new_text) => {
-                    println!("[voice-core] {}", new_text);
-                    if cfg.stabilizer {
-                        voice_io::synthesize_with(
-                            &cfg,
-                            &prof,
-                            effective_pace,
-                            effective_pause_u64,
-                            &new_text,
-                        );
-                    } else {
-                        voice_io::synthesize_response(&cfg, &prof, &new_text);
-                    }
-                    guard_flag = Some("rephrased".to_string());
-                }
-            }
-        }
-
-        let tts_start = Instant::now();
-        if cfg.stabilizer {
-            voice_io::synthesize_with(
-                &cfg,
-                &prof,
-                effective_pace,
-                effective_pause_u64,
-                &format!("Semantic Drift: {:.2}, Resonance: {:.2}", drift, res),
-            );
-        } else {
-            voice_io::synthesize_response(
-                &cfg,
-                &prof,
-                &format!("Semantic Drift: {:.2}, Resonance: {:.2}", drift, res),
-            );
-        }
-        vm.tts_ms = tts_start.elapsed().as_millis();
-
-        metrics::finish(&mut vm);
-
-        if cfg.enable_metrics {
-            metrics::print(&vm);
-        }
-
-        drift_history.push(drift);
-        resonance_history.push(res);
-
-        let snapshot = session::Snapshot {
-            ts: now_rfc3339(),
-            device: cfg.mode.clone(),
-            drift,
-            resonance: res,
-            wpm: prosody.wpm,
-            articulation,
-            tone: format!("{:?}", prosody.tone),
-            asr_ms: vm.asr_ms,
-            tts_ms: vm.tts_ms,
-            total_ms: vm.total_ms,
-            idx,
-            utterance: text.clone(),
-            guard: guard_flag.clone(),
-            state: stab_state_label.clone(),
-            emote_state: if idx + 1 == utterances.len() {
-                Some(format!("{:?}", prosody.tone))
-            } else {
-                None
-            },
-            sync: if idx + 1 == utterances.len() {
-                sync_delta
-            } else {
-                None
-            },
-            meta_self_drift: meta_cognition.as_ref().map(|m| m.self_drift),
-            meta_self_resonance: meta_cognition.as_ref().map(|m| m.self_resonance),
-            meta_confidence: meta_cognition.as_ref().map(|m| m.confidence),
-            meta_clarity: meta_cognition.as_ref().map(|m| m.clarity),
-            meta_doubt: meta_cognition.as_ref().map(|m| m.doubt),
-            compassion_suffering: compassion_metrics.as_ref().map(|c| c.user_suffering),
-            compassion_type: compassion_metrics.as_ref().map(|c| format!("{:?}", c.suffering_type)),
-            compassion_kindness: compassion_metrics.as_ref().map(|c| c.response_kindness),
-            compassion_healing: compassion_metrics.as_ref().map(|c| c.healing_intent),
-            compassion_level: compassion_metrics.as_ref().map(|c| c.compassion_level),
-        };
-
-        last_articulation = Some(articulation);
-        last_drift = Some(drift);
-        last_res = Some(res);
-        last_tone = Some(prosody.tone);
-        last_wpm = Some(prosody.wpm);
-
-        if let Some(sess) = session_handle.as_mut() {
-            if let Err(err) = session::write(sess, &snapshot) {
-                eprintln!("[log] failed to write snapshot: {}", err);
-            }
-        }
-
-        if let (Some(store), Some(ref key)) = (astro_store.as_mut(), astro_key.as_ref()) {
-            let ts = astro_recall_ts.unwrap_or_else(|| current_unix_secs());
-            store.consolidate(key, measured_drift, measured_res, emo_flag, ts);
+    if let (Some(stab), Some(seed)) = (pipe.stabilizer.as_mut(), emote_seed_opt.as_ref()) {
+        // A resumed stabilizer already carries its own warm state.
+        if stab_snapshot.is_none() {
+            stab.push(seed.ema_drift, seed.ema_res);
         }
+    }
+    pipe.emote_seed = emote_seed_opt;
 
-        last_snapshot = Some(snapshot);
+    pipe.run(&utterances);
 
-        if let Some(stats) = alert_stats.as_mut() {
-            alerts::update(stats, drift, res, cfg.baseline_drift, cfg.baseline_res);
-        }
+    if cfg.sync && cfg.is_verbose() {
+        let recent: Vec<String> = pipe
+            .sync_state
+            .recent_residuals(5)
+            .iter()
+            .map(|r| format!("({:+.3},{:+.3})", r.d_drift, r.d_res))
+            .collect();
+        println!(
+            "[sync] recent residuals (d_drift,d_res): {}",
+            recent.join(" ")
+        );
     }
 
     let (astro_delta_drift, astro_delta_res) = if cfg.sync {
-        sync_state.to_slow_increments(&sync_cfg)
+        pipe.sync_state.to_slow_increments(&pipe.sync_cfg)
     } else {
         (0.0, 0.0)
     };
 
+    if let (true, Some(path)) = (cfg.sync, cfg.sync_seed_path.as_deref())
+        && let Err(err) = sync::save_seeds(path, &pipe.sync_state.updated_seeds(&pipe.sync_cfg))
+    {
+        eprintln!("[sync] failed to save seeds: {}", err);
+    }
+
     if cfg.sync && cfg.astro && !astro_theme.is_empty() {
-        if let Some(store) = astro_store.as_mut() {
+        if let Some(store) = pipe.astro_store.as_mut() {
             let now_ts = current_unix_secs();
             store.fold_sync_delta(&astro_theme, astro_delta_drift, astro_delta_res, now_ts);
         }
-        astro_session_stats.bias_drift += astro_delta_drift;
-        astro_session_stats.boost_res += astro_delta_res;
+        pipe.astro_stats.bias_drift += astro_delta_drift;
+        pipe.astro_stats.boost_res += astro_delta_res;
     }
 
-    println!("[viz] resonance  {}", spark::sparkline(&resonance_history));
-    println!("[viz] drift      {}", spark::sparkline(&drift_history));
+    println!(
+        "[viz] resonance  {}",
+        spark::sparkline_scaled_for(&pipe.resonance_history, cfg.ascii)
+    );
+    println!(
+        "[viz] drift      {}",
+        spark::sparkline_scaled_for(&pipe.drift_history, cfg.ascii)
+    );
+    if !pipe.drift_history.is_empty() {
+        println!(
+            "[viz] drift map  |{}|",
+            viz::heatmap_for(&pipe.drift_history, viz::HEATMAP_WIDTH, cfg.ascii)
+        );
+    }
+    if let (true, Some(comp)) = (cfg.compassion_viz, pipe.compassion_metrics.as_ref()) {
+        println!(
+            "[viz] kindness   {}",
+            spark::sparkline_for(&comp.kindness_history, cfg.ascii)
+        );
+    }
 
     if cfg.astro {
         println!(
             "[astro] hits={} boost_res={:.3} bias_drift={:.3}",
-            astro_session_stats.hits, astro_session_stats.boost_res, astro_session_stats.bias_drift
+            pipe.astro_stats.hits, pipe.astro_stats.boost_res, pipe.astro_stats.bias_drift
         );
     }
 
-    if let VizMode::Full = cfg.viz_mode {
-        if let Some(ref snap) = last_snapshot {
-            let stab_detail = stabilizer.as_ref().map(|stab| {
-                format!(
-                    "{:?} (EMA d={:.2} r={:.2})",
-                    stab.state, stab.ema_drift, stab.ema_res
-                )
-            });
-            viz::print_table(
-                snap.drift,
-                snap.resonance,
-                snap.wpm,
-                snap.articulation,
-                &snap.tone,
-                snap.asr_ms,
-                snap.tts_ms,
-                snap.total_ms,
-                stab_detail.as_deref(),
-                emote_seed_display.as_deref(),
-                meta_cognition.as_ref(),
-                compassion_metrics.as_ref(),
-            );
+    if let (VizMode::Full | VizMode::Json, Some(snap)) = (cfg.viz_mode, pipe.last_snapshot.as_ref())
+    {
+        let viz_cfg = viz::VizCfg {
+            trend_deadband: cfg.trend_deadband,
+            color: cfg.color.enabled(),
+            ascii: cfg.ascii,
+            ..cfg
+                .viz_width
+                .or_else(viz::terminal_width)
+                .map_or_else(viz::VizCfg::default, viz::VizCfg::for_width)
+        };
+        let stab_detail = pipe.stabilizer.as_ref().map(|stab| {
+            format!(
+                "{:?} (EMA d={:.2} r={:.2})",
+                stab.state, stab.ema_drift, stab.ema_res
+            )
+        });
+        let table = viz::TableData {
+            drift: snap.drift,
+            res: snap.resonance,
+            wpm: snap.wpm,
+            articulation: snap.articulation,
+            filler_density: pipe.last_filler_density,
+            tone: &snap.tone,
+            asr_ms: snap.asr_ms,
+            tts_ms: snap.tts_ms,
+            total_ms: snap.total_ms,
+            stab_state: stab_detail.as_deref(),
+            emote_seed: emote_seed_display.as_deref(),
+            meta_cognition: pipe.meta_cognition.as_ref(),
+            compassion: pipe.compassion_metrics.as_ref(),
+            drift_prev: pipe.drift_history.iter().rev().nth(1).copied(),
+            res_prev: pipe.resonance_history.iter().rev().nth(1).copied(),
+        };
+        if cfg.viz_mode == VizMode::Json {
+            println!("{}", viz::table_json(&table));
+        } else {
+            viz::print_table(&table, &viz_cfg);
         }
     }
 
     if cfg.emote {
-        if let (Some(last_wpm), Some(last_tone)) = (last_wpm, last_tone) {
-            let (ema_drift, ema_res) = if let Some(stab) = stabilizer.as_ref() {
+        if let (Some(last_wpm), Some(last_tone)) = (pipe.last_wpm, pipe.last_tone) {
+            let (ema_drift, ema_res) = if let Some(stab) = pipe.stabilizer.as_ref() {
                 (stab.ema_drift, stab.ema_res)
             } else {
                 (
-                    last_drift.unwrap_or(cfg.baseline_drift),
-                    last_res.unwrap_or(cfg.baseline_res),
+                    pipe.last_drift.unwrap_or(cfg.baseline_drift),
+                    pipe.last_res.unwrap_or(cfg.baseline_res),
                 )
             };
-            let final_tone = format!("{:?}", last_tone);
             let seed = emotive::EmoteSeed {
                 ema_drift,
                 ema_res,
-                tone: final_tone.clone(),
+                tone: last_tone,
                 wpm: last_wpm,
                 ts_unix: current_unix_secs(),
+                profile: cfg.emote_profile.clone(),
             };
             match emotive::save_append(&cfg.emote_path, &seed) {
                 Ok(()) => {
                     println!(
-                        "[emote] saved tone={} ema_drift={:.2} ema_res={:.2} wpm={:.0}",
+                        "[emote] saved tone={:?} ema_drift={:.2} ema_res={:.2} wpm={:.0}",
                         seed.tone, seed.ema_drift, seed.ema_res, seed.wpm
                     );
                 }
@@ -634,88 +374,98 @@ fn main() {
                 }
             }
         }
-    }
-
-    if cfg.memory {
-        if let (Some(art), Some(drift), Some(res)) = (last_articulation, last_drift, last_res) {
-            mem_store.update(
-                &device_key,
-                prof.pace_factor,
-                prof.pause_ms as f32,
-                art,
-                drift,
-                res,
-            );
-            mem_store.save();
-            println!("[memory] saved updated profile for {:?}", mode);
+        if let Some(keep) = cfg.emote_keep {
+            match emotive::prune(&cfg.emote_path, keep) {
+                Ok(dropped) if dropped > 0 && cfg.is_verbose() => {
+                    println!("[emote] pruned {} old seeds, kept {}", dropped, keep);
+                }
+                Ok(_) => {}
+                Err(err) => eprintln!("[emote] failed to prune seeds: {}", err),
+            }
         }
     }
 
-    let mut strict_exit = false;
-    if let Some(ref stats) = alert_stats {
-        alerts::print_summary(stats, cfg.baseline_drift, cfg.baseline_res);
-        strict_exit = cfg.strict && (stats.drift_breaches > 0 || stats.res_breaches > 0);
+    if let (Some(meta), Some(path)) = (pipe.meta_cognition.as_ref(), cfg.awareness_path.as_deref())
+        && let Err(err) = awareness::save_state(path, meta)
+    {
+        eprintln!("[meta] failed to save state: {}", err);
     }
 
-    if let Some(sess) = session_handle.take() {
-        session::close(sess);
+    if let (Some(comp), Some(path)) = (
+        pipe.compassion_metrics.as_ref(),
+        cfg.compassion_path.as_deref(),
+    ) && let Err(err) = compassion::save_seed(path, comp)
+    {
+        eprintln!("[compassion] failed to save seed: {}", err);
     }
 
-    if strict_exit {
-        std::process::exit(2);
+    if let (Some(stab), Some(path)) = (pipe.stabilizer.as_ref(), cfg.stab_state_path.as_deref())
+        && let Err(err) = stabilizer::save_snapshot(path, &stab.snapshot())
+    {
+        eprintln!("[stabilizer] failed to save state: {}", err);
     }
-}
 
-fn current_unix_secs() -> i64 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
-}
-
-fn now_rfc3339() -> String {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    let now = SystemTime::now();
-    let duration = now.duration_since(UNIX_EPOCH).unwrap_or_default();
-    format_rfc3339(duration.as_secs(), duration.subsec_nanos())
-}
-
-fn format_rfc3339(seconds: u64, nanos: u32) -> String {
-    const SECONDS_PER_DAY: u64 = 86_400;
-
-    let days = (seconds / SECONDS_PER_DAY) as i64;
-    let secs_of_day = (seconds % SECONDS_PER_DAY) as u32;
-
-    let (year, month, day) = civil_from_days(days);
-
-    let hour = secs_of_day / 3_600;
-    let minute = (secs_of_day % 3_600) / 60;
-    let second = secs_of_day % 60;
-    let millis = nanos / 1_000_000;
+    if cfg.memory
+        && let (Some(art), Some(drift), Some(res), Some(tone)) = (
+            pipe.last_articulation,
+            pipe.last_drift,
+            pipe.last_res,
+            pipe.last_tone,
+        )
+    {
+        let sample = device_memory::DeviceMemory {
+            avg_pace: prof.pace_factor,
+            avg_pause: prof.pause_ms as f32,
+            avg_articulation: art,
+            avg_drift: drift,
+            avg_res: res,
+            ..Default::default()
+        };
+        mem_store.update_tone(&device_key, &format!("{:?}", tone), &sample);
+        mem_store.save();
+        println!("[memory] saved updated profile for {:?}", mode);
+    }
 
-    format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
-        year, month, day, hour, minute, second, millis
-    )
-}
+    let mut strict_exit = false;
+    if let Some(ref stats) = pipe.alert_stats {
+        alerts::print_summary(stats, cfg.baseline_drift, cfg.baseline_res, cfg.ascii);
+        strict_exit = cfg.strict && stats.longest_streak() > cfg.strict_streak;
+    }
+    if cfg.guard {
+        println!("{}", pipe.guard_stats.summary_line());
+    }
+    if cfg.enable_metrics && !cfg.is_quiet() {
+        if let Some(line) = pipe.latency.summary_line() {
+            println!("{}", line);
+        }
+        if let Some(line) = pipe.latency.stage_line() {
+            println!("{}", line);
+        }
+        if cfg.is_verbose() && pipe.latency_hist.total() > 0 {
+            println!("[metrics] total latency histogram:");
+            print!("{}", pipe.latency_hist.render());
+        }
+    }
+    if let Some(path) = cfg.metrics_out.as_ref() {
+        let empty = AlertStats::default();
+        let text =
+            metrics::prometheus_text(&pipe.latency, pipe.alert_stats.as_ref().unwrap_or(&empty));
+        if let Err(err) = write_atomic(path, &text) {
+            eprintln!("[metrics] failed to write {}: {}", path, err);
+        }
+    }
 
-fn civil_from_days(days: i64) -> (i32, u32, u32) {
-    let z = days + 719_468;
-    let era = z.div_euclid(146_097);
-    let doe = z - era * 146_097;
-    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
-    let mut year = (yoe + era * 400) as i32;
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
-    let mp = (5 * doy + 2) / 153;
-    let day = doy - (153 * mp + 2) / 5 + 1;
-    let mut month = mp + if mp < 10 { 3 } else { -9 };
-    year += if month <= 2 { 1 } else { 0 };
-    if month <= 0 {
-        month += 12;
+    if let Some(sess) = pipe.session.take() {
+        let summary = session::summarize(&pipe.session_snapshots);
+        if let Err(err) = session::write_summary(&sess, &summary) {
+            eprintln!("[log] failed to write session summary: {}", err);
+        }
+        if let Err(err) = session::close(sess) {
+            eprintln!("[log] failed to flush session log: {}", err);
+        }
     }
 
-    (year, month as u32, day as u32)
+    if strict_exit {
+        std::process::exit(2);
+    }
 }
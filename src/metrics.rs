@@ -1,5 +1,9 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
 use std::time::Instant;
 
+use crate::alerts::AlertStats;
+
 #[derive(Debug)]
 pub struct VoiceMetrics {
     pub start_ts: Instant,
@@ -17,8 +21,10 @@ pub fn start() -> VoiceMetrics {
     }
 }
 
+/// Never reports less than the stages add up to, which matters when their
+/// latency was simulated rather than waited out (`--no-latency`).
 pub fn finish(vm: &mut VoiceMetrics) {
-    vm.total_ms = vm.start_ts.elapsed().as_millis();
+    vm.total_ms = vm.start_ts.elapsed().as_millis().max(vm.asr_ms + vm.tts_ms);
 }
 
 pub fn print(vm: &VoiceMetrics) {
@@ -28,12 +34,314 @@ pub fn print(vm: &VoiceMetrics) {
     );
 }
 
-pub fn clamp01(v: f32) -> f32 {
-    if v < 0.0 {
-        0.0
-    } else if v > 1.0 {
-        1.0
-    } else {
-        v
+/// Like `print`, with the rolling mean and jitter appended.
+pub fn print_with_rolling(vm: &VoiceMetrics, rolling: &RollingMetrics) {
+    println!(
+        "[metrics] asr={}ms tts={}ms total={}ms rolling_mean={:.1}ms jitter={:.1}ms (n={})",
+        vm.asr_ms,
+        vm.tts_ms,
+        vm.total_ms,
+        rolling.mean().unwrap_or(0.0),
+        rolling.jitter().unwrap_or(0.0),
+        rolling.len()
+    );
+}
+
+/// Latencies recorded for one stage across a run.
+#[derive(Debug, Default, Clone)]
+pub struct LatencySamples {
+    samples: Vec<u128>,
+}
+
+impl LatencySamples {
+    pub fn push(&mut self, ms: u128) {
+        self.samples.push(ms);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Linearly interpolated percentile, `p` in 0..=100.
+    pub fn percentile(&self, p: f32) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f32;
+        let lo = rank.floor() as usize;
+        let hi = rank.ceil() as usize;
+        let frac = rank - lo as f32;
+        Some(sorted[lo] as f32 + (sorted[hi] as f32 - sorted[lo] as f32) * frac)
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<u128>() as f32 / self.samples.len() as f32)
+    }
+}
+
+/// Collects every cycle's `VoiceMetrics` so a run can report percentiles.
+#[derive(Debug, Default, Clone)]
+pub struct LatencyAggregator {
+    pub asr: LatencySamples,
+    pub tts: LatencySamples,
+    pub total: LatencySamples,
+}
+
+impl LatencyAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, vm: &VoiceMetrics) {
+        self.asr.push(vm.asr_ms);
+        self.tts.push(vm.tts_ms);
+        self.total.push(vm.total_ms);
+    }
+
+    pub fn summary_line(&self) -> Option<String> {
+        let fmt = |samples: &LatencySamples| {
+            Some(format!(
+                "{:.0}ms/{:.0}ms",
+                samples.percentile(50.0)?,
+                samples.percentile(95.0)?
+            ))
+        };
+        Some(format!(
+            "[metrics] p50/p95 total={} asr={} tts={} (n={})",
+            fmt(&self.total)?,
+            fmt(&self.asr)?,
+            fmt(&self.tts)?,
+            self.total.len()
+        ))
+    }
+
+    /// Mean ASR time over mean TTS time: above 1 means recognition
+    /// dominates the run, below 1 means synthesis does.
+    pub fn asr_tts_ratio(&self) -> Option<f32> {
+        let tts = self.tts.mean()?;
+        (tts > 0.0).then(|| self.asr.mean().unwrap_or(0.0) / tts)
+    }
+
+    pub fn stage_line(&self) -> Option<String> {
+        let ratio = self
+            .asr_tts_ratio()
+            .map_or("n/a".to_string(), |r| format!("{:.2}", r));
+        Some(format!(
+            "[metrics] asr_mean={:.1}ms tts_mean={:.1}ms ratio={}",
+            self.asr.mean()?,
+            self.tts.mean()?,
+            ratio
+        ))
+    }
+}
+
+/// Total latency over the last `window` cycles.
+#[derive(Debug, Clone)]
+pub struct RollingMetrics {
+    pub window: usize,
+    totals: VecDeque<u128>,
+}
+
+impl RollingMetrics {
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            totals: VecDeque::with_capacity(window),
+        }
+    }
+
+    pub fn record(&mut self, vm: &VoiceMetrics) {
+        if self.totals.len() == self.window {
+            self.totals.pop_front();
+        }
+        self.totals.push_back(vm.total_ms);
+    }
+
+    pub fn len(&self) -> usize {
+        self.totals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        if self.totals.is_empty() {
+            return None;
+        }
+        Some(self.totals.iter().sum::<u128>() as f32 / self.totals.len() as f32)
+    }
+
+    pub fn min(&self) -> Option<u128> {
+        self.totals.iter().copied().min()
+    }
+
+    pub fn max(&self) -> Option<u128> {
+        self.totals.iter().copied().max()
+    }
+
+    /// Population standard deviation of the windowed totals.
+    pub fn jitter(&self) -> Option<f32> {
+        let mean = self.mean()?;
+        let var = self
+            .totals
+            .iter()
+            .map(|&t| (t as f32 - mean).powi(2))
+            .sum::<f32>()
+            / self.totals.len() as f32;
+        Some(var.sqrt())
+    }
+}
+
+pub const DEFAULT_LATENCY_BUCKETS: [u128; 5] = [10, 25, 50, 100, 250];
+
+const HISTOGRAM_BAR_WIDTH: usize = 20;
+
+/// Counts of latencies per bucket. Each bucket holds samples up to and
+/// including its bound; a final overflow bucket takes everything above the
+/// last bound.
+#[derive(Debug, Clone)]
+pub struct LatencyHistogram {
+    bounds: Vec<u128>,
+    counts: Vec<usize>,
+}
+
+impl LatencyHistogram {
+    pub fn new(buckets: &[u128]) -> Self {
+        let mut bounds = buckets.to_vec();
+        bounds.sort_unstable();
+        bounds.dedup();
+        let counts = vec![0; bounds.len() + 1];
+        Self { bounds, counts }
+    }
+
+    pub fn record(&mut self, ms: u128) {
+        let idx = self
+            .bounds
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[idx] += 1;
+    }
+
+    pub fn bounds(&self) -> &[u128] {
+        &self.bounds
     }
+
+    /// One count per bound, plus the overflow bucket last.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// One line per bucket, bars scaled to the fullest bucket.
+    pub fn render(&self) -> String {
+        let peak = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let labels: Vec<String> = self
+            .bounds
+            .iter()
+            .map(|b| format!("<={}ms", b))
+            .chain(
+                self.bounds
+                    .last()
+                    .map(|b| format!(">{}ms", b))
+                    .or_else(|| Some("all".to_string())),
+            )
+            .collect();
+        let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for (label, &count) in labels.iter().zip(&self.counts) {
+            let bar = (count * HISTOGRAM_BAR_WIDTH).div_ceil(peak);
+            let _ = writeln!(
+                out,
+                "{:>lw$} |{:<bw$}| {}",
+                label,
+                "#".repeat(bar),
+                count,
+                lw = label_width,
+                bw = HISTOGRAM_BAR_WIDTH
+            );
+        }
+        out
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(&DEFAULT_LATENCY_BUCKETS)
+    }
+}
+
+/// Prometheus text exposition of run-level latency percentiles and alert
+/// breach counts, ready for a textfile collector.
+pub fn prometheus_text(agg: &LatencyAggregator, alerts: &AlertStats) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP liminal_latency_ms Per-cycle latency percentiles in milliseconds."
+    );
+    let _ = writeln!(out, "# TYPE liminal_latency_ms gauge");
+    for (stage, samples) in [("asr", &agg.asr), ("tts", &agg.tts), ("total", &agg.total)] {
+        for (quantile, p) in [("0.5", 50.0), ("0.95", 95.0)] {
+            if let Some(value) = samples.percentile(p) {
+                let _ = writeln!(
+                    out,
+                    "liminal_latency_ms{{stage=\"{}\",quantile=\"{}\"}} {}",
+                    stage, quantile, value
+                );
+            }
+        }
+    }
+
+    let counters = [
+        (
+            "liminal_cycles",
+            "Cycles recorded in this run.",
+            agg.total.len(),
+        ),
+        (
+            "liminal_drift_breaches",
+            "Cycles with drift above the baseline.",
+            alerts.drift_breaches,
+        ),
+        (
+            "liminal_resonance_breaches",
+            "Cycles with resonance below the baseline.",
+            alerts.res_breaches,
+        ),
+        (
+            "liminal_drift_critical",
+            "Cycles with drift above the critical threshold.",
+            alerts.drift_crit,
+        ),
+        (
+            "liminal_resonance_critical",
+            "Cycles with resonance below the critical threshold.",
+            alerts.res_crit,
+        ),
+    ];
+    for (name, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        let _ = writeln!(out, "{} {}", name, value);
+    }
+    out
+}
+
+pub fn clamp01(v: f32) -> f32 {
+    v.clamp(0.0, 1.0)
 }
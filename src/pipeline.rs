@@ -0,0 +1,604 @@
+use std::time::Instant;
+
+use crate::adaptive_qa::{self, AnalysisCache};
+use crate::alerts::{self, AlertStats};
+use crate::astro::{self, AstroSessionStats, AstroStore};
+use crate::awareness::{MetaCognition, MetaStabilizer, ObservationContext};
+use crate::compassion::{CompassionAdjustments, CompassionCfg, CompassionMetrics};
+use crate::config::{Config, VizMode};
+use crate::device::DeviceProfile;
+use crate::emotive::EmoteSeed;
+use crate::metrics::{self, LatencyAggregator, LatencyHistogram, RollingMetrics};
+use crate::prosody::{self, HeuristicProsody, ProsodyAnalyzer, ToneTag};
+use crate::session::{self, Session, Snapshot, SyncDelta};
+use crate::softguard::{self, GuardAction, GuardConfig, GuardStats};
+use crate::spark;
+use crate::stabilizer::{self, Stabilizer, StabilizerCfg};
+use crate::sync::{SyncCfg, SyncState};
+use crate::utils;
+use crate::viz;
+use crate::voice_io::{self, AsrBackend, SimulatedAsr, SimulatedTts, TtsBackend};
+
+type Scorer<'a> = Box<dyn FnMut(&str) -> (f32, f32) + 'a>;
+
+/// Per-run state of the voice loop. `new` builds every layer `cfg` enables
+/// from scratch; callers warm-start layers from disk through the public
+/// fields before the first cycle and read the results back afterwards.
+pub struct Pipeline<'a> {
+    cfg: &'a Config,
+    prof: &'a DeviceProfile,
+    /// Planned number of cycles; the last one also logs emote and sync state.
+    pub cycles: usize,
+    pub asr: Box<dyn AsrBackend>,
+    pub tts: Box<dyn TtsBackend>,
+    pub prosody_analyzer: Box<dyn ProsodyAnalyzer>,
+    analysis_cache: AnalysisCache<Scorer<'a>>,
+    guard_cfg: GuardConfig,
+    pub sync_cfg: SyncCfg,
+    pub sync_state: SyncState,
+    pub stabilizer: Option<Stabilizer>,
+    pub meta_cognition: Option<MetaCognition>,
+    pub meta_stabilizer: Option<MetaStabilizer>,
+    pub compassion_metrics: Option<CompassionMetrics>,
+    pub astro_store: Option<AstroStore>,
+    pub astro_stats: AstroSessionStats,
+    /// Decayed seed from the previous session, if one was loaded.
+    pub emote_seed: Option<EmoteSeed>,
+    pub session: Option<Session>,
+    pub session_snapshots: Vec<Snapshot>,
+    pub drift_history: Vec<f32>,
+    pub resonance_history: Vec<f32>,
+    pub guard_stats: GuardStats,
+    pub latency: LatencyAggregator,
+    pub latency_hist: LatencyHistogram,
+    pub rolling: Option<RollingMetrics>,
+    pub alert_stats: Option<AlertStats>,
+    pub last_snapshot: Option<Snapshot>,
+    pub last_articulation: Option<f32>,
+    pub last_drift: Option<f32>,
+    pub last_res: Option<f32>,
+    pub last_tone: Option<ToneTag>,
+    pub last_wpm: Option<f32>,
+    pub last_filler_density: f32,
+    seed_bias_applied: bool,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(cfg: &'a Config, prof: &'a DeviceProfile, cycles: usize) -> Self {
+        let lexicon = cfg
+            .lexicon_path
+            .as_deref()
+            .map(adaptive_qa::load_lexicon)
+            .unwrap_or_default();
+        let pace_factor = prof.pace_factor;
+        let scorer: Scorer<'a> = Box::new(move |text: &str| {
+            adaptive_qa::analyze_prompt_with_lexicon(text, pace_factor, &lexicon)
+        });
+        let prosody_cfg = prosody::ProsodyCfg {
+            calm_wpm: cfg.prosody_calm_wpm,
+            energetic_wpm: cfg.prosody_energetic_wpm,
+            duration_ms: None,
+        };
+
+        let stabilizer = cfg.stabilizer.then(|| {
+            let mut stab = Stabilizer::new(stabilizer_cfg(cfg));
+            stab.advice_table = cfg.stab_advice;
+            stab
+        });
+
+        let compassion_metrics = cfg.compassion.then(|| {
+            let mut comp_cfg = CompassionCfg {
+                activation_threshold: cfg.compassion_threshold,
+                ..CompassionCfg::default()
+            };
+            comp_cfg.weights.overheat = cfg.compassion_overheat_weight;
+            comp_cfg.weights.fast_speech = cfg.compassion_wpm_weight;
+            CompassionMetrics::with_cfg(comp_cfg)
+        });
+
+        Self {
+            cfg,
+            prof,
+            cycles,
+            asr: Box::new(SimulatedAsr),
+            tts: Box::new(SimulatedTts::new(cfg.stabilizer)),
+            prosody_analyzer: Box::new(HeuristicProsody { cfg: prosody_cfg }),
+            analysis_cache: AnalysisCache::new(adaptive_qa::ANALYSIS_CACHE_CAPACITY, scorer),
+            guard_cfg: GuardConfig {
+                drift_limit: cfg.guard_drift,
+                res_limit: cfg.guard_res,
+                rephrase_factor: cfg.guard_factor,
+                banned: cfg.guard_banned.clone(),
+                strategy: cfg.guard_strategy,
+                low_res_margin: cfg.guard_low_res_margin,
+                warn_low_res: cfg.guard_warn_low_res,
+                hard_drift_limit: cfg.guard_hard_drift,
+            },
+            sync_cfg: SyncCfg {
+                lr_fast: cfg.sync_lr_fast,
+                lr_slow: cfg.sync_lr_slow,
+                clamp_step: cfg.sync_step,
+                momentum: cfg.sync_momentum,
+                lr_warming: cfg.sync_lr_warming,
+                lr_overheat: cfg.sync_lr_overheat,
+                lr_cooldown: cfg.sync_lr_cooldown,
+                accum_decay: cfg.sync_accum_decay,
+                pause_clamp_ms: cfg.sync_pause_clamp_ms,
+                boost_clamp: cfg.sync_boost_clamp,
+            },
+            sync_state: SyncState::default(),
+            stabilizer,
+            meta_cognition: cfg.awareness.then(MetaCognition::new),
+            meta_stabilizer: cfg
+                .awareness
+                .then(|| MetaStabilizer::new(cfg.meta_stab_alpha)),
+            compassion_metrics,
+            astro_store: None,
+            astro_stats: AstroSessionStats::default(),
+            emote_seed: None,
+            session: None,
+            session_snapshots: Vec::new(),
+            drift_history: Vec::with_capacity(cycles),
+            resonance_history: Vec::with_capacity(cycles),
+            guard_stats: GuardStats::default(),
+            latency: LatencyAggregator::new(),
+            latency_hist: LatencyHistogram::default(),
+            rolling: cfg.metrics_window.map(RollingMetrics::new),
+            alert_stats: cfg
+                .alarm
+                .then(|| AlertStats::with_crit(cfg.crit_drift, cfg.crit_res)),
+            last_snapshot: None,
+            last_articulation: None,
+            last_drift: None,
+            last_res: None,
+            last_tone: None,
+            last_wpm: None,
+            last_filler_density: 0.0,
+            seed_bias_applied: false,
+        }
+    }
+
+    /// Runs every utterance in order.
+    pub fn run(&mut self, utterances: &[String]) {
+        self.run_with(utterances, |_| {});
+    }
+
+    /// `run`, calling `on_cycle` with each snapshot as soon as it is built.
+    pub fn run_with(&mut self, utterances: &[String], mut on_cycle: impl FnMut(&Snapshot)) {
+        for (idx, utterance) in utterances.iter().enumerate() {
+            self.run_cycle(idx, utterance, &mut on_cycle);
+        }
+    }
+
+    /// One turn: recognize, score, stabilize, guard and voice `utterance`,
+    /// then log and report the resulting snapshot to `on_cycle`.
+    pub fn run_cycle(&mut self, idx: usize, utterance: &str, mut on_cycle: impl FnMut(&Snapshot)) {
+        let cfg = self.cfg;
+        let prof = self.prof;
+        let mut vm = metrics::start();
+
+        // The `--audio` recording stands for the first utterance only; the
+        // rest keep their simulated duration.
+        let recorded_ms = cfg.audio_duration_ms.filter(|_| idx == 0);
+
+        let asr_start = Instant::now();
+        let text = self.asr.transcribe(cfg, prof, utterance);
+        let asr_simulated = self
+            .asr
+            .simulated_ms(cfg, prof)
+            .map(|ms| ms + recorded_ms.map_or(0, |rec| voice_io::simulate_audio_latency(cfg, rec)));
+        vm.asr_ms = voice_io::stage_ms(cfg, asr_start, asr_simulated);
+
+        let prosody = match recorded_ms {
+            Some(rec) => {
+                self.prosody_analyzer
+                    .analyze_recorded(&text, prof.pace_factor, prof.pause_ms, rec)
+            }
+            None => self
+                .prosody_analyzer
+                .analyze(&text, prof.pace_factor, prof.pause_ms),
+        };
+        let scores = self.analysis_cache.analyze_cached(&text);
+        let (mut drift, mut res) = match cfg.seed {
+            Some(seed) => adaptive_qa::jitter_seeded(scores, &text, seed.wrapping_add(idx as u64)),
+            None => scores,
+        };
+        (drift, res) = adaptive_qa::apply_prosody_bias(drift, res, &prosody);
+        drift = metrics::clamp01(drift);
+        res = metrics::clamp01(res);
+        let measured_drift = drift;
+        let measured_res = res;
+
+        let mut astro_advice: Option<astro::AstroAdvice> = None;
+        let mut astro_key: Option<String> = None;
+        let mut astro_recall_ts: Option<i64> = None;
+        if cfg.astro {
+            astro_key = Some(astro::topic_key(&text, prosody.tone));
+        }
+        if let (Some(store), Some(key)) = (self.astro_store.as_mut(), astro_key.as_ref()) {
+            let now_ts = utils::current_unix_secs();
+            if let Some(mut advice) = store.recall(key, now_ts) {
+                if let Some(seed) = self.emote_seed.as_ref()
+                    && idx < 2
+                    && seed.tone == prosody.tone
+                {
+                    let extra = 0.02 + (advice.res_bias.abs().min(0.06) * 0.5);
+                    advice.res_bias += extra;
+                    advice.drift_bias -= extra * 0.6;
+                }
+                drift = metrics::clamp01(drift + advice.drift_bias);
+                res = metrics::clamp01(res + advice.res_bias);
+                self.astro_stats.hits = self.astro_stats.hits.saturating_add(1);
+                self.astro_stats.boost_res += advice.res_bias;
+                self.astro_stats.bias_drift += advice.drift_bias;
+                astro_recall_ts = Some(now_ts);
+                astro_advice = Some(advice);
+            }
+        }
+
+        let emo_flag = matches!(prosody.tone, ToneTag::Energetic | ToneTag::Anxious)
+            && (measured_drift > cfg.baseline_drift || measured_res > 0.75);
+
+        let mut articulation = prosody.articulation;
+        let mut articulation_hint = 0.0;
+        let mut effective_pace = prof.pace_factor;
+        let mut effective_pause_ms = prof.pause_ms as i64;
+        let mut stab_state_label: Option<String> = None;
+        let mut current_state = stabilizer::EmoState::Normal;
+
+        if !self.seed_bias_applied {
+            if let Some(seed) = self.emote_seed.as_ref() {
+                let pace_bias = (seed.wpm / 160.0).clamp(0.8, 1.2);
+                effective_pace = (effective_pace * pace_bias).clamp(0.7, 1.3);
+            }
+            if cfg.sync {
+                let seeds = &self.sync_state.seeds;
+                effective_pace = (effective_pace + seeds.pace_bias).clamp(0.7, 1.3);
+                effective_pause_ms = (effective_pause_ms + seeds.pause_bias_ms).clamp(20, 250);
+                res = metrics::clamp01(res + seeds.res_warm);
+                drift = metrics::clamp01(drift - seeds.drift_soft);
+            }
+            self.seed_bias_applied = true;
+        }
+
+        if let Some(stab) = self.stabilizer.as_mut() {
+            stab.push(drift, res);
+            let advice = stab.advice();
+            effective_pace = (prof.pace_factor + advice.pace_delta).clamp(0.7, 1.3);
+            effective_pause_ms = (prof.pause_ms as i64 + advice.pause_delta_ms).clamp(20, 250);
+            articulation_hint = advice.articulation_hint;
+            articulation =
+                prosody::apply_articulation_hint(prosody.articulation, articulation_hint);
+            println!(
+                "{}",
+                stabilizer::format_status(
+                    stab.state,
+                    stab.ema_drift,
+                    stab.ema_res,
+                    stab.cooldown_remaining()
+                )
+            );
+            if let VizMode::Compact = cfg.viz_mode {
+                viz::print_compact_stabilizer(stab.state, stab.ema_drift, stab.ema_res);
+            }
+            stab_state_label = Some(format!("{:?}", stab.state));
+            current_state = stab.state;
+        }
+
+        if let Some(advice) = astro_advice {
+            (effective_pace, effective_pause_ms) =
+                self.apply_astro_advice(advice, effective_pace, effective_pause_ms);
+        }
+
+        // A doubting meta layer (as of the previous turn) acts more cautiously
+        let caution = self
+            .meta_cognition
+            .as_ref()
+            .map(|meta| meta.caution())
+            .unwrap_or(1.0);
+
+        let mut sync_delta: Option<SyncDelta> = None;
+        if cfg.sync {
+            let (pace_delta, pause_delta_ms, res_boost, drift_relief) =
+                self.sync_state
+                    .step(drift, res, current_state, &self.sync_cfg);
+            let delta = SyncDelta {
+                pace_delta,
+                pause_delta_ms,
+                res_boost,
+                drift_relief,
+            }
+            .scaled(caution);
+            effective_pace += delta.pace_delta;
+            effective_pause_ms += delta.pause_delta_ms;
+            res = metrics::clamp01(res + delta.res_boost);
+            drift = metrics::clamp01(drift - delta.drift_relief);
+            sync_delta = Some(delta);
+        }
+
+        // Meta-cognition observation
+        if let Some(ref mut meta) = self.meta_cognition {
+            let sync_correction = if let Some(ref delta) = sync_delta {
+                delta.pace_delta.abs() + (delta.pause_delta_ms as f32 / 100.0)
+            } else {
+                0.0
+            };
+
+            let stab_state_str = stab_state_label.as_deref().unwrap_or("None");
+            let observation_ctx = ObservationContext {
+                measurement_confidence: Some(prosody.confidence),
+                steps_in_state: self.stabilizer.as_ref().map(|stab| stab.steps_in_state),
+            };
+            meta.observe_with(
+                measured_drift,
+                measured_res,
+                stab_state_str,
+                sync_correction,
+                &observation_ctx,
+            );
+
+            // Update meta-stabilizer
+            if let Some(ref mut meta_stab) = self.meta_stabilizer {
+                meta_stab.update(meta);
+            }
+
+            if let VizMode::Compact = cfg.viz_mode {
+                viz::print_compact_meta(meta);
+            }
+
+            // Log meta-cognition state
+            if cfg.meta_viz {
+                println!("[meta] {}", meta.self_assess());
+
+                if meta.should_express_doubt() {
+                    println!(
+                        "[meta] {}  System is uncertain about measurements",
+                        spark::glyphs(cfg.ascii).warning
+                    );
+                }
+            }
+        }
+
+        // Compassion detection and response
+        if let Some(ref mut comp) = self.compassion_metrics {
+            // Check if theme is repeated (from astro)
+            // Simple heuristic: if we've seen this theme before (would need astro store method)
+            // TODO: implement has_trace() in astro
+            let repeated_theme = false;
+
+            let stab_state_str = stab_state_label.as_deref().unwrap_or("Normal");
+            comp.detect_suffering(
+                measured_drift,
+                measured_res,
+                prosody.tone,
+                prosody.wpm,
+                stab_state_str,
+                repeated_theme,
+            );
+
+            // Kindness for this turn is scored after the guard runs; the level
+            // below uses the previous turn's kindness
+            comp.detect_recovery(measured_drift, measured_res);
+            comp.update_compassion_level();
+
+            // Apply compassion adjustments if activated
+            if comp.should_activate_compassion() {
+                let adj = CompassionAdjustments::from_compassion(comp);
+
+                // Apply adjustments
+                res = metrics::clamp01(res + adj.resonance_boost);
+                drift = metrics::clamp01(drift - adj.drift_reduction);
+                effective_pace = (effective_pace + adj.pace_adjustment).clamp(0.7, 1.3);
+                effective_pause_ms = (effective_pause_ms + adj.pause_adjustment_ms).clamp(20, 250);
+            }
+
+            if let VizMode::Compact = cfg.viz_mode {
+                viz::print_compact_compassion(comp);
+            }
+
+            // Log compassion state
+            if cfg.compassion_viz {
+                println!("[compassion] {}", comp.status_message(cfg.ascii));
+
+                if comp.should_offer_support() {
+                    println!(
+                        "[compassion] {} {}",
+                        spark::glyphs(cfg.ascii).support,
+                        comp.support_message()
+                    );
+                }
+            }
+        }
+
+        if let Some(advice) = astro_advice {
+            (effective_pace, effective_pause_ms) =
+                self.apply_astro_advice(advice, effective_pace, effective_pause_ms);
+        }
+
+        let effective_pause_ms = effective_pause_ms.clamp(20, 250);
+        let effective_pause_u64 = effective_pause_ms as u64;
+        effective_pace = effective_pace.clamp(0.7, 1.3);
+
+        let mut guard_flag = None;
+        let mut blocked = false;
+        if cfg.guard {
+            let guard_ctx = softguard::GuardContext {
+                compassion_level: self
+                    .compassion_metrics
+                    .as_ref()
+                    .map(|c| c.compassion_level)
+                    .unwrap_or(0.0),
+            };
+            let relaxed_cfg;
+            let turn_guard_cfg = if caution < 1.0 {
+                relaxed_cfg = self.guard_cfg.relaxed(caution);
+                &relaxed_cfg
+            } else {
+                &self.guard_cfg
+            };
+            let action =
+                softguard::check_and_rephrase_with(&text, drift, res, turn_guard_cfg, &guard_ctx);
+            guard_flag = action.label();
+            self.guard_stats.record(&action);
+            match action {
+                GuardAction::None => {}
+                GuardAction::Block(reason) => {
+                    println!(
+                        "[soft-guard] blocked response (reason={}) → not voiced",
+                        reason.as_str()
+                    );
+                    blocked = true;
+                }
+                GuardAction::Warn { reason, message } => {
+                    println!("{} (reason={})", message, reason.as_str());
+                }
+                GuardAction::Rephrased {
+                    reason,
+                    text: new_text,
+                } => {
+                    println!("[voice-core] {} (reason={})", new_text, reason.as_str());
+                    self.tts.speak(
+                        cfg,
+                        prof,
+                        effective_pace,
+                        effective_pause_u64,
+                        articulation_hint,
+                        &new_text,
+                    );
+                }
+            }
+        }
+
+        if let Some(comp) = self.compassion_metrics.as_mut() {
+            comp.record_turn_kindness(guard_flag.as_deref(), sync_delta.as_ref());
+        }
+
+        let tts_start = Instant::now();
+        let mut tts_simulated_ms = None;
+        if !blocked {
+            self.tts.speak(
+                cfg,
+                prof,
+                effective_pace,
+                effective_pause_u64,
+                articulation_hint,
+                &format!("Semantic Drift: {:.2}, Resonance: {:.2}", drift, res),
+            );
+            tts_simulated_ms = self.tts.simulated_ms();
+        }
+        vm.tts_ms = voice_io::stage_ms(cfg, tts_start, tts_simulated_ms);
+
+        metrics::finish(&mut vm);
+        self.latency.record(&vm);
+        self.latency_hist.record(vm.total_ms);
+        if let Some(rolling) = self.rolling.as_mut() {
+            rolling.record(&vm);
+        }
+
+        if cfg.enable_metrics && !cfg.is_quiet() {
+            match self.rolling.as_ref() {
+                Some(rolling) => metrics::print_with_rolling(&vm, rolling),
+                None => metrics::print(&vm),
+            }
+        }
+
+        self.drift_history.push(drift);
+        self.resonance_history.push(res);
+
+        let is_last = idx + 1 == self.cycles;
+        let meta = self.meta_cognition.as_ref();
+        let comp = self.compassion_metrics.as_ref();
+        let snapshot = Snapshot {
+            ts: session::now_rfc3339(),
+            device: cfg.mode.clone(),
+            drift,
+            resonance: res,
+            wpm: prosody.wpm,
+            articulation,
+            tone: format!("{:?}", prosody.tone),
+            asr_ms: vm.asr_ms,
+            tts_ms: vm.tts_ms,
+            total_ms: vm.total_ms,
+            idx,
+            utterance: text.clone(),
+            guard: guard_flag.clone(),
+            state: stab_state_label.clone(),
+            emote_state: is_last.then(|| format!("{:?}", prosody.tone)),
+            sync: if is_last { sync_delta } else { None },
+            meta_self_drift: meta.map(|m| m.self_drift),
+            meta_self_resonance: meta.map(|m| m.self_resonance),
+            meta_confidence: meta.map(|m| m.confidence),
+            meta_clarity: meta.map(|m| m.clarity),
+            meta_doubt: meta.map(|m| m.doubt),
+            compassion_suffering: comp.map(|c| c.user_suffering),
+            compassion_type: comp.map(|c| format!("{:?}", c.suffering_type)),
+            compassion_kindness: comp.map(|c| c.response_kindness),
+            compassion_healing: comp.map(|c| c.healing_intent),
+            compassion_level: comp.map(|c| c.compassion_level),
+        };
+        on_cycle(&snapshot);
+
+        self.last_articulation = Some(articulation);
+        self.last_drift = Some(drift);
+        self.last_res = Some(res);
+        self.last_tone = Some(prosody.tone);
+        self.last_wpm = Some(prosody.wpm);
+        self.last_filler_density = prosody.filler_density;
+
+        if let Some(sess) = self.session.as_mut() {
+            if let Err(err) = session::write(sess, &snapshot) {
+                eprintln!("[log] failed to write snapshot: {}", err);
+            }
+            self.session_snapshots.push(snapshot.clone());
+        }
+
+        if let (Some(store), Some(key)) = (self.astro_store.as_mut(), astro_key.as_ref()) {
+            let ts = astro_recall_ts.unwrap_or_else(utils::current_unix_secs);
+            store.consolidate(key, measured_drift, measured_res, emo_flag, ts);
+        }
+
+        self.last_snapshot = Some(snapshot);
+
+        if let Some(stats) = self.alert_stats.as_mut() {
+            alerts::update(stats, drift, res, cfg.baseline_drift, cfg.baseline_res);
+        }
+    }
+
+    /// Astro pacing advice, nudged slower while the stabilizer runs hot.
+    fn apply_astro_advice(
+        &self,
+        mut advice: astro::AstroAdvice,
+        pace: f32,
+        pause_ms: i64,
+    ) -> (f32, i64) {
+        if let Some(stab) = self.stabilizer.as_ref()
+            && matches!(
+                stab.state,
+                stabilizer::EmoState::Overheat | stabilizer::EmoState::Panic
+            )
+        {
+            advice.pace_delta -= 0.02;
+            advice.pause_delta_ms += 15;
+        }
+        (
+            (pace + advice.pace_delta).clamp(0.7, 1.3),
+            (pause_ms + advice.pause_delta_ms).clamp(20, 250),
+        )
+    }
+}
+
+/// The stabilizer settings `cfg` describes.
+pub fn stabilizer_cfg(cfg: &Config) -> StabilizerCfg {
+    StabilizerCfg {
+        win: cfg.stab_win,
+        ema_alpha: cfg.stab_alpha,
+        warm_drift: cfg.stab_warm,
+        hot_drift: cfg.stab_hot,
+        low_res: cfg.stab_low_res,
+        cool_steps: cfg.stab_cool,
+        calm_boost: cfg.stab_calm,
+        panic_drift: cfg.stab_panic,
+        hysteresis: cfg.stab_hysteresis,
+        use_median: cfg.stab_median,
+    }
+}
@@ -1,48 +1,395 @@
+use std::str::FromStr;
+
 use crate::metrics;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ToneTag {
+    #[default]
     Neutral,
     Calm,
     Energetic,
+    Anxious,
+    Sad,
+}
+
+impl ToneTag {
+    /// Canonical lowercase name, as persisted in seed files.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ToneTag::Neutral => "neutral",
+            ToneTag::Calm => "calm",
+            ToneTag::Energetic => "energetic",
+            ToneTag::Anxious => "anxious",
+            ToneTag::Sad => "sad",
+        }
+    }
+}
+
+impl FromStr for ToneTag {
+    type Err = String;
+
+    /// Case-insensitive, so seeds written as `"Calm"` still parse.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "neutral" => Ok(ToneTag::Neutral),
+            "calm" => Ok(ToneTag::Calm),
+            "energetic" => Ok(ToneTag::Energetic),
+            "anxious" => Ok(ToneTag::Anxious),
+            "sad" => Ok(ToneTag::Sad),
+            other => Err(format!("unknown tone '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProsodyCfg {
+    pub calm_wpm: f32,
+    pub energetic_wpm: f32,
+    /// Measured utterance length, e.g. from a WAV header; replaces the
+    /// duration simulated from pace and pause when set.
+    pub duration_ms: Option<u64>,
+}
+
+impl Default for ProsodyCfg {
+    fn default() -> Self {
+        Self {
+            calm_wpm: 120.0,
+            energetic_wpm: 180.0,
+            duration_ms: None,
+        }
+    }
 }
 
 pub struct Prosody {
     pub wpm: f32,
     pub articulation: f32,
     pub tone: ToneTag,
+    /// Reliability of the estimates above, from word count (0.3 for a single
+    /// word up to 1.0 at eight or more).
+    pub confidence: f32,
+    pub is_question: bool,
+    pub is_exclamation: bool,
+    /// Fraction of tokens that are filler words (see [`FILLERS`]).
+    pub filler_density: f32,
 }
 
-pub fn analyze(text: &str, pace_factor: f32, pause_ms: u64) -> Prosody {
-    let words = text
-        .split_whitespace()
-        .filter(|w| !w.is_empty())
-        .count()
-        .max(1);
-    let _ = words; // reserved for future heuristics
+const BASE_WPM: f32 = 150.0;
+const MAX_WPM: f32 = 220.0;
+
+/// Word count at which prosody estimates are considered fully reliable.
+const CONFIDENT_WORDS: usize = 8;
+
+/// Syllable rate treated as fully enunciated speech.
+const CLEAR_SYLLABLES_PER_SEC: f32 = 6.0;
+
+/// Filler words and phrases counted by [`filler_density`].
+pub const FILLERS: &[&str] = &[
+    "um", "umm", "uh", "uhh", "erm", "hmm", "like", "you know", "i mean",
+];
+
+const QUESTION_WORDS: &[&str] = &[
+    "who", "what", "when", "where", "why", "how", "which", "is", "are", "do", "does", "can",
+    "could", "would", "should", "will",
+];
+
+const ANXIOUS_WORDS: &[&str] = &[
+    "stuck", "can't", "cant", "cannot", "worried", "scared", "panic",
+];
+const SAD_WORDS: &[&str] = &["tired", "alone", "lonely", "sad", "empty", "hopeless"];
+
+/// Lowercase vowels counted as syllable nuclei, Latin and Cyrillic.
+const VOWELS: &str = "aeiouyàáâãäåæèéêëìíîïòóôõöøùúûüýÿаеёиоуыэюя";
+
+/// All-caps words that are ordinary acronyms rather than shouting.
+pub const ACRONYMS: &[&str] = &[
+    "OK", "API", "AI", "ID", "TV", "PC", "UK", "USA", "EU", "FAQ", "URL", "PDF", "GPS", "CEO",
+    "ASAP", "DIY", "ETA", "FYI", "SMS", "USB",
+];
+
+/// Whether `word` is shouted: at least two letters, none lowercase, and not
+/// one of the [`ACRONYMS`]. Surrounding punctuation is ignored.
+pub fn is_shouted(word: &str) -> bool {
+    let bare = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let letters = bare.chars().filter(|c| c.is_alphabetic()).count();
+    letters >= 2
+        && !bare.chars().any(|c| c.is_lowercase())
+        && !ACRONYMS.contains(&bare.to_uppercase().as_str())
+}
+
+/// Source of prosody estimates for a transcript. The built-in
+/// [`HeuristicProsody`] derives everything from text and device pacing; an
+/// integrator with a real acoustic frontend can supply its own.
+pub trait ProsodyAnalyzer {
+    fn analyze(&self, text: &str, pace: f32, pause_ms: u64) -> Prosody;
+
+    /// Like [`analyze`](Self::analyze) for a transcript whose recording is
+    /// known to last `duration_ms`. Ignores the length by default.
+    fn analyze_recorded(&self, text: &str, pace: f32, pause_ms: u64, _duration_ms: u64) -> Prosody {
+        self.analyze(text, pace, pause_ms)
+    }
+}
+
+/// Text-based analyzer backed by [`analyze`] with the given thresholds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HeuristicProsody {
+    pub cfg: ProsodyCfg,
+}
+
+impl ProsodyAnalyzer for HeuristicProsody {
+    fn analyze(&self, text: &str, pace: f32, pause_ms: u64) -> Prosody {
+        analyze(text, pace, pause_ms, &self.cfg)
+    }
+
+    fn analyze_recorded(&self, text: &str, pace: f32, pause_ms: u64, duration_ms: u64) -> Prosody {
+        let cfg = ProsodyCfg {
+            duration_ms: Some(duration_ms),
+            ..self.cfg
+        };
+        analyze(text, pace, pause_ms, &cfg)
+    }
+}
+
+pub fn analyze_default(text: &str, pace_factor: f32, pause_ms: u64) -> Prosody {
+    analyze(text, pace_factor, pause_ms, &ProsodyCfg::default())
+}
+
+pub fn analyze(text: &str, pace_factor: f32, pause_ms: u64, cfg: &ProsodyCfg) -> Prosody {
+    let words = tokenize(text).len().max(1);
 
-    let base_wpm = 150.0_f32;
     let pause = (pause_ms as f32).max(20.0);
-    let raw = (base_wpm * pace_factor * (40.0 / pause)) / 200.0;
-    let wpm = metrics::clamp01(raw) * 220.0;
+    let duration_ms = match cfg.duration_ms {
+        Some(ms) if ms > 0 => ms as f32,
+        _ => simulated_duration_ms(words, pace_factor, pause),
+    };
+    let raw = words as f32 * 60_000.0 / duration_ms;
+    let wpm = metrics::clamp01(raw / MAX_WPM) * MAX_WPM;
 
-    let articulation = metrics::clamp01((0.85 / pace_factor.max(0.1)) * (pause / 80.0));
+    let device_articulation = metrics::clamp01((0.85 / pace_factor.max(0.1)) * (pause / 80.0));
+    let syllables_per_sec = estimate_syllables(text) as f32 / (duration_ms / 1000.0);
+    let enunciation = metrics::clamp01(syllables_per_sec / CLEAR_SYLLABLES_PER_SEC);
+    let articulation = metrics::clamp01(device_articulation * 0.75 + enunciation * 0.25);
 
-    let tone = if wpm < 120.0 {
+    let tone = affect_tone(text, wpm).unwrap_or(if wpm < cfg.calm_wpm {
         ToneTag::Calm
-    } else if wpm > 180.0 {
+    } else if wpm > cfg.energetic_wpm {
         ToneTag::Energetic
     } else {
         ToneTag::Neutral
-    };
+    });
+
+    let confidence = word_confidence(words);
+    let (is_question, is_exclamation) = sentence_kind(text);
+    let filler_density = filler_density(text);
 
     Prosody {
         wpm,
         articulation,
         tone,
+        confidence,
+        is_question,
+        is_exclamation,
+        filler_density,
     }
 }
 
+/// Fraction of tokens that belong to a filler word or phrase. Multi-word
+/// fillers such as "you know" count every token they cover.
+pub fn filler_density(text: &str) -> f32 {
+    let tokens: Vec<String> = tokenize(text).iter().map(|w| w.to_lowercase()).collect();
+    if tokens.is_empty() {
+        return 0.0;
+    }
+
+    let mut filler_tokens = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let matched = FILLERS.iter().find_map(|filler| {
+            let parts: Vec<&str> = filler.split(' ').collect();
+            let end = i + parts.len();
+            (end <= tokens.len() && tokens[i..end].iter().zip(&parts).all(|(t, p)| t == p))
+                .then_some(parts.len())
+        });
+        match matched {
+            Some(len) => {
+                filler_tokens += len;
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+
+    filler_tokens as f32 / tokens.len() as f32
+}
+
+/// Reads the terminal punctuation run (`?`, `!`, `?!`, ...) and falls back to
+/// a leading question word for unpunctuated questions.
+fn sentence_kind(text: &str) -> (bool, bool) {
+    let trimmed = text.trim_end();
+    let tail: String = trimmed
+        .chars()
+        .rev()
+        .take_while(|c| matches!(c, '?' | '!' | '.'))
+        .collect();
+    let is_exclamation = tail.contains('!');
+    let mut is_question = tail.contains('?');
+
+    if !is_question
+        && tail.is_empty()
+        && let Some(first) = tokenize(trimmed).first()
+    {
+        let first = first.to_lowercase();
+        is_question = QUESTION_WORDS.contains(&first.as_str());
+    }
+
+    (is_question, is_exclamation)
+}
+
+fn word_confidence(words: usize) -> f32 {
+    let ramp = words.saturating_sub(1) as f32 / (CONFIDENT_WORDS - 1) as f32;
+    0.3 + 0.7 * metrics::clamp01(ramp)
+}
+
+/// Rough syllable count: one per group of consecutive vowels in each word,
+/// ignoring a silent trailing `e`, with at least one per word that has
+/// letters.
+pub fn estimate_syllables(text: &str) -> usize {
+    tokenize(text)
+        .into_iter()
+        .map(|word| {
+            let letters: Vec<char> = word
+                .chars()
+                .filter(|c| c.is_alphabetic())
+                .flat_map(char::to_lowercase)
+                .collect();
+            if letters.is_empty() {
+                return 0;
+            }
+
+            let mut groups = 0;
+            let mut in_vowel = false;
+            for &c in &letters {
+                let vowel = is_vowel(c);
+                if vowel && !in_vowel {
+                    groups += 1;
+                }
+                in_vowel = vowel;
+            }
+
+            let len = letters.len();
+            if groups > 1 && letters[len - 1] == 'e' && !is_vowel(letters[len - 2]) {
+                groups -= 1;
+            }
+            groups.max(1)
+        })
+        .sum()
+}
+
+fn is_vowel(c: char) -> bool {
+    c.to_lowercase().any(|l| VOWELS.contains(l))
+}
+
+/// Splits a transcript into words: Unicode whitespace separates tokens,
+/// surrounding punctuation is stripped (inner apostrophes survive), and
+/// characters from scripts written without spaces (Han, kana) count as one
+/// word each.
+pub fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    for raw in text.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+        if word.is_empty() {
+            continue;
+        }
+        if !word.chars().any(is_spaceless_script) {
+            tokens.push(word);
+            continue;
+        }
+
+        let mut run_start: Option<usize> = None;
+        for (idx, c) in word.char_indices() {
+            if is_spaceless_script(c) {
+                if let Some(start) = run_start.take() {
+                    push_trimmed(&mut tokens, &word[start..idx]);
+                }
+                tokens.push(&word[idx..idx + c.len_utf8()]);
+            } else if run_start.is_none() {
+                run_start = Some(idx);
+            }
+        }
+        if let Some(start) = run_start {
+            push_trimmed(&mut tokens, &word[start..]);
+        }
+    }
+    tokens
+}
+
+fn push_trimmed<'a>(tokens: &mut Vec<&'a str>, run: &'a str) {
+    let run = run.trim_matches(|c: char| !c.is_alphanumeric());
+    if !run.is_empty() {
+        tokens.push(run);
+    }
+}
+
+fn is_spaceless_script(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x3040..=0x30FF // Hiragana, Katakana
+            | 0x3400..=0x4DBF // CJK Extension A
+            | 0x4E00..=0x9FFF // CJK Unified Ideographs
+            | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+    )
+}
+
+/// Scores anxious and sad cues in the transcript. Repeated `!`/`?`, shouted
+/// (all-caps) words and words like "stuck" count toward Anxious; words like
+/// "tired" and a trailing `...` count toward Sad. Ties go to Anxious when the
+/// speech is faster than the base tempo and to Sad otherwise.
+fn affect_tone(text: &str, wpm: f32) -> Option<ToneTag> {
+    let mut anxious = 0usize;
+    let mut sad = 0usize;
+
+    if text.contains("!!") || text.contains("??") || text.contains("?!") || text.contains("!?") {
+        anxious += 1;
+    }
+    if text.trim_end().ends_with("...") || text.trim_end().ends_with('…') {
+        sad += 1;
+    }
+
+    for word in tokenize(text) {
+        if is_shouted(word) {
+            anxious += 1;
+        }
+        let lower = word.to_lowercase();
+        if ANXIOUS_WORDS.contains(&lower.as_str()) {
+            anxious += 1;
+        }
+        if SAD_WORDS.contains(&lower.as_str()) {
+            sad += 1;
+        }
+    }
+
+    if anxious == 0 && sad == 0 {
+        None
+    } else if anxious > sad {
+        Some(ToneTag::Anxious)
+    } else if sad > anxious {
+        Some(ToneTag::Sad)
+    } else if wpm >= BASE_WPM {
+        Some(ToneTag::Anxious)
+    } else {
+        Some(ToneTag::Sad)
+    }
+}
+
+/// Simulated speaking time: each word takes `60s / (BASE_WPM * pace)`, words
+/// are separated by half a pause, and the utterance is framed by one full
+/// pause of onset and one of release.
+fn simulated_duration_ms(words: usize, pace_factor: f32, pause_ms: f32) -> f32 {
+    let word_ms = 60_000.0 / (BASE_WPM * pace_factor.max(0.1));
+    let gaps = words.saturating_sub(1) as f32 * pause_ms * 0.5;
+    let edges = pause_ms * 2.0;
+    words as f32 * word_ms + gaps + edges
+}
+
 pub fn apply_articulation_hint(articulation: f32, hint: f32) -> f32 {
     metrics::clamp01(articulation + hint)
 }
@@ -1,17 +1,33 @@
-use std::fs::{self, File};
-use std::io::{self, Write};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::config::Config;
+
+/// Session id for append mode when no `session_id` is configured.
+pub const APPEND_SESSION_ID: &str = "live";
+
 pub struct Session {
     pub id: String,
     #[allow(dead_code)]
     pub cycles: usize,
     pub log_dir: String,
-    file: Option<File>,
+    /// Keep existing contents of the session file instead of truncating it.
+    pub append: bool,
+    /// Roll the log to `.1`, `.2`, ... once it would grow past this size.
+    pub max_log_bytes: Option<u64>,
+    /// Flush after every snapshot instead of only on rotation and `close`.
+    pub flush_each: bool,
+    file: Option<BufWriter<File>>,
+    bytes_written: u64,
 }
 
-#[derive(Clone)]
+/// One logged turn. Optional layers default to `None`, so call sites can set
+/// what they know and fill the rest with `..Snapshot::default()`.
+#[derive(Clone, Debug, Default)]
 pub struct Snapshot {
     pub ts: String,
     pub device: String,
@@ -41,7 +57,13 @@ pub struct Snapshot {
     pub compassion_level: Option<f32>,
 }
 
-#[derive(Clone, Copy)]
+impl Snapshot {
+    /// Layout version written as `"schema"` on every log line. Untagged lines
+    /// predate versioning but share this layout.
+    pub const SCHEMA: u32 = 1;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct SyncDelta {
     pub pace_delta: f32,
     pub pause_delta_ms: i64,
@@ -49,29 +71,67 @@ pub struct SyncDelta {
     pub drift_relief: f32,
 }
 
+impl SyncDelta {
+    /// Corrections shrunk by `factor` (0..1), e.g. while the system doubts itself.
+    pub fn scaled(self, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        Self {
+            pace_delta: self.pace_delta * factor,
+            pause_delta_ms: (self.pause_delta_ms as f32 * factor).round() as i64,
+            res_boost: self.res_boost * factor,
+            drift_relief: self.drift_relief * factor,
+        }
+    }
+}
+
 pub fn start(cycles: usize, log_dir: &str) -> Session {
     Session {
         id: generate_id(),
         cycles,
         log_dir: log_dir.to_string(),
+        append: false,
+        max_log_bytes: None,
+        flush_each: false,
         file: None,
+        bytes_written: 0,
     }
 }
 
+/// A session set up from the logging options in `cfg`. Append mode keeps a
+/// stable id (`session_id`, else `APPEND_SESSION_ID`) so each run extends the
+/// same file; otherwise a fresh id is generated unless one is given.
+pub fn start_from(cfg: &Config) -> Session {
+    let mut sess = start(cfg.cycles, &cfg.log_dir);
+    match (cfg.session_id.as_ref(), cfg.log_append) {
+        (Some(id), _) => sess.id = id.clone(),
+        (None, true) => sess.id = APPEND_SESSION_ID.to_string(),
+        (None, false) => {}
+    }
+    sess.append = cfg.log_append;
+    sess.max_log_bytes = cfg.log_max_bytes;
+    sess.flush_each = cfg.log_flush_each;
+    sess
+}
+
 pub fn open_file(sess: &mut Session) -> io::Result<()> {
     let path = session_path(sess);
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-    sess.file = Some(File::create(path)?);
+    let file = if sess.append {
+        OpenOptions::new().create(true).append(true).open(&path)?
+    } else {
+        File::create(&path)?
+    };
+    sess.bytes_written = file.metadata()?.len();
+    sess.file = Some(BufWriter::new(file));
     Ok(())
 }
 
 pub fn write(sess: &mut Session, snap: &Snapshot) -> io::Result<()> {
-    let file = sess
-        .file
-        .as_mut()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "session file not opened"))?;
+    if sess.file.is_none() {
+        return Err(io::Error::other("session file not opened"));
+    }
 
     let guard_value = match snap.guard.as_ref() {
         Some(value) => format!("\"{}\"", escape_json(value)),
@@ -107,7 +167,8 @@ pub fn write(sess: &mut Session, snap: &Snapshot) -> io::Result<()> {
     let comp_level_value = snap.compassion_level.map_or("null".to_string(), |v| format!("{:.3}", v));
 
     let line = format!(
-        r#"{{"ts":"{}","device":"{}","drift":{:.3},"resonance":{:.3},"wpm":{:.3},"articulation":{:.3},"tone":"{}","asr_ms":{},"tts_ms":{},"total_ms":{},"idx":{},"utt":"{}","guard":{},"state":{},"emote_state":{},"sync":{},"meta_self_drift":{},"meta_self_resonance":{},"meta_confidence":{},"meta_clarity":{},"meta_doubt":{},"compassion_suffering":{},"compassion_type":{},"compassion_kindness":{},"compassion_healing":{},"compassion_level":{}}}"#,
+        r#"{{"schema":{},"ts":"{}","device":"{}","drift":{:.3},"resonance":{:.3},"wpm":{:.3},"articulation":{:.3},"tone":"{}","asr_ms":{},"tts_ms":{},"total_ms":{},"idx":{},"utt":"{}","guard":{},"state":{},"emote_state":{},"sync":{},"meta_self_drift":{},"meta_self_resonance":{},"meta_confidence":{},"meta_clarity":{},"meta_doubt":{},"compassion_suffering":{},"compassion_type":{},"compassion_kindness":{},"compassion_healing":{},"compassion_level":{}}}"#,
+        Snapshot::SCHEMA,
         escape_json(&snap.ts),
         escape_json(&snap.device),
         snap.drift,
@@ -136,12 +197,438 @@ pub fn write(sess: &mut Session, snap: &Snapshot) -> io::Result<()> {
         comp_level_value
     );
 
-    writeln!(file, "{}", line)
+    let len = line.len() as u64 + 1;
+    if let Some(max) = sess.max_log_bytes
+        && sess.bytes_written > 0
+        && sess.bytes_written + len > max
+    {
+        rotate(sess)?;
+    }
+    let file = sess
+        .file
+        .as_mut()
+        .ok_or_else(|| io::Error::other("session file not opened"))?;
+    writeln!(file, "{}", line)?;
+    if sess.flush_each {
+        file.flush()?;
+    }
+    sess.bytes_written += len;
+    Ok(())
 }
 
-pub fn close(mut sess: Session) {
+/// Shift `session-<id>.jsonl.N` to `.N+1`, move the live log to `.1`, and
+/// start a fresh file.
+fn rotate(sess: &mut Session) -> io::Result<()> {
     if let Some(mut file) = sess.file.take() {
-        let _ = file.flush();
+        file.flush()?;
+    }
+    let base = session_path(sess);
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", base.display(), n));
+    let mut free = 1;
+    while rotated(free).exists() {
+        free += 1;
+    }
+    for n in (1..free).rev() {
+        fs::rename(rotated(n), rotated(n + 1))?;
+    }
+    fs::rename(&base, rotated(1))?;
+    sess.file = Some(BufWriter::new(File::create(&base)?));
+    sess.bytes_written = 0;
+    Ok(())
+}
+
+/// Flushes buffered snapshots to disk; the error is returned so callers
+/// can report a log that did not make it out.
+pub fn close(mut sess: Session) -> io::Result<()> {
+    match sess.file.take() {
+        Some(mut file) => file.flush(),
+        None => Ok(()),
+    }
+}
+
+/// Aggregates over one session's snapshots.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    pub turns: usize,
+    pub drift_mean: f32,
+    pub drift_min: f32,
+    pub drift_max: f32,
+    pub res_mean: f32,
+    pub res_min: f32,
+    pub res_max: f32,
+    /// Turns per tone, sorted by tone name.
+    pub tone_counts: Vec<(String, usize)>,
+    /// Sum of each turn's `total_ms`.
+    pub total_latency_ms: u128,
+}
+
+impl SessionSummary {
+    pub fn to_json(&self) -> String {
+        let tones = self
+            .tone_counts
+            .iter()
+            .map(|(tone, count)| format!("\"{}\":{}", escape_json(tone), count))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            r#"{{"turns":{},"drift":{{"mean":{:.3},"min":{:.3},"max":{:.3}}},"resonance":{{"mean":{:.3},"min":{:.3},"max":{:.3}}},"tones":{{{}}},"total_latency_ms":{}}}"#,
+            self.turns,
+            self.drift_mean,
+            self.drift_min,
+            self.drift_max,
+            self.res_mean,
+            self.res_min,
+            self.res_max,
+            tones,
+            self.total_latency_ms
+        )
+    }
+}
+
+pub fn summarize(snaps: &[Snapshot]) -> SessionSummary {
+    if snaps.is_empty() {
+        return SessionSummary::default();
+    }
+
+    let turns = snaps.len();
+    let stats = |values: &mut dyn Iterator<Item = f32>| {
+        values.fold(
+            (0.0, f32::INFINITY, f32::NEG_INFINITY),
+            |(sum, lo, hi), v| (sum + v, lo.min(v), hi.max(v)),
+        )
+    };
+    let (drift_sum, drift_min, drift_max) = stats(&mut snaps.iter().map(|s| s.drift));
+    let (res_sum, res_min, res_max) = stats(&mut snaps.iter().map(|s| s.resonance));
+
+    let mut tones: BTreeMap<String, usize> = BTreeMap::new();
+    for snap in snaps {
+        *tones.entry(snap.tone.clone()).or_default() += 1;
+    }
+
+    SessionSummary {
+        turns,
+        drift_mean: drift_sum / turns as f32,
+        drift_min,
+        drift_max,
+        res_mean: res_sum / turns as f32,
+        res_min,
+        res_max,
+        tone_counts: tones.into_iter().collect(),
+        total_latency_ms: snaps.iter().map(|s| s.total_ms).sum(),
+    }
+}
+
+/// Write `session-<id>-summary.json` next to the session's JSONL log.
+pub fn write_summary(sess: &Session, summary: &SessionSummary) -> io::Result<PathBuf> {
+    let path = Path::new(&sess.log_dir).join(format!("session-{}-summary.json", sess.id));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format!("{}\n", summary.to_json()))?;
+    Ok(path)
+}
+
+const CSV_HEADER: &str =
+    "ts,device,drift,resonance,wpm,articulation,tone,asr_ms,tts_ms,total_ms,guard,state";
+
+/// Write the scalar fields of `snaps` as CSV, one row per snapshot.
+/// Missing guard/state values are left empty.
+pub fn export_csv(snaps: &[Snapshot], path: &str) -> io::Result<()> {
+    let path = Path::new(path);
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for snap in snaps {
+        let row = format!(
+            "{},{},{:.3},{:.3},{:.3},{:.3},{},{},{},{},{},{}\n",
+            csv_field(&snap.ts),
+            csv_field(&snap.device),
+            snap.drift,
+            snap.resonance,
+            snap.wpm,
+            snap.articulation,
+            csv_field(&snap.tone),
+            snap.asr_ms,
+            snap.tts_ms,
+            snap.total_ms,
+            csv_field(snap.guard.as_deref().unwrap_or("")),
+            csv_field(snap.state.as_deref().unwrap_or(""))
+        );
+        out.push_str(&row);
+    }
+    fs::write(path, out)
+}
+
+/// Load a session log back into snapshots. Lines that don't parse, or that
+/// carry a `"schema"` newer than [`Snapshot::SCHEMA`], are skipped with a
+/// warning on stderr; untagged lines count as schema 1 and missing optional
+/// fields become `None`.
+pub fn read_log(path: &str) -> io::Result<Vec<Snapshot>> {
+    let contents = fs::read_to_string(path)?;
+    let mut snaps = Vec::new();
+    for (lineno, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(fields) = parse_json_object(line) else {
+            eprintln!(
+                "[session] skipping malformed line {} in {}",
+                lineno + 1,
+                path
+            );
+            continue;
+        };
+        let schema = fields
+            .iter()
+            .find(|(k, _)| k == "schema")
+            .and_then(|(_, v)| v.as_f64())
+            .map_or(1, |v| v as u32);
+        if schema > Snapshot::SCHEMA {
+            eprintln!(
+                "[session] skipping line {} in {}: schema {} is newer than {}",
+                lineno + 1,
+                path,
+                schema,
+                Snapshot::SCHEMA
+            );
+            continue;
+        }
+        match parse_snapshot(&fields) {
+            Some(snap) => snaps.push(snap),
+            None => eprintln!(
+                "[session] skipping malformed line {} in {}",
+                lineno + 1,
+                path
+            ),
+        }
+    }
+    Ok(snaps)
+}
+
+fn parse_snapshot(fields: &[(String, JsonValue)]) -> Option<Snapshot> {
+    let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+    let num = |key: &str| get(key)?.as_f64();
+    let text = |key: &str| get(key)?.as_str().map(str::to_string);
+    let opt_f32 = |key: &str| num(key).map(|v| v as f32);
+
+    let sync = match get("sync") {
+        Some(JsonValue::Obj(delta)) => {
+            let field = |key: &str| {
+                delta
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .and_then(|(_, v)| v.as_f64())
+            };
+            Some(SyncDelta {
+                pace_delta: field("pace_delta")? as f32,
+                pause_delta_ms: field("pause_delta")? as i64,
+                res_boost: field("res_boost")? as f32,
+                drift_relief: field("drift_relief")? as f32,
+            })
+        }
+        _ => None,
+    };
+
+    Some(Snapshot {
+        ts: text("ts")?,
+        device: text("device")?,
+        drift: num("drift")? as f32,
+        resonance: num("resonance")? as f32,
+        wpm: num("wpm")? as f32,
+        articulation: num("articulation")? as f32,
+        tone: text("tone")?,
+        asr_ms: num("asr_ms")? as u128,
+        tts_ms: num("tts_ms")? as u128,
+        total_ms: num("total_ms")? as u128,
+        idx: num("idx")? as usize,
+        utterance: text("utt")?,
+        guard: text("guard"),
+        state: text("state"),
+        emote_state: text("emote_state"),
+        sync,
+        meta_self_drift: opt_f32("meta_self_drift"),
+        meta_self_resonance: opt_f32("meta_self_resonance"),
+        meta_confidence: opt_f32("meta_confidence"),
+        meta_clarity: opt_f32("meta_clarity"),
+        meta_doubt: opt_f32("meta_doubt"),
+        compassion_suffering: opt_f32("compassion_suffering"),
+        compassion_type: text("compassion_type"),
+        compassion_kindness: opt_f32("compassion_kindness"),
+        compassion_healing: opt_f32("compassion_healing"),
+        compassion_level: opt_f32("compassion_level"),
+    })
+}
+
+/// Just enough JSON for the JSONL files this crate writes: objects, arrays,
+/// strings, numbers and null.
+pub(crate) enum JsonValue {
+    Null,
+    Num(f64),
+    Str(String),
+    Arr(Vec<JsonValue>),
+    Obj(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Num(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Elements of a numeric array; `None` if any element is not a number.
+    pub(crate) fn as_f32_list(&self) -> Option<Vec<f32>> {
+        match self {
+            JsonValue::Arr(items) => items.iter().map(|v| v.as_f64().map(|v| v as f32)).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of a single-object JSON line, in file order.
+pub(crate) fn parse_json_object(line: &str) -> Option<Vec<(String, JsonValue)>> {
+    JsonParser::new(line).parse_document()
+}
+
+struct JsonParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars().peekable(),
+        }
+    }
+
+    /// A single top-level object with nothing but whitespace after it.
+    fn parse_document(&mut self) -> Option<Vec<(String, JsonValue)>> {
+        let fields = match self.parse_value()? {
+            JsonValue::Obj(fields) => fields,
+            _ => return None,
+        };
+        self.skip_ws();
+        self.chars.peek().is_none().then_some(fields)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+        }
+    }
+
+    fn expect(&mut self, want: char) -> Option<()> {
+        self.skip_ws();
+        (self.chars.next()? == want).then_some(())
+    }
+
+    fn parse_value(&mut self) -> Option<JsonValue> {
+        self.skip_ws();
+        match *self.chars.peek()? {
+            '{' => self.parse_object(),
+            '[' => self.parse_array(),
+            '"' => self.parse_string().map(JsonValue::Str),
+            'n' => self.parse_literal("null", JsonValue::Null),
+            _ => self.parse_number(),
+        }
+    }
+
+    fn parse_object(&mut self) -> Option<JsonValue> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_ws();
+        if self.chars.peek() == Some(&'}') {
+            self.chars.next();
+            return Some(JsonValue::Obj(fields));
+        }
+        loop {
+            self.skip_ws();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            self.skip_ws();
+            match self.chars.next()? {
+                ',' => continue,
+                '}' => return Some(JsonValue::Obj(fields)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Option<JsonValue> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_ws();
+        if self.chars.peek() == Some(&']') {
+            self.chars.next();
+            return Some(JsonValue::Arr(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_ws();
+            match self.chars.next()? {
+                ',' => continue,
+                ']' => return Some(JsonValue::Arr(items)),
+                _ => return None,
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(out),
+                '\\' => match self.chars.next()? {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'u' => {
+                        let hex: String = self.chars.by_ref().take(4).collect();
+                        out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                    }
+                    other => out.push(other),
+                },
+                ch => out.push(ch),
+            }
+        }
+    }
+
+    fn parse_literal(&mut self, word: &str, value: JsonValue) -> Option<JsonValue> {
+        for want in word.chars() {
+            if self.chars.next()? != want {
+                return None;
+            }
+        }
+        Some(value)
+    }
+
+    fn parse_number(&mut self) -> Option<JsonValue> {
+        let mut raw = String::new();
+        while let Some(&ch) = self.chars.peek() {
+            if ch.is_ascii_digit() || matches!(ch, '-' | '+' | '.' | 'e' | 'E') {
+                raw.push(ch);
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        raw.parse().ok().map(JsonValue::Num)
     }
 }
 
@@ -149,7 +636,7 @@ fn session_path(sess: &Session) -> PathBuf {
     Path::new(&sess.log_dir).join(format!("session-{}.jsonl", sess.id))
 }
 
-fn escape_json(value: &str) -> String {
+pub(crate) fn escape_json(value: &str) -> String {
     let mut escaped = String::with_capacity(value.len());
     for ch in value.chars() {
         match ch {
@@ -164,22 +651,95 @@ fn escape_json(value: &str) -> String {
     escaped
 }
 
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `<nanos>-<pid>-<seq>` in hex: the clock keeps ids from different runs
+/// apart, the pid separates concurrent processes, and the per-process
+/// counter separates sessions started within the same clock tick.
 fn generate_id() -> String {
+    static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default();
     let nanos = now.as_nanos();
     let hex = format!("{:016x}", nanos);
     let len = hex.len();
-    hex[len.saturating_sub(8)..].to_string()
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    format!(
+        "{}-{:x}-{:x}",
+        &hex[len.saturating_sub(8)..],
+        std::process::id(),
+        seq
+    )
+}
+
+/// Current UTC time as RFC 3339 with millisecond precision, for snapshot
+/// timestamps.
+pub fn now_rfc3339() -> String {
+    let now = SystemTime::now();
+    let duration = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+    format_rfc3339(duration.as_secs(), duration.subsec_nanos())
+}
+
+fn format_rfc3339(seconds: u64, nanos: u32) -> String {
+    const SECONDS_PER_DAY: u64 = 86_400;
+
+    let days = (seconds / SECONDS_PER_DAY) as i64;
+    let secs_of_day = (seconds % SECONDS_PER_DAY) as u32;
+
+    let (year, month, day) = civil_from_days(days);
+
+    let hour = secs_of_day / 3_600;
+    let minute = (secs_of_day % 3_600) / 60;
+    let second = secs_of_day % 60;
+    let millis = nanos / 1_000_000;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, millis
+    )
+}
+
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let mut year = (yoe + era * 400) as i32;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let mut month = mp + if mp < 10 { 3 } else { -9 };
+    year += if month <= 2 { 1 } else { 0 };
+    if month <= 0 {
+        month += 12;
+    }
+
+    (year, month as u32, day as u32)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::escape_json;
+    use super::{escape_json, generate_id};
+    use std::collections::HashSet;
 
     #[test]
     fn escape_handles_quotes() {
         assert_eq!(escape_json("\"test\\"), "\\\"test\\\\");
     }
+
+    #[test]
+    fn ids_are_unique_in_a_tight_loop() {
+        let ids: HashSet<String> = (0..10_000).map(|_| generate_id()).collect();
+        assert_eq!(ids.len(), 10_000);
+        let safe = |id: &String| id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+        assert!(ids.iter().all(safe));
+    }
 }
@@ -1,16 +1,134 @@
 // Minimal soft guard heuristics
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::prosody::is_shouted;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct GuardConfig {
     pub drift_limit: f32,
     pub res_limit: f32,
     pub rephrase_factor: f32,
+    /// Words replaced with [`REDACTED`] whatever the drift/resonance.
+    /// Matching is case-insensitive and whole-word.
+    pub banned: Vec<String>,
+    pub strategy: RephraseStrategy,
+    /// How far below `res_limit` resonance may sink with acceptable drift
+    /// before the response gets a re-engaging rephrase.
+    pub low_res_margin: f32,
+    /// Warn about resonance inside that margin too; off, it passes as
+    /// [`GuardAction::None`].
+    pub warn_low_res: bool,
+    /// Drift beyond which a response is not voiced at all; `None` never
+    /// blocks on drift alone.
+    pub hard_drift_limit: Option<f32>,
+}
+
+/// Extra signals from the rest of the pipeline that shape a rephrase.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GuardContext {
+    /// Current `CompassionMetrics::compassion_level` (0 when compassion is off).
+    pub compassion_level: f32,
+}
+
+/// Compassion level from which rephrases open with [`SUPPORTIVE_CLAUSE`]
+/// instead of carrying the `[recentered]` marker.
+pub const WARM_COMPASSION_LEVEL: f32 = 0.5;
+
+pub const SUPPORTIVE_CLAUSE: &str = "Take your time —";
+
+/// Appended to disengaged (very low resonance) responses.
+pub const REENGAGE_CUE: &str = "— does that sound right to you?";
+
+/// How a low-resonance, high-drift response is rewritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RephraseStrategy {
+    /// Calm punctuation and tag the text `[recentered]`.
+    #[default]
+    Recenter,
+    /// Lowercase shouted words and drop intensifiers.
+    Soften,
+    /// Keep only the first sentence.
+    Shorten,
+}
+
+impl RephraseStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "recenter" => Some(RephraseStrategy::Recenter),
+            "soften" => Some(RephraseStrategy::Soften),
+            "shorten" => Some(RephraseStrategy::Shorten),
+            _ => None,
+        }
+    }
+}
+
+const INTENSIFIERS: &[&str] = &[
+    "very",
+    "really",
+    "extremely",
+    "totally",
+    "absolutely",
+    "literally",
+    "super",
+    "incredibly",
+];
+
+pub const REDACTED: &str = "[redacted]";
+
+/// Why the guard stepped in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardReason {
+    HighDrift,
+    LowResonance,
+    Both,
+    Banned,
+}
+
+impl GuardReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardReason::HighDrift => "high_drift",
+            GuardReason::LowResonance => "low_resonance",
+            GuardReason::Both => "both",
+            GuardReason::Banned => "banned",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum GuardAction {
     None,
-    Warn(String),
-    Rephrased(String),
+    Warn {
+        reason: GuardReason,
+        message: String,
+    },
+    Rephrased {
+        reason: GuardReason,
+        text: String,
+    },
+    /// Too far gone to voice: extreme drift, or mostly banned words.
+    Block(GuardReason),
+}
+
+impl GuardAction {
+    pub fn reason(&self) -> Option<GuardReason> {
+        match self {
+            GuardAction::None => None,
+            GuardAction::Warn { reason, .. }
+            | GuardAction::Rephrased { reason, .. }
+            | GuardAction::Block(reason) => Some(*reason),
+        }
+    }
+
+    /// Session-log label such as `"warn:high_drift"`; `None` when nothing fired.
+    pub fn label(&self) -> Option<String> {
+        let kind = match self {
+            GuardAction::None => return None,
+            GuardAction::Warn { .. } => "warn",
+            GuardAction::Rephrased { .. } => "rephrased",
+            GuardAction::Block(_) => "blocked",
+        };
+        self.reason()
+            .map(|reason| format!("{}:{}", kind, reason.as_str()))
+    }
 }
 
 impl Default for GuardConfig {
@@ -19,33 +137,271 @@ impl Default for GuardConfig {
             drift_limit: 0.40,
             res_limit: 0.60,
             rephrase_factor: 0.2,
+            banned: Vec::new(),
+            strategy: RephraseStrategy::Recenter,
+            low_res_margin: 0.15,
+            warn_low_res: false,
+            hard_drift_limit: None,
         }
     }
 }
 
+impl GuardConfig {
+    /// Thresholds loosened for a less certain system: `caution` of 1.0 keeps
+    /// them as-is, lower values raise the drift limit (up to the hard limit,
+    /// but never below the current one) and lower the resonance limit.
+    pub fn relaxed(&self, caution: f32) -> GuardConfig {
+        let caution = caution.clamp(0.1, 1.0);
+        GuardConfig {
+            drift_limit: (self.drift_limit / caution)
+                .min(self.hard_drift_limit.unwrap_or(1.0))
+                .max(self.drift_limit),
+            res_limit: self.res_limit * caution,
+            ..self.clone()
+        }
+    }
+}
+
+/// Session totals of guard interventions. Redactions are counted apart from
+/// drift/resonance rephrases, and blocks apart from both.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GuardStats {
+    pub warns: usize,
+    pub rephrases: usize,
+    pub redactions: usize,
+    pub blocks: usize,
+}
+
+impl GuardStats {
+    pub fn record(&mut self, action: &GuardAction) {
+        match action {
+            GuardAction::None => {}
+            GuardAction::Warn { .. } => self.warns += 1,
+            GuardAction::Rephrased {
+                reason: GuardReason::Banned,
+                ..
+            } => self.redactions += 1,
+            GuardAction::Rephrased { .. } => self.rephrases += 1,
+            GuardAction::Block(_) => self.blocks += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.warns + self.rephrases + self.redactions + self.blocks
+    }
+
+    pub fn summary_line(&self) -> String {
+        format!(
+            "[guard] interventions: warns={}, rephrases={}, redactions={}, blocks={}, total={}",
+            self.warns,
+            self.rephrases,
+            self.redactions,
+            self.blocks,
+            self.total()
+        )
+    }
+}
+
 pub fn check_and_rephrase(text: &str, drift: f32, res: f32, cfg: &GuardConfig) -> GuardAction {
-    use std::fmt::Write;
+    check_and_rephrase_with(text, drift, res, cfg, &GuardContext::default())
+}
 
-    if drift <= cfg.drift_limit && res >= cfg.res_limit {
-        return GuardAction::None;
+/// [`check_and_rephrase`] that also weighs pipeline context; at
+/// [`WARM_COMPASSION_LEVEL`] and above, rephrases are warmer.
+pub fn check_and_rephrase_with(
+    text: &str,
+    drift: f32,
+    res: f32,
+    cfg: &GuardConfig,
+    ctx: &GuardContext,
+) -> GuardAction {
+    let warm = ctx.compassion_level >= WARM_COMPASSION_LEVEL;
+    let high_drift = drift > cfg.drift_limit;
+    let low_res = res < cfg.res_limit;
+
+    if cfg.hard_drift_limit.is_some_and(|hard| drift > hard) {
+        return GuardAction::Block(GuardReason::HighDrift);
     }
 
-    if drift > cfg.drift_limit && res >= cfg.res_limit {
-        let mut msg = String::new();
-        write!(
-            &mut msg,
-            "[soft-guard] high drift {:.2} → adjusting tone",
-            drift
+    let (clean, redacted, words) = redact(text, &cfg.banned);
+    if redacted * 2 > words {
+        return GuardAction::Block(GuardReason::Banned);
+    }
+    if redacted > 0 {
+        let text = if high_drift && low_res {
+            rephrase_in_context(&clean, cfg.strategy, warm)
+        } else {
+            clean
+        };
+        return GuardAction::Rephrased {
+            reason: GuardReason::Banned,
+            text,
+        };
+    }
+
+    match (high_drift, low_res) {
+        (false, false) => GuardAction::None,
+        (true, false) => GuardAction::Warn {
+            reason: GuardReason::HighDrift,
+            message: format!("[soft-guard] high drift {:.2} → adjusting tone", drift),
+        },
+        (false, true) if res < cfg.res_limit - cfg.low_res_margin => GuardAction::Rephrased {
+            reason: GuardReason::LowResonance,
+            text: format!("{} {}", text.trim(), REENGAGE_CUE),
+        },
+        (false, true) if cfg.warn_low_res => GuardAction::Warn {
+            reason: GuardReason::LowResonance,
+            message: format!("[soft-guard] low resonance {:.2} → re-engaging", res),
+        },
+        (false, true) => GuardAction::None,
+        (true, true) => GuardAction::Rephrased {
+            reason: GuardReason::Both,
+            text: rephrase_in_context(text, cfg.strategy, warm),
+        },
+    }
+}
+
+/// Rewrites `text` with `strategy`. `Recenter` and `Soften` only touch the
+/// offending sentences (exclamations or shouting), falling back to the whole
+/// text when none stands out; `Shorten` keeps the first sentence.
+pub fn rephrase(text: &str, strategy: RephraseStrategy) -> String {
+    rephrase_tagged(text, strategy, true)
+}
+
+fn rephrase_in_context(text: &str, strategy: RephraseStrategy, warm: bool) -> String {
+    if warm {
+        format!(
+            "{} {}",
+            SUPPORTIVE_CLAUSE,
+            rephrase_tagged(text, strategy, false)
         )
-        .ok();
-        return GuardAction::Warn(msg);
+    } else {
+        rephrase_tagged(text, strategy, true)
+    }
+}
+
+fn rephrase_tagged(text: &str, strategy: RephraseStrategy, tag: bool) -> String {
+    let t = text.trim();
+    if strategy == RephraseStrategy::Shorten {
+        return split_sentences(t).first().copied().unwrap_or(t).to_string();
+    }
+
+    let sentences = split_sentences(t);
+    if !sentences.iter().any(|s| is_offending(s)) {
+        return rephrase_sentence(t, strategy, tag);
     }
+    sentences
+        .iter()
+        .map(|s| {
+            if is_offending(s) {
+                rephrase_sentence(s, strategy, tag)
+            } else {
+                s.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits on `.`, `?` and `!`, keeping each sentence's trailing punctuation.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((idx, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        let mut end = idx + c.len_utf8();
+        while let Some(&(next_idx, next)) = chars.peek() {
+            if !matches!(next, '.' | '!' | '?') {
+                break;
+            }
+            end = next_idx + next.len_utf8();
+            chars.next();
+        }
+        let sentence = text[start..end].trim();
+        if !sentence.is_empty() {
+            sentences.push(sentence);
+        }
+        start = end;
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+fn is_offending(sentence: &str) -> bool {
+    sentence.contains('!') || sentence.split_whitespace().any(is_shouted)
+}
 
-    let mut t = text.trim().to_string();
-    if drift > cfg.drift_limit && res < cfg.res_limit {
-        t = format!("{} [recentered]", t.replace("!", ".").replace("  ", " "));
-        return GuardAction::Rephrased(t);
+fn rephrase_sentence(sentence: &str, strategy: RephraseStrategy, tag: bool) -> String {
+    match strategy {
+        RephraseStrategy::Recenter => {
+            let calmed = sentence.replace("!", ".").replace("  ", " ");
+            if tag {
+                format!("{} [recentered]", calmed)
+            } else {
+                calmed
+            }
+        }
+        RephraseStrategy::Soften => sentence
+            .split_whitespace()
+            .filter(|w| {
+                let bare = w.trim_matches(|c: char| !c.is_alphanumeric());
+                !INTENSIFIERS.contains(&bare.to_lowercase().as_str())
+            })
+            .map(|w| {
+                let shouted = is_shouted(w);
+                let w = w.replace('!', ".");
+                if shouted { w.to_lowercase() } else { w }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        RephraseStrategy::Shorten => sentence.to_string(),
+    }
+}
+
+/// Replaces each whole word found in `banned` (case-insensitive) with
+/// [`REDACTED`]. Returns `None` when nothing matched.
+pub fn redact_banned(text: &str, banned: &[String]) -> Option<String> {
+    let (out, redacted, _) = redact(text, banned);
+    (redacted > 0).then_some(out)
+}
+
+/// Redacted text plus the number of redacted and total words.
+fn redact(text: &str, banned: &[String]) -> (String, usize, usize) {
+    if banned.is_empty() {
+        return (text.to_string(), 0, 0);
+    }
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '\'';
+    let mut out = String::with_capacity(text.len());
+    let mut redacted = 0;
+    let mut words = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find(is_word_char) {
+        out.push_str(&rest[..start]);
+        let word_rest = &rest[start..];
+        let end = word_rest
+            .find(|c: char| !is_word_char(c))
+            .unwrap_or(word_rest.len());
+        let word = &word_rest[..end];
+        words += 1;
+        if banned
+            .iter()
+            .any(|b| b.trim().to_lowercase() == word.to_lowercase())
+        {
+            out.push_str(REDACTED);
+            redacted += 1;
+        } else {
+            out.push_str(word);
+        }
+        rest = &word_rest[end..];
     }
+    out.push_str(rest);
 
-    GuardAction::None
+    (out, redacted, words)
 }
@@ -1,20 +1,106 @@
 use crate::metrics;
 
 pub static GLYPHS: &[char] = &[' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// Same number of steps as `GLYPHS`, for terminals without Unicode.
+pub static ASCII_GLYPHS: &[char] = &['.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Every non-ASCII symbol the console output uses, in one place.
+#[derive(Debug)]
+pub struct Glyphs {
+    pub ramp: &'static [char],
+    /// Shading steps for heatmaps, lightest first.
+    pub shade: &'static [char],
+    pub ok: &'static str,
+    pub warning: &'static str,
+    pub heart: &'static str,
+    pub support: &'static str,
+    pub candle: &'static str,
+    pub rising: &'static str,
+    pub falling: &'static str,
+    pub flat: &'static str,
+}
+
+static UNICODE_SET: Glyphs = Glyphs {
+    ramp: GLYPHS,
+    shade: &[' ', '░', '▒', '▓', '█'],
+    ok: "✅",
+    warning: "⚠️",
+    heart: "❤️",
+    support: "💝",
+    candle: "🕯️",
+    rising: "↑",
+    falling: "↓",
+    flat: "→",
+};
+
+static ASCII_SET: Glyphs = Glyphs {
+    ramp: ASCII_GLYPHS,
+    shade: &[' ', '.', ':', '*', '#'],
+    ok: "[ok]",
+    warning: "[!]",
+    heart: "[<3]",
+    support: "[<3]",
+    candle: "[~]",
+    rising: "^",
+    falling: "v",
+    flat: "-",
+};
+
+/// The glyph set for the current output mode (`--ascii`).
+pub fn glyphs(ascii: bool) -> &'static Glyphs {
+    if ascii { &ASCII_SET } else { &UNICODE_SET }
+}
 
 pub fn sparkline(values: &[f32]) -> String {
+    sparkline_for(values, false)
+}
+
+/// `sparkline` drawn with the ramp from `glyphs(ascii)`.
+pub fn sparkline_for(values: &[f32], ascii: bool) -> String {
     if values.is_empty() {
         return String::new();
     }
 
-    let max_index = (GLYPHS.len() - 1) as f32;
+    let ramp = glyphs(ascii).ramp;
+    let max_index = (ramp.len() - 1) as f32;
     values
         .iter()
         .map(|v| {
             let clamped = metrics::clamp01(*v);
             let idx = (clamped * max_index).round() as usize;
-            let idx = idx.min(GLYPHS.len() - 1);
-            GLYPHS[idx]
+            let idx = idx.min(ramp.len() - 1);
+            ramp[idx]
         })
         .collect::<String>()
 }
+
+/// Ranges narrower than this fall back to the fixed 0..1 scale.
+const MIN_SCALE_RANGE: f32 = 1e-3;
+
+/// Like `sparkline`, but stretched to the series' own min..max so small
+/// variations stay visible. The lowest value still gets a visible bar.
+pub fn sparkline_scaled(values: &[f32]) -> String {
+    sparkline_scaled_for(values, false)
+}
+
+/// `sparkline_scaled` drawn with the ramp from `glyphs(ascii)`.
+pub fn sparkline_scaled_for(values: &[f32], ascii: bool) -> String {
+    let finite = values.iter().copied().filter(|v| v.is_finite());
+    let (lo, hi) = finite.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if hi - lo < MIN_SCALE_RANGE {
+        return sparkline_for(values, ascii);
+    }
+
+    let ramp = glyphs(ascii).ramp;
+    let steps = (ramp.len() - 2) as f32;
+    values
+        .iter()
+        .map(|v| {
+            let norm = ((v - lo) / (hi - lo)).clamp(0.0, 1.0);
+            let idx = 1 + (norm * steps).round() as usize;
+            ramp[idx.min(ramp.len() - 1)]
+        })
+        .collect()
+}
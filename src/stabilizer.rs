@@ -1,4 +1,9 @@
 use std::cmp;
+use std::fs;
+use std::io;
+
+use crate::session::parse_json_object;
+use crate::utils;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EmoState {
@@ -6,6 +11,21 @@ pub enum EmoState {
     Warming,
     Overheat,
     Cooldown,
+    /// Sustained extreme drift: hard intervention until drift falls back.
+    Panic,
+}
+
+impl EmoState {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Normal" => Some(EmoState::Normal),
+            "Warming" => Some(EmoState::Warming),
+            "Overheat" => Some(EmoState::Overheat),
+            "Cooldown" => Some(EmoState::Cooldown),
+            "Panic" => Some(EmoState::Panic),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -17,6 +37,15 @@ pub struct StabilizerCfg {
     pub low_res: f32,
     pub cool_steps: usize,
     pub calm_boost: f32,
+    /// Drift that, held for `cool_steps` consecutive pushes, escalates to
+    /// `Panic`.
+    pub panic_drift: f32,
+    /// Once `Warming`, drift must fall below `warm_drift - hysteresis` to
+    /// return to `Normal`, so a signal hovering at the threshold does not flap.
+    pub hysteresis: f32,
+    /// Drive state transitions from the windowed median instead of the latest
+    /// sample, so a lone spike cannot trip `Overheat`. EMA is unaffected.
+    pub use_median: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -26,12 +55,29 @@ pub struct Stabilizer {
     pub steps_in_state: usize,
     pub ema_drift: f32,
     pub ema_res: f32,
+    pub advice_table: AdviceTable,
     ring_drift: Vec<f32>,
     ring_res: Vec<f32>,
     idx: usize,
+    filled: usize,
+    extreme_streak: usize,
     initialized: bool,
 }
 
+/// Persistable stabilizer state. Ring samples are stored oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabSnapshot {
+    pub state: EmoState,
+    pub steps_in_state: usize,
+    pub ema_drift: f32,
+    pub ema_res: f32,
+    pub ring_drift: Vec<f32>,
+    pub ring_res: Vec<f32>,
+    /// Consecutive pushes at or above `panic_drift`, so a restart mid-run
+    /// keeps counting toward `Panic`.
+    pub extreme_streak: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Advice {
     pub pace_delta: f32,
@@ -39,6 +85,60 @@ pub struct Advice {
     pub articulation_hint: f32,
 }
 
+/// Base [`Advice`] per [`EmoState`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdviceTable {
+    pub normal: Advice,
+    pub warming: Advice,
+    pub overheat: Advice,
+    pub cooldown: Advice,
+    pub panic: Advice,
+}
+
+impl AdviceTable {
+    pub fn for_state(&self, state: EmoState) -> Advice {
+        match state {
+            EmoState::Normal => self.normal,
+            EmoState::Warming => self.warming,
+            EmoState::Overheat => self.overheat,
+            EmoState::Cooldown => self.cooldown,
+            EmoState::Panic => self.panic,
+        }
+    }
+}
+
+impl Default for AdviceTable {
+    fn default() -> Self {
+        Self {
+            normal: Advice {
+                pace_delta: 0.0,
+                pause_delta_ms: 0,
+                articulation_hint: 0.0,
+            },
+            warming: Advice {
+                pace_delta: -0.03,
+                pause_delta_ms: 10,
+                articulation_hint: 0.02,
+            },
+            overheat: Advice {
+                pace_delta: -0.07,
+                pause_delta_ms: 30,
+                articulation_hint: 0.05,
+            },
+            cooldown: Advice {
+                pace_delta: -0.04,
+                pause_delta_ms: 20,
+                articulation_hint: 0.03,
+            },
+            panic: Advice {
+                pace_delta: -0.15,
+                pause_delta_ms: 80,
+                articulation_hint: 0.08,
+            },
+        }
+    }
+}
+
 impl Stabilizer {
     pub fn new(mut cfg: StabilizerCfg) -> Self {
         cfg.win = cfg.win.max(1);
@@ -48,17 +148,22 @@ impl Stabilizer {
         cfg.low_res = cfg.low_res.clamp(0.0, 1.0);
         cfg.cool_steps = cfg.cool_steps.max(1);
         cfg.calm_boost = cfg.calm_boost.clamp(0.0, 0.2);
+        cfg.panic_drift = cfg.panic_drift.clamp(0.0, 1.0);
+        cfg.hysteresis = cfg.hysteresis.clamp(0.0, cfg.warm_drift);
 
         Self {
             ring_drift: vec![0.0; cfg.win],
             ring_res: vec![0.0; cfg.win],
             idx: 0,
+            filled: 0,
+            extreme_streak: 0,
             initialized: false,
             cfg,
             state: EmoState::Normal,
             steps_in_state: 0,
             ema_drift: 0.0,
             ema_res: 0.0,
+            advice_table: AdviceTable::default(),
         }
     }
 
@@ -73,6 +178,7 @@ impl Stabilizer {
         self.ring_drift[self.idx] = drift;
         self.ring_res[self.idx] = res;
         self.idx = (self.idx + 1) % self.ring_drift.len();
+        self.filled = cmp::min(self.filled + 1, self.ring_drift.len());
         if !self.initialized {
             self.ema_drift = drift;
             self.ema_res = res;
@@ -86,9 +192,42 @@ impl Stabilizer {
         self.ema_drift = self.ema_drift.clamp(0.0, 1.0);
         self.ema_res = self.ema_res.clamp(0.0, 1.0);
 
-        let next_state = if drift >= self.cfg.hot_drift && res <= self.cfg.low_res {
+        let (drift, res) = if self.cfg.use_median {
+            (
+                median(&self.ring_drift[..self.filled]),
+                median(&self.ring_res[..self.filled]),
+            )
+        } else {
+            (drift, res)
+        };
+
+        if drift >= self.cfg.panic_drift {
+            self.extreme_streak += 1;
+        } else {
+            self.extreme_streak = 0;
+        }
+
+        let next_state = if self.extreme_streak >= self.cfg.cool_steps {
+            EmoState::Panic
+        } else if self.state == EmoState::Panic {
+            // Panic always decays through a full Cooldown.
+            EmoState::Cooldown
+        } else if drift >= self.cfg.hot_drift && res <= self.cfg.low_res {
             EmoState::Overheat
-        } else if drift >= self.cfg.warm_drift {
+        } else if self.state == EmoState::Cooldown {
+            // Cooldown runs its counter out even if drift lingers in the
+            // warming band, then hands over to whichever state fits.
+            if self.steps_in_state + 1 < self.cfg.cool_steps {
+                EmoState::Cooldown
+            } else if drift >= self.cfg.warm_drift {
+                EmoState::Warming
+            } else {
+                EmoState::Normal
+            }
+        } else if drift >= self.cfg.warm_drift
+            || (self.state == EmoState::Warming
+                && drift >= self.cfg.warm_drift - self.cfg.hysteresis)
+        {
             EmoState::Warming
         } else {
             match self.state {
@@ -99,13 +238,6 @@ impl Stabilizer {
                         EmoState::Normal
                     }
                 }
-                EmoState::Cooldown => {
-                    if self.steps_in_state + 1 >= self.cfg.cool_steps {
-                        EmoState::Normal
-                    } else {
-                        EmoState::Cooldown
-                    }
-                }
                 _ => EmoState::Normal,
             }
         };
@@ -121,37 +253,176 @@ impl Stabilizer {
         }
     }
 
+    /// Pushes left before `Cooldown` hands over to `Normal` or `Warming`;
+    /// `None` in any other state.
+    pub fn cooldown_remaining(&self) -> Option<usize> {
+        (self.state == EmoState::Cooldown)
+            .then(|| self.cfg.cool_steps.saturating_sub(self.steps_in_state))
+    }
+
+    /// Clears the window, EMAs and state while keeping `cfg` and the advice
+    /// table, so the stabilizer can be replayed without reallocating.
+    pub fn reset(&mut self) {
+        self.ring_drift.fill(0.0);
+        self.ring_res.fill(0.0);
+        self.idx = 0;
+        self.filled = 0;
+        self.extreme_streak = 0;
+        self.initialized = false;
+        self.state = EmoState::Normal;
+        self.steps_in_state = 0;
+        self.ema_drift = 0.0;
+        self.ema_res = 0.0;
+    }
+
+    /// Window samples `(drift, res)` oldest first; slots not yet written are
+    /// left out.
+    pub fn history(&self) -> (Vec<f32>, Vec<f32>) {
+        let len = self.ring_drift.len();
+        let start = if self.filled < len { 0 } else { self.idx };
+        let ordered = |ring: &[f32]| {
+            (0..self.filled)
+                .map(|i| ring[(start + i) % len])
+                .collect::<Vec<_>>()
+        };
+        (ordered(&self.ring_drift), ordered(&self.ring_res))
+    }
+
+    pub fn snapshot(&self) -> StabSnapshot {
+        let (ring_drift, ring_res) = self.history();
+        StabSnapshot {
+            state: self.state,
+            steps_in_state: self.steps_in_state,
+            ema_drift: self.ema_drift,
+            ema_res: self.ema_res,
+            ring_drift,
+            ring_res,
+            extreme_streak: self.extreme_streak,
+        }
+    }
+
+    /// Rebuilds a stabilizer from `snap`. When the window shrank, only the most
+    /// recent samples are kept.
+    pub fn restore(cfg: StabilizerCfg, snap: &StabSnapshot) -> Self {
+        let mut stab = Self::new(cfg);
+        let len = stab.ring_drift.len();
+        let samples = snap.ring_drift.len().min(snap.ring_res.len());
+        let skip = samples.saturating_sub(len);
+        for i in skip..samples {
+            stab.ring_drift[stab.idx] = snap.ring_drift[i].clamp(0.0, 1.0);
+            stab.ring_res[stab.idx] = snap.ring_res[i].clamp(0.0, 1.0);
+            stab.idx = (stab.idx + 1) % len;
+            stab.filled = cmp::min(stab.filled + 1, len);
+        }
+        stab.state = snap.state;
+        stab.steps_in_state = snap.steps_in_state;
+        stab.extreme_streak = snap.extreme_streak;
+        stab.ema_drift = snap.ema_drift.clamp(0.0, 1.0);
+        stab.ema_res = snap.ema_res.clamp(0.0, 1.0);
+        stab.initialized = true;
+        stab
+    }
+
+    /// Advice for the current state from [`Stabilizer::advice_table`], with
+    /// `calm_boost` folded into `Overheat` and `Panic`.
     pub fn advice(&self) -> Advice {
+        let mut advice = self.advice_table.for_state(self.state);
         match self.state {
-            EmoState::Normal => Advice {
-                pace_delta: 0.0,
-                pause_delta_ms: 0,
-                articulation_hint: 0.0,
-            },
-            EmoState::Warming => Advice {
-                pace_delta: -0.03,
-                pause_delta_ms: 10,
-                articulation_hint: 0.02,
-            },
-            EmoState::Overheat => Advice {
-                pace_delta: -0.07 - self.cfg.calm_boost,
-                pause_delta_ms: 30 + (self.cfg.calm_boost * 100.0).round() as i64,
-                articulation_hint: 0.05,
-            },
-            EmoState::Cooldown => Advice {
-                pace_delta: -0.04,
-                pause_delta_ms: 20,
-                articulation_hint: 0.03,
-            },
+            EmoState::Overheat => {
+                advice.pace_delta -= self.cfg.calm_boost;
+                advice.pause_delta_ms += (self.cfg.calm_boost * 100.0).round() as i64;
+            }
+            EmoState::Panic => {
+                advice.pace_delta -= self.cfg.calm_boost;
+                advice.pause_delta_ms += (self.cfg.calm_boost * 200.0).round() as i64;
+            }
+            _ => {}
         }
+        advice
     }
 }
 
-pub fn format_status(state: EmoState, ema_drift: f32, ema_res: f32) -> String {
-    format!(
+impl StabSnapshot {
+    pub fn to_json_line(&self) -> String {
+        let list = |values: &[f32]| {
+            values
+                .iter()
+                .map(|v| format!("{:.6}", v))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        format!(
+            "{{\"state\":\"{:?}\",\"steps_in_state\":{},\"ema_drift\":{:.6},\"ema_res\":{:.6},\"ring_drift\":[{}],\"ring_res\":[{}],\"extreme_streak\":{}}}",
+            self.state,
+            self.steps_in_state,
+            self.ema_drift,
+            self.ema_res,
+            list(&self.ring_drift),
+            list(&self.ring_res),
+            self.extreme_streak
+        )
+    }
+
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        let fields = parse_json_object(line)?;
+        let get = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+        let num = |key: &str| get(key)?.as_f64();
+        Some(Self {
+            state: EmoState::parse(get("state")?.as_str()?)?,
+            steps_in_state: num("steps_in_state")? as usize,
+            ema_drift: num("ema_drift")? as f32,
+            ema_res: num("ema_res")? as f32,
+            ring_drift: get("ring_drift")?.as_f32_list()?,
+            ring_res: get("ring_res")?.as_f32_list()?,
+            extreme_streak: num("extreme_streak").map_or(0, |v| v as usize),
+        })
+    }
+}
+
+/// Latest parseable snapshot in a JSONL file.
+pub fn load_snapshot(path: &str) -> Option<StabSnapshot> {
+    let txt = fs::read_to_string(path).ok()?;
+    txt.lines()
+        .rev()
+        .find_map(|line| StabSnapshot::from_json_line(line.trim()))
+}
+
+/// Replace the file at `path` with `snap` as its only line.
+pub fn save_snapshot(path: &str, snap: &StabSnapshot) -> io::Result<()> {
+    utils::write_atomic(path, &format!("{}\n", snap.to_json_line()))
+}
+
+fn median(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+pub fn format_status(
+    state: EmoState,
+    ema_drift: f32,
+    ema_res: f32,
+    cooldown_remaining: Option<usize>,
+) -> String {
+    let mut status = format!(
         "[stabilizer] state={:?} ema_drift={:.2} ema_res={:.2}",
         state,
         ema_drift.clamp(0.0, 1.0),
         ema_res.clamp(0.0, 1.0)
-    )
+    );
+    if let Some(remaining) = cooldown_remaining {
+        status.push_str(&format!(" cooldown_remaining={}", remaining));
+    }
+    if state == EmoState::Panic {
+        status.push_str(" → hard intervention");
+    }
+    status
 }
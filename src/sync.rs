@@ -1,4 +1,10 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+
+use crate::session::parse_json_object;
 use crate::stabilizer::EmoState;
+use crate::utils;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Baselines {
@@ -6,7 +12,7 @@ pub struct Baselines {
     pub res: f32,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Seeds {
     pub pace_bias: f32,
     pub pause_bias_ms: i64,
@@ -14,7 +20,10 @@ pub struct Seeds {
     pub drift_soft: f32,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+/// Default number of residuals kept by `SyncState` for diagnostics.
+pub const RESIDUAL_HISTORY: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Residual {
     pub d_drift: f32,
     pub d_res: f32,
@@ -24,6 +33,62 @@ pub struct SyncCfg {
     pub lr_fast: f32,
     pub lr_slow: f32,
     pub clamp_step: f32,
+    /// Share of the previous pace/pause correction carried into the next one;
+    /// `0.0` is a purely proportional response.
+    pub momentum: f32,
+    /// Per-state overrides of `lr_fast`; `None` falls back to `lr_fast`.
+    /// `Panic` uses the Overheat rate.
+    pub lr_warming: Option<f32>,
+    pub lr_overheat: Option<f32>,
+    pub lr_cooldown: Option<f32>,
+    /// Forgetting factor applied to the accumulators each step; `1.0` keeps
+    /// a plain running sum.
+    pub accum_decay: f32,
+    /// Bounds for the per-turn pause correction, in milliseconds.
+    pub pause_clamp_ms: (i64, i64),
+    /// Upper bound for `res_boost` and `drift_relief`; `None` falls back to
+    /// `clamp_step`.
+    pub boost_clamp: Option<f32>,
+}
+
+impl Default for SyncCfg {
+    fn default() -> Self {
+        Self {
+            lr_fast: 0.15,
+            lr_slow: 0.05,
+            clamp_step: 0.02,
+            momentum: 0.0,
+            lr_warming: None,
+            lr_overheat: None,
+            lr_cooldown: None,
+            accum_decay: 1.0,
+            pause_clamp_ms: (-20, 40),
+            boost_clamp: None,
+        }
+    }
+}
+
+impl SyncCfg {
+    /// Fast learning rate for the given stabilizer state.
+    pub fn lr_for(&self, state: EmoState) -> f32 {
+        let lr = match state {
+            EmoState::Normal => None,
+            EmoState::Warming => self.lr_warming,
+            EmoState::Overheat | EmoState::Panic => self.lr_overheat,
+            EmoState::Cooldown => self.lr_cooldown,
+        };
+        lr.unwrap_or(self.lr_fast)
+    }
+
+    /// Effective sample count behind decayed accumulators after `steps`.
+    pub fn effective_count(&self, steps: usize) -> f32 {
+        let decay = self.accum_decay.clamp(0.0, 1.0);
+        if decay >= 1.0 {
+            steps as f32
+        } else {
+            (1.0 - decay.powi(steps as i32)) / (1.0 - decay)
+        }
+    }
 }
 
 pub struct SyncState {
@@ -32,6 +97,12 @@ pub struct SyncState {
     pub accum_drift: f32,
     pub accum_res: f32,
     pub steps: usize,
+    /// Previous pace/pause corrections, before state-specific adjustments.
+    pub pace_velocity: f32,
+    pub pause_velocity: f32,
+    /// Maximum residuals retained for diagnostics; `0` disables the history.
+    pub residual_capacity: usize,
+    residuals: VecDeque<Residual>,
 }
 
 impl Default for SyncState {
@@ -45,6 +116,10 @@ impl Default for SyncState {
             accum_drift: 0.0,
             accum_res: 0.0,
             steps: 0,
+            pace_velocity: 0.0,
+            pause_velocity: 0.0,
+            residual_capacity: RESIDUAL_HISTORY,
+            residuals: VecDeque::new(),
         }
     }
 }
@@ -56,6 +131,9 @@ impl SyncState {
         self.accum_drift = 0.0;
         self.accum_res = 0.0;
         self.steps = 0;
+        self.pace_velocity = 0.0;
+        self.pause_velocity = 0.0;
+        self.residuals.clear();
     }
 
     pub fn step(
@@ -70,22 +148,36 @@ impl SyncState {
             d_res: (self.baselines.res - res).clamp(-1.0, 1.0),
         };
 
-        self.accum_drift += r.d_drift;
-        self.accum_res += r.d_res;
+        let decay = cfg.accum_decay.clamp(0.0, 1.0);
+        self.accum_drift = self.accum_drift * decay + r.d_drift;
+        self.accum_res = self.accum_res * decay + r.d_res;
         self.steps += 1;
+        if self.residual_capacity > 0 {
+            while self.residuals.len() >= self.residual_capacity {
+                self.residuals.pop_front();
+            }
+            self.residuals.push_back(r);
+        }
 
-        let mut pace = -cfg.lr_fast * r.d_drift;
-        let mut pause = (cfg.lr_fast * r.d_res * 80.0) as i64;
-        let mut res_boost = cfg.lr_fast * r.d_res.max(0.0) * 0.05;
-        let mut drift_relief = cfg.lr_fast * (-r.d_drift).max(0.0) * 0.05;
-
+        let lr = cfg.lr_for(state);
+        let m = cfg.momentum.clamp(0.0, 1.0);
         let c = cfg.clamp_step;
-        pace = pace.clamp(-c, c);
-        pause = pause.clamp(-20, 40);
-        res_boost = res_boost.clamp(0.0, c);
-        drift_relief = drift_relief.clamp(0.0, c);
+        let (lo, hi) = cfg.pause_clamp_ms;
+        let (pause_min, pause_max) = (lo.min(hi) as f32, lo.max(hi) as f32);
+        self.pace_velocity = (m * self.pace_velocity - (1.0 - m) * lr * r.d_drift).clamp(-c, c);
+        self.pause_velocity =
+            (m * self.pause_velocity + (1.0 - m) * lr * r.d_res * 80.0).clamp(pause_min, pause_max);
+
+        let mut pace = self.pace_velocity;
+        let mut pause = self.pause_velocity as i64;
+        let mut res_boost = lr * r.d_res.max(0.0) * 0.05;
+        let mut drift_relief = lr * (-r.d_drift).max(0.0) * 0.05;
 
-        if matches!(state, EmoState::Overheat) {
+        let b = cfg.boost_clamp.unwrap_or(c).max(0.0);
+        res_boost = res_boost.clamp(0.0, b);
+        drift_relief = drift_relief.clamp(0.0, b);
+
+        if matches!(state, EmoState::Overheat | EmoState::Panic) {
             pace -= 0.01;
             pause += 10;
         }
@@ -93,12 +185,32 @@ impl SyncState {
         (pace, pause, res_boost, drift_relief)
     }
 
+    /// Up to `n` most recent residuals, oldest first.
+    pub fn recent_residuals(&self, n: usize) -> Vec<Residual> {
+        let skip = self.residuals.len().saturating_sub(n);
+        self.residuals.iter().skip(skip).copied().collect()
+    }
+
+    /// Seeds with this session's slow increments folded in, for persisting to
+    /// the next run. The increments are resonance/drift deltas, so only
+    /// `res_warm` and `drift_soft` move; pace and pause carry over as seeded.
+    pub fn updated_seeds(&self, cfg: &SyncCfg) -> Seeds {
+        let (drift_bias, res_bias) = self.to_slow_increments(cfg);
+        Seeds {
+            pace_bias: self.seeds.pace_bias,
+            pause_bias_ms: self.seeds.pause_bias_ms,
+            res_warm: (self.seeds.res_warm + res_bias).clamp(-0.05, 0.05),
+            drift_soft: (self.seeds.drift_soft - drift_bias).clamp(-0.05, 0.05),
+        }
+    }
+
     pub fn to_slow_increments(&self, cfg: &SyncCfg) -> (f32, f32) {
         if self.steps == 0 {
             return (0.0, 0.0);
         }
-        let mean_drift = self.accum_drift / self.steps as f32;
-        let mean_res = self.accum_res / self.steps as f32;
+        let count = cfg.effective_count(self.steps);
+        let mean_drift = self.accum_drift / count;
+        let mean_res = self.accum_res / count;
         let drift_bias = (-mean_drift * cfg.lr_slow).clamp(-0.03, 0.03);
         let res_bias = (mean_res * cfg.lr_slow).clamp(-0.03, 0.03);
         (drift_bias, res_bias)
@@ -120,3 +232,50 @@ pub fn merge_seeds(
         drift_soft: (emote_drift + astro_drift) * 0.5,
     }
 }
+
+impl Seeds {
+    /// Moves `self` toward `other` by `weight` (`0.0` keeps `self`, `1.0`
+    /// takes `other`).
+    pub fn blend(&self, other: &Seeds, weight: f32) -> Seeds {
+        let w = weight.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * w;
+        Seeds {
+            pace_bias: mix(self.pace_bias, other.pace_bias),
+            pause_bias_ms: mix(self.pause_bias_ms as f32, other.pause_bias_ms as f32).round()
+                as i64,
+            res_warm: mix(self.res_warm, other.res_warm),
+            drift_soft: mix(self.drift_soft, other.drift_soft),
+        }
+    }
+
+    pub fn to_json_line(&self) -> String {
+        format!(
+            "{{\"pace_bias\":{:.6},\"pause_bias_ms\":{},\"res_warm\":{:.6},\"drift_soft\":{:.6}}}",
+            self.pace_bias, self.pause_bias_ms, self.res_warm, self.drift_soft
+        )
+    }
+
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        let fields = parse_json_object(line)?;
+        let num = |key: &str| fields.iter().find(|(k, _)| k == key)?.1.as_f64();
+        Some(Seeds {
+            pace_bias: num("pace_bias")? as f32,
+            pause_bias_ms: num("pause_bias_ms")? as i64,
+            res_warm: num("res_warm")? as f32,
+            drift_soft: num("drift_soft")? as f32,
+        })
+    }
+}
+
+/// Latest parseable seeds in a JSONL file; older files may still hold one
+/// line per run.
+pub fn load_seeds(path: &str) -> Option<Seeds> {
+    let txt = fs::read_to_string(path).ok()?;
+    txt.lines().rev().find_map(Seeds::from_json_line)
+}
+
+/// Replaces the seed file with `seeds`, so it stays one line however many
+/// runs persist to it.
+pub fn save_seeds(path: &str, seeds: &Seeds) -> io::Result<()> {
+    utils::write_atomic(path, &format!("{}\n", seeds.to_json_line()))
+}
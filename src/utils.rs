@@ -1,17 +1,86 @@
-#[allow(dead_code)]
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
 pub fn normalize_text(text: &str) -> String {
     text.trim().to_lowercase()
 }
 
-#[allow(dead_code)]
-pub fn hash01(s: &str) -> (f32, f32) {
+pub fn fnv1a64(s: &str) -> u64 {
     let mut h: u64 = 0xcbf2_29d1_821f_1cfd; // 1469598103934665603 FNV offset basis
     for b in s.as_bytes() {
         h ^= *b as u64;
         h = h.wrapping_mul(0x1000_0000_01b3);
     }
+    h
+}
+
+/// Small splitmix64 generator for reproducible jitter; the same seed always
+/// yields the same sequence.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0.0, 1.0)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform value in `[lo, hi)`.
+    pub fn gen_range(&mut self, lo: f32, hi: f32) -> f32 {
+        lo + (hi - lo) * self.next_f32()
+    }
+}
+
+/// Replace `path` with `contents` by writing a sibling `.tmp` file and
+/// renaming it over the target, so readers never see a half-written file.
+/// Missing parent directories are created.
+pub fn write_atomic(path: &str, contents: &str) -> io::Result<()> {
+    let path = Path::new(path);
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+/// Append `line` plus a newline to the JSONL file at `path`, creating the
+/// file and any missing parent directories.
+pub fn append_line<P: AsRef<Path>>(path: P, line: &str) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Seconds since the Unix epoch, 0 if the clock is before it.
+pub fn current_unix_secs() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
 
-    let a = ((h >> 11) & 0xFFFF) as f32 / 65535.0;
-    let b = ((h >> 27) & 0xFFFF) as f32 / 65535.0;
-    (a, b)
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
 }
@@ -1,12 +1,192 @@
 use crate::awareness::MetaCognition;
 use crate::compassion::CompassionMetrics;
 use crate::metrics;
+use crate::session::escape_json;
+use crate::spark;
 use crate::stabilizer::EmoState;
 
 const LABEL_WIDTH: usize = 22;
+/// Longest row label, `"  Confidence/Clarity"`; narrow layouts keep at
+/// least this much label column.
+const LABEL_MIN_WIDTH: usize = 20;
 const VALUE_WIDTH: usize = 25;
 const BAR_WIDTH: usize = 19;
 
+/// Narrowest and widest table `VizCfg::for_width` will lay out.
+const MIN_TABLE_WIDTH: usize = 40;
+const MAX_TABLE_WIDTH: usize = 120;
+/// Borders and padding around the two columns: `| ` + ` | ` + ` |`.
+const TABLE_CHROME: usize = 7;
+/// A bar entry's `0.00  ` prefix ahead of the bar itself.
+const BAR_PREFIX: usize = 6;
+
+/// How `print_table` lays out and decorates the table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VizCfg {
+    pub label_width: usize,
+    pub value_width: usize,
+    pub bar_width: usize,
+    /// Changes smaller than this show as a flat trend arrow.
+    pub trend_deadband: f32,
+    /// ANSI severity colors on the drift/resonance bars.
+    pub color: bool,
+    /// Plain-ASCII arrows and markers.
+    pub ascii: bool,
+}
+
+impl Default for VizCfg {
+    fn default() -> Self {
+        Self {
+            label_width: LABEL_WIDTH,
+            value_width: VALUE_WIDTH,
+            bar_width: BAR_WIDTH,
+            trend_deadband: 0.02,
+            color: false,
+            ascii: false,
+        }
+    }
+}
+
+impl VizCfg {
+    /// Fit the table into `total` columns (clamped to 40..=120). The label
+    /// column keeps its default width while the value column can spare it,
+    /// and never gets narrower than the longest label.
+    pub fn for_width(total: usize) -> Self {
+        let inner = total.clamp(MIN_TABLE_WIDTH, MAX_TABLE_WIDTH) - TABLE_CHROME;
+        let label_width = LABEL_WIDTH.min(inner / 2).max(LABEL_MIN_WIDTH);
+        let value_width = inner - label_width;
+        Self {
+            label_width,
+            value_width,
+            bar_width: value_width - BAR_PREFIX,
+            ..Self::default()
+        }
+    }
+
+    /// Full width of a rendered table row.
+    pub fn table_width(&self) -> usize {
+        self.label_width + self.value_width + TABLE_CHROME
+    }
+}
+
+/// Terminal width from `COLUMNS`, if the shell exports it.
+pub fn terminal_width() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&cols| cols > 0)
+}
+
+// Bar color thresholds: drift is worse high, resonance is worse low.
+const DRIFT_WARN: f32 = 0.35;
+const DRIFT_CRIT: f32 = 0.60;
+const RES_WARN: f32 = 0.65;
+const RES_CRIT: f32 = 0.40;
+
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_YELLOW: &str = "\x1b[33m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warn,
+    Critical,
+}
+
+pub fn drift_severity(drift: f32) -> Severity {
+    if drift >= DRIFT_CRIT {
+        Severity::Critical
+    } else if drift >= DRIFT_WARN {
+        Severity::Warn
+    } else {
+        Severity::Ok
+    }
+}
+
+/// Inverse of `drift_severity`: low resonance is the dangerous end.
+pub fn resonance_severity(res: f32) -> Severity {
+    if res < RES_CRIT {
+        Severity::Critical
+    } else if res < RES_WARN {
+        Severity::Warn
+    } else {
+        Severity::Ok
+    }
+}
+
+/// Wrap `text` in the ANSI color for `severity`; unchanged when `color` is off.
+pub fn paint(text: &str, severity: Severity, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+    let code = match severity {
+        Severity::Ok => ANSI_GREEN,
+        Severity::Warn => ANSI_YELLOW,
+        Severity::Critical => ANSI_RED,
+    };
+    format!("{}{}{}", code, text, ANSI_RESET)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Flat,
+}
+
+/// Direction from `prev` to `curr`; moves within `deadband` count as flat.
+pub fn trend(prev: f32, curr: f32, deadband: f32) -> Trend {
+    let delta = curr - prev;
+    if delta > deadband {
+        Trend::Rising
+    } else if delta < -deadband {
+        Trend::Falling
+    } else {
+        Trend::Flat
+    }
+}
+
+pub fn trend_arrow(trend: Trend, ascii: bool) -> &'static str {
+    let glyphs = spark::glyphs(ascii);
+    match trend {
+        Trend::Rising => glyphs.rising,
+        Trend::Falling => glyphs.falling,
+        Trend::Flat => glyphs.flat,
+    }
+}
+
+/// Columns in the end-of-session drift heatmap.
+pub const HEATMAP_WIDTH: usize = 24;
+
+pub fn heatmap(values: &[f32], width: usize) -> String {
+    heatmap_for(values, width, false)
+}
+
+/// Spreads the series over exactly `width` columns, shading each by its
+/// 0..1 magnitude. Columns covering several values show their mean; with
+/// fewer values than columns each value is repeated.
+pub fn heatmap_for(values: &[f32], width: usize, ascii: bool) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let shade = spark::glyphs(ascii).shade;
+    let max_index = (shade.len() - 1) as f32;
+    (0..width)
+        .map(|col| {
+            let start = col * values.len() / width;
+            let end = ((col + 1) * values.len() / width).max(start + 1);
+            let bucket = &values[start..end];
+            let mean = bucket.iter().sum::<f32>() / bucket.len() as f32;
+            let idx = (metrics::clamp01(mean) * max_index).round() as usize;
+            shade[idx.min(shade.len() - 1)]
+        })
+        .collect()
+}
+
 pub fn bar(value_0_1: f32, width: usize) -> String {
     if width == 0 {
         return String::new();
@@ -20,65 +200,112 @@ pub fn bar(value_0_1: f32, width: usize) -> String {
     "#".repeat(filled)
 }
 
-pub fn print_table(
-    drift: f32,
-    res: f32,
-    wpm: f32,
-    articulation: f32,
-    tone: &str,
-    asr_ms: u128,
-    tts_ms: u128,
-    total_ms: u128,
-    stab_state: Option<&str>,
-    emote_seed: Option<&str>,
-    meta_cognition: Option<&MetaCognition>,
-    compassion: Option<&CompassionMetrics>,
-) -> Vec<String> {
+/// Everything the final table shows. Optional layers left `None` are
+/// skipped, so call sites can fill what they have and use
+/// `..TableData::default()` for the rest.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableData<'a> {
+    pub drift: f32,
+    pub res: f32,
+    pub wpm: f32,
+    pub articulation: f32,
+    pub filler_density: f32,
+    pub tone: &'a str,
+    pub asr_ms: u128,
+    pub tts_ms: u128,
+    pub total_ms: u128,
+    pub stab_state: Option<&'a str>,
+    pub emote_seed: Option<&'a str>,
+    pub meta_cognition: Option<&'a MetaCognition>,
+    pub compassion: Option<&'a CompassionMetrics>,
+    /// Previous cycle's values, for the trend arrows.
+    pub drift_prev: Option<f32>,
+    pub res_prev: Option<f32>,
+}
+
+pub fn print_table(data: &TableData, viz_cfg: &VizCfg) -> Vec<String> {
+    let TableData {
+        drift,
+        res,
+        wpm,
+        articulation,
+        filler_density,
+        tone,
+        asr_ms,
+        tts_ms,
+        total_ms,
+        stab_state,
+        emote_seed,
+        meta_cognition,
+        compassion,
+        drift_prev,
+        res_prev,
+    } = *data;
+    let VizCfg {
+        trend_deadband,
+        color,
+        ascii,
+        ..
+    } = *viz_cfg;
     let mut lines = Vec::new();
     let border = format!(
         "+{}+{}+",
-        "-".repeat(LABEL_WIDTH + 2),
-        "-".repeat(VALUE_WIDTH + 2)
-    );
-    let header = format!(
-        "| {:<label$} | {:<value$} |",
-        "Metric",
-        "Value",
-        label = LABEL_WIDTH,
-        value = VALUE_WIDTH
+        "-".repeat(viz_cfg.label_width + 2),
+        "-".repeat(viz_cfg.value_width + 2)
     );
+    let header = format_row(viz_cfg, "Metric", "Value");
 
     lines.push(border.clone());
-    lines.push(header);
+    lines.extend(header);
     lines.push(border.clone());
 
-    let drift_bar = format_bar_entry(drift);
-    let res_bar = format_bar_entry(res);
-    let articulation_bar = format_bar_entry(articulation);
+    let drift_bar = format_severity_entry(viz_cfg, drift, drift_severity(drift), color);
+    let res_bar = format_severity_entry(viz_cfg, res, resonance_severity(res), color);
+    let articulation_bar = format_bar_entry(viz_cfg, articulation);
 
-    lines.push(format_row("Semantic Drift", &drift_bar));
-    lines.push(format_row("Resonance", &res_bar));
-    lines.push(format_row("WPM", &format!("{:.1}", wpm)));
-    lines.push(format_row("Articulation", &articulation_bar));
-    lines.push(format_row("Tone", tone));
-    lines.push(format_row(
+    let with_trend = |label: &str, prev: Option<f32>, curr: f32| match prev {
+        Some(prev) => {
+            let arrow = trend_arrow(trend(prev, curr, trend_deadband), ascii);
+            format!("{} {}", label, arrow)
+        }
+        None => label.to_string(),
+    };
+    let drift_label = with_trend("Semantic Drift", drift_prev, drift);
+    let res_label = with_trend("Resonance", res_prev, res);
+    lines.extend(format_row(viz_cfg, &drift_label, &drift_bar));
+    lines.extend(format_row(viz_cfg, &res_label, &res_bar));
+    lines.extend(format_row(viz_cfg, "WPM", &format!("{:.1}", wpm)));
+    lines.extend(format_row(viz_cfg, "Articulation", &articulation_bar));
+    if filler_density > 0.0 {
+        lines.extend(format_row(
+            viz_cfg,
+            "Filler Density",
+            &format_bar_entry(viz_cfg, filler_density),
+        ));
+    }
+    lines.extend(format_row(viz_cfg, "Tone", tone));
+    lines.extend(format_row(
+        viz_cfg,
         "Latency (ASR/TTS/T)",
         &format!("{}ms / {}ms / {}ms", asr_ms, tts_ms, total_ms),
     ));
     if let Some(state) = stab_state {
-        lines.push(format_row("Stabilizer State", state));
+        lines.extend(format_row(viz_cfg, "Stabilizer State", state));
     }
     if let Some(seed) = emote_seed {
-        lines.push(format_row("Emotive Seed", seed));
+        lines.extend(format_row(viz_cfg, "Emotive Seed", seed));
     }
 
     // Meta-cognition metrics (if available)
+    let glyphs = spark::glyphs(ascii);
     if let Some(meta) = meta_cognition {
-        lines.push(format_row(
+        lines.extend(format_row(
+            viz_cfg,
             "Meta-Cognition",
             &format!("self_d={:.2} self_r={:.2}", meta.self_drift, meta.self_resonance),
         ));
-        lines.push(format_row(
+        lines.extend(format_row(
+            viz_cfg,
             "  Confidence/Clarity",
             &format!(
                 "conf={:.2} clarity={:.2} doubt={:.2}",
@@ -87,17 +314,23 @@ pub fn print_table(
         ));
 
         if meta.should_express_doubt() {
-            lines.push(format_row("  Status", "⚠️  UNCERTAIN STATE"));
+            lines.extend(format_row(
+                viz_cfg,
+                "  Status",
+                &format!("{}  UNCERTAIN STATE", glyphs.warning),
+            ));
         }
     }
 
     // Compassion metrics (if available)
     if let Some(comp) = compassion {
-        lines.push(format_row(
+        lines.extend(format_row(
+            viz_cfg,
             "Compassion",
             &format!("suffering={:.2} type={:?}", comp.user_suffering, comp.suffering_type),
         ));
-        lines.push(format_row(
+        lines.extend(format_row(
+            viz_cfg,
             "  Kindness/Intent",
             &format!(
                 "kind={:.2} healing={:.2} level={:.2}",
@@ -106,7 +339,11 @@ pub fn print_table(
         ));
 
         if comp.should_offer_support() {
-            lines.push(format_row("  Status", "💝 ACTIVE SUPPORT"));
+            lines.extend(format_row(
+                viz_cfg,
+                "  Status",
+                &format!("{} ACTIVE SUPPORT", glyphs.support),
+            ));
         }
     }
 
@@ -119,6 +356,59 @@ pub fn print_table(
     lines
 }
 
+/// The same metrics as `print_table`, as a single JSON object for
+/// dashboards and scripts. Absent layers are `null`.
+pub fn table_json(data: &TableData) -> String {
+    let TableData {
+        drift,
+        res,
+        wpm,
+        articulation,
+        filler_density,
+        tone,
+        asr_ms,
+        tts_ms,
+        total_ms,
+        stab_state,
+        emote_seed,
+        meta_cognition,
+        compassion,
+        ..
+    } = *data;
+    let json_str = |value: Option<&str>| {
+        value.map_or("null".to_string(), |v| format!("\"{}\"", escape_json(v)))
+    };
+    let meta = meta_cognition.map_or("null".to_string(), MetaCognition::to_json);
+    let compassion = compassion.map_or("null".to_string(), |comp| {
+        format!(
+            "{{\"suffering\":{:.3},\"type\":\"{:?}\",\"kindness\":{:.3},\"healing\":{:.3},\"level\":{:.3},\"active_support\":{}}}",
+            comp.user_suffering,
+            comp.suffering_type,
+            comp.response_kindness,
+            comp.healing_intent,
+            comp.compassion_level,
+            comp.should_offer_support()
+        )
+    });
+
+    format!(
+        "{{\"drift\":{:.3},\"resonance\":{:.3},\"wpm\":{:.1},\"articulation\":{:.3},\"filler_density\":{:.3},\"tone\":{},\"latency\":{{\"asr_ms\":{},\"tts_ms\":{},\"total_ms\":{}}},\"stabilizer\":{},\"emotive_seed\":{},\"meta\":{},\"compassion\":{}}}",
+        drift,
+        res,
+        wpm,
+        articulation,
+        filler_density,
+        json_str(Some(tone)),
+        asr_ms,
+        tts_ms,
+        total_ms,
+        json_str(stab_state),
+        json_str(emote_seed),
+        meta,
+        compassion
+    )
+}
+
 pub fn print_compact_stabilizer(state: EmoState, ema_drift: f32, ema_res: f32) {
     println!(
         "[stab] {:?} d={:.2} r={:.2}",
@@ -128,21 +418,98 @@ pub fn print_compact_stabilizer(state: EmoState, ema_drift: f32, ema_res: f32) {
     );
 }
 
-fn format_bar_entry(value: f32) -> String {
-    let bar = bar(value, BAR_WIDTH);
+/// One-line meta-cognition summary for compact mode; returns what it printed.
+pub fn print_compact_meta(meta: &MetaCognition) -> String {
+    let line = format!(
+        "[meta] conf={:.2} clarity={:.2} doubt={:.2}",
+        meta.confidence, meta.clarity, meta.doubt
+    );
+    println!("{}", line);
+    line
+}
+
+/// One-line compassion summary for compact mode; returns what it printed.
+pub fn print_compact_compassion(comp: &CompassionMetrics) -> String {
+    let line = format!(
+        "[compassion] suffering={:.2} type={:?} kind={:.2} level={:.2}",
+        comp.user_suffering, comp.suffering_type, comp.response_kindness, comp.compassion_level
+    );
+    println!("{}", line);
+    line
+}
+
+fn format_bar_entry(viz_cfg: &VizCfg, value: f32) -> String {
+    let bar = bar(value, viz_cfg.bar_width);
     if bar.is_empty() {
         format!("{:.2}", value)
     } else {
-        format!("{:.2}  {:<width$}", value, bar, width = BAR_WIDTH)
+        format!("{:.2}  {:<width$}", value, bar, width = viz_cfg.bar_width)
     }
 }
 
-fn format_row(label: &str, value: &str) -> String {
-    format!(
-        "| {:<label$} | {:<value$} |",
-        label,
-        value,
-        label = LABEL_WIDTH,
-        value = VALUE_WIDTH
-    )
+/// `format_bar_entry` padded to the full value column before painting, so
+/// the escape codes don't upset the table's alignment.
+fn format_severity_entry(viz_cfg: &VizCfg, value: f32, severity: Severity, color: bool) -> String {
+    if !color {
+        return format_bar_entry(viz_cfg, value);
+    }
+    let entry = format!(
+        "{:<width$}",
+        format_bar_entry(viz_cfg, value),
+        width = viz_cfg.value_width
+    );
+    paint(&entry, severity, color)
+}
+
+/// One table row. A value wider than its column wraps onto continuation
+/// rows with a blank label, and a label wider than a custom label column is
+/// cut, so every row keeps the table's width. Painted entries are already
+/// sized to the column and pass through untouched.
+fn format_row(viz_cfg: &VizCfg, label: &str, value: &str) -> Vec<String> {
+    let label: String = label.chars().take(viz_cfg.label_width).collect();
+    let row = |label: &str, value: &str| {
+        format!(
+            "| {:<label$} | {:<value$} |",
+            label,
+            value,
+            label = viz_cfg.label_width,
+            value = viz_cfg.value_width
+        )
+    };
+    if value.chars().count() <= viz_cfg.value_width || value.contains('\x1b') {
+        return vec![row(&label, value)];
+    }
+    wrap(value, viz_cfg.value_width)
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| row(if i == 0 { &label } else { "" }, chunk))
+        .collect()
+}
+
+/// Greedy word wrap to `width` columns; words longer than a line are split.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > width {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            lines.push(word.drain(..width).collect());
+        }
+        let len = current.chars().count();
+        if len > 0 && len + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.extend(word);
+    }
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
 }
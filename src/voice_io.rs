@@ -1,69 +1,408 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::config::Config;
 use crate::device::DeviceProfile;
 
-use crate::dialog;
+use crate::{dialog, metrics};
 
-#[allow(dead_code)]
-pub fn record_audio() -> &'static str {
-    "recorded.wav"
+/// Speech recognizer the pipeline hands each utterance to. Integrators
+/// plug a real engine in here; `SimulatedAsr` is the default.
+pub trait AsrBackend {
+    fn transcribe(&self, cfg: &Config, prof: &DeviceProfile, input: &str) -> String;
+
+    /// Latency this backend stands in for, when it only simulates one.
+    fn simulated_ms(&self, _cfg: &Config, _prof: &DeviceProfile) -> Option<u64> {
+        None
+    }
 }
 
-#[allow(dead_code)]
-pub fn transcribe_audio(cfg: &Config, prof: &DeviceProfile) -> String {
-    transcribe_audio_like(cfg, prof, dialog::default_utterance())
+/// Echoes the provided text after a device-dependent delay.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimulatedAsr;
+
+impl AsrBackend for SimulatedAsr {
+    fn transcribe(&self, cfg: &Config, prof: &DeviceProfile, input: &str) -> String {
+        transcribe_audio_like(cfg, prof, input)
+    }
+
+    fn simulated_ms(&self, cfg: &Config, prof: &DeviceProfile) -> Option<u64> {
+        Some(asr_latency_ms(cfg, prof))
+    }
 }
 
-pub fn transcribe_audio_like(cfg: &Config, prof: &DeviceProfile, provided: &str) -> String {
-    println!(
-        "[voice] cfg mode={} sr={} ch={} frame={}ms",
-        cfg.mode, cfg.sample_rate, cfg.channels, cfg.frame_ms
+/// Speech synthesizer the pipeline voices each response through.
+pub trait TtsBackend {
+    /// `articulation` is the stabilizer's clarity hint, 0 when it asks for
+    /// nothing beyond normal speech.
+    fn speak(
+        &mut self,
+        cfg: &Config,
+        prof: &DeviceProfile,
+        pace: f32,
+        pause_ms: u64,
+        articulation: f32,
+        text: &str,
     );
-    println!("[voice] ASR capturing...");
 
-    let latency_ms = prof.pause_ms + cfg.frame_ms as u64;
-    thread::sleep(Duration::from_millis(latency_ms));
+    /// Latency the last `speak` stood in for, when it only simulated one.
+    fn simulated_ms(&self) -> Option<u64> {
+        None
+    }
+}
 
-    println!("[voice] ASR done (latency={}ms)", latency_ms);
-    println!("[voice] transcript: {}", provided);
-    provided.to_string()
+/// Prints and sleeps in place of real audio. With `paced` set it honours
+/// the pace, pause and articulation it is given (`synthesize_with`);
+/// otherwise it uses the device profile alone (`synthesize_response`).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimulatedTts {
+    pub paced: bool,
+    last_ms: Option<u64>,
 }
 
-pub fn synthesize_response(cfg: &Config, prof: &DeviceProfile, text: &str) {
-    let latency_ms = (prof.pause_ms / 2).saturating_add(cfg.frame_ms as u64);
-    println!("[voice] TTS rendering...");
-    thread::sleep(Duration::from_millis(latency_ms));
-    println!("[voice] TTS done (latency={}ms)", latency_ms);
-    println!("[voice] response: {}", text);
-    println!(
-        "[voice] audio sr={} ch={} gain={:.1}dB",
-        cfg.sample_rate, cfg.channels, prof.gain_db
-    );
+impl SimulatedTts {
+    pub fn new(paced: bool) -> Self {
+        Self {
+            paced,
+            last_ms: None,
+        }
+    }
+}
+
+impl TtsBackend for SimulatedTts {
+    fn speak(
+        &mut self,
+        cfg: &Config,
+        prof: &DeviceProfile,
+        pace: f32,
+        pause_ms: u64,
+        articulation: f32,
+        text: &str,
+    ) {
+        self.last_ms = Some(if self.paced {
+            synthesize_with(cfg, prof, pace, pause_ms, articulation, text)
+        } else {
+            synthesize_response(cfg, prof, text)
+        });
+    }
+
+    fn simulated_ms(&self) -> Option<u64> {
+        self.last_ms
+    }
+}
+
+/// Records every spoken text instead of voicing it.
+#[derive(Debug, Default, Clone)]
+pub struct CapturingTts {
+    pub spoken: Vec<String>,
+}
+
+impl TtsBackend for CapturingTts {
+    fn speak(
+        &mut self,
+        _cfg: &Config,
+        _prof: &DeviceProfile,
+        _pace: f32,
+        _pause_ms: u64,
+        _articulation: f32,
+        text: &str,
+    ) {
+        self.spoken.push(text.to_string());
+    }
+}
+
+/// Pace the simulated synthesizer accepts, as a multiple of normal speed.
+pub const TTS_PACE_RANGE: (f32, f32) = (0.5, 2.0);
+/// Pause between words the simulated synthesizer accepts.
+pub const TTS_PAUSE_MS_RANGE: (u64, u64) = (20, 250);
+
+/// Extra synthesis time per word at the strongest articulation hint.
+pub const ARTICULATION_MS_PER_WORD: f32 = 100.0;
+/// Recognition time per millisecond of recorded audio.
+pub const ASR_REAL_TIME_FACTOR: f32 = 0.1;
+
+/// `ms` stretched by `cfg.latency_scale`.
+pub fn scaled_ms(cfg: &Config, ms: u64) -> u64 {
+    (ms as f32 * cfg.latency_scale.max(0.0)).round() as u64
 }
 
-pub fn synthesize_with(cfg: &Config, prof: &DeviceProfile, pace: f32, pause_ms: u64, text: &str) {
-    let pace = pace.clamp(0.5, 2.0);
-    let pause = pause_ms.clamp(20, 250);
-    println!(
-        "[voice] TTS rendering (pace={:.2}, pause={}ms)...",
-        pace, pause
+/// Waits out a simulated latency, unless `--no-latency` turned that off.
+fn simulate_delay(cfg: &Config, latency_ms: u64) {
+    if cfg.simulate_latency && latency_ms > 0 {
+        thread::sleep(Duration::from_millis(latency_ms));
+    }
+}
+
+/// Simulated recognition latency for one utterance.
+pub fn asr_latency_ms(cfg: &Config, prof: &DeviceProfile) -> u64 {
+    scaled_ms(cfg, prof.pause_ms + cfg.frame_ms as u64)
+}
+
+/// Waits out the extra recognition time of a `duration_ms` recording, an
+/// [`ASR_REAL_TIME_FACTOR`] share of its length, and returns it.
+pub fn simulate_audio_latency(cfg: &Config, duration_ms: u64) -> u64 {
+    let latency_ms = scaled_ms(
+        cfg,
+        (duration_ms as f32 * ASR_REAL_TIME_FACTOR).round() as u64,
     );
+    simulate_delay(cfg, latency_ms);
+    latency_ms
+}
 
+/// Simulated synthesis latency at the device's own pace and pause.
+pub fn response_latency_ms(cfg: &Config, prof: &DeviceProfile) -> u64 {
+    scaled_ms(cfg, (prof.pause_ms / 2).saturating_add(cfg.frame_ms as u64))
+}
+
+/// Simulated synthesis latency at an adjusted pace and pause, split into
+/// the pause-driven base and the pace adjustment.
+pub fn paced_latency_ms(cfg: &Config, pace: f32, pause_ms: u64) -> (u64, u64) {
+    let pace = pace.clamp(TTS_PACE_RANGE.0, TTS_PACE_RANGE.1);
+    let pause = pause_ms.clamp(TTS_PAUSE_MS_RANGE.0, TTS_PAUSE_MS_RANGE.1);
     let base_latency = (pause / 2).saturating_add(cfg.frame_ms as u64);
     let pace_adjust = if pace < 1.0 {
         ((1.0 - pace) * 80.0).round() as u64
     } else {
         ((pace - 1.0) * 40.0).round() as u64
     };
-    let latency_ms = base_latency.saturating_add(pace_adjust);
-
-    thread::sleep(Duration::from_millis(latency_ms));
-    println!("[voice] TTS done (latency={}ms)", latency_ms);
-    println!("[voice] response: {}", text);
-    println!(
-        "[voice] audio sr={} ch={} gain={:.1}dB",
-        cfg.sample_rate, cfg.channels, prof.gain_db
-    );
+    (scaled_ms(cfg, base_latency), scaled_ms(cfg, pace_adjust))
+}
+
+/// Extra synthesis latency for articulating `text` more clearly: every
+/// word is stretched in proportion to the hint, clamped to 0..1.
+pub fn articulation_latency_ms(cfg: &Config, articulation: f32, text: &str) -> u64 {
+    let words = text.split_whitespace().count() as f32;
+    let extra_ms = words * metrics::clamp01(articulation) * ARTICULATION_MS_PER_WORD;
+    scaled_ms(cfg, extra_ms.round() as u64)
+}
+
+/// How long a stage took. With `--no-latency` the simulated figure the
+/// backend skipped is added back, so fast runs still report real numbers.
+pub fn stage_ms(cfg: &Config, start: Instant, simulated_ms: Option<u64>) -> u128 {
+    match simulated_ms {
+        Some(ms) if !cfg.simulate_latency => start.elapsed().as_millis() + ms as u128,
+        _ => start.elapsed().as_millis(),
+    }
+}
+
+/// Format details of a 16-bit PCM WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    /// Samples per channel.
+    pub frames: u64,
+}
+
+impl WavInfo {
+    pub fn duration_ms(&self) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        self.frames * 1000 / self.sample_rate as u64
+    }
+}
+
+/// Reads the header of a 16-bit PCM WAV file. The sample data itself is
+/// never loaded.
+pub fn read_wav(path: &str) -> io::Result<WavInfo> {
+    read_wav_from(&mut BufReader::new(File::open(path)?))
+}
+
+/// `read_wav` over any reader positioned at the start of the file.
+pub fn read_wav_from<R: Read>(reader: &mut R) -> io::Result<WavInfo> {
+    let mut riff = [0u8; 12];
+    reader.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(invalid_wav("missing RIFF/WAVE header"));
+    }
+
+    let mut format: Option<(u32, u16, u16)> = None;
+    loop {
+        let mut header = [0u8; 8];
+        if let Err(err) = reader.read_exact(&mut header) {
+            return Err(if err.kind() == io::ErrorKind::UnexpectedEof {
+                invalid_wav("no data chunk")
+            } else {
+                err
+            });
+        }
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        match &header[0..4] {
+            b"fmt " => {
+                if size < 16 {
+                    return Err(invalid_wav("fmt chunk too short"));
+                }
+                let mut fmt = [0u8; 16];
+                reader.read_exact(&mut fmt)?;
+                let audio_format = u16::from_le_bytes([fmt[0], fmt[1]]);
+                let channels = u16::from_le_bytes([fmt[2], fmt[3]]);
+                let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+                let bits = u16::from_le_bytes([fmt[14], fmt[15]]);
+                if audio_format != 1 || bits != 16 {
+                    return Err(invalid_wav("only 16-bit PCM is supported"));
+                }
+                if channels == 0 || sample_rate == 0 {
+                    return Err(invalid_wav("zero channels or sample rate"));
+                }
+                format = Some((sample_rate, channels, bits));
+                // Extension bytes (cbSize and beyond) only matter for
+                // non-PCM formats.
+                skip_bytes(reader, u64::from(size - 16) + u64::from(size % 2))?;
+            }
+            b"data" => {
+                let (sample_rate, channels, bits_per_sample) =
+                    format.ok_or_else(|| invalid_wav("data chunk before fmt chunk"))?;
+                let block_align = u64::from(channels) * u64::from(bits_per_sample / 8);
+                return Ok(WavInfo {
+                    sample_rate,
+                    channels,
+                    bits_per_sample,
+                    frames: u64::from(size) / block_align,
+                });
+            }
+            // LIST, fact and friends carry nothing the model uses.
+            _ => skip_bytes(reader, u64::from(size) + u64::from(size % 2))?,
+        }
+    }
+}
+
+fn skip_bytes<R: Read>(reader: &mut R, count: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.by_ref().take(count), &mut io::sink())?;
+    if skipped < count {
+        return Err(invalid_wav("truncated chunk"));
+    }
+    Ok(())
+}
+
+fn invalid_wav(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("invalid WAV: {}", msg))
+}
+
+#[allow(dead_code)]
+pub fn record_audio() -> &'static str {
+    "recorded.wav"
+}
+
+#[allow(dead_code)]
+pub fn transcribe_audio(cfg: &Config, prof: &DeviceProfile) -> String {
+    transcribe_audio_like(cfg, prof, dialog::default_utterance())
+}
+
+pub fn transcribe_audio_like(cfg: &Config, prof: &DeviceProfile, provided: &str) -> String {
+    let chatty = !cfg.is_quiet();
+    if chatty {
+        println!(
+            "[voice] cfg mode={} sr={} ch={} frame={}ms",
+            cfg.mode, cfg.sample_rate, cfg.channels, cfg.frame_ms
+        );
+        println!("[voice] ASR capturing...");
+    }
+
+    let latency_ms = asr_latency_ms(cfg, prof);
+    simulate_delay(cfg, latency_ms);
+
+    if chatty {
+        println!("[voice] ASR done (latency={}ms)", latency_ms);
+        println!("[voice] transcript: {}", provided);
+    }
+    provided.to_string()
+}
+
+/// Returns the simulated latency, whether or not it was waited out.
+pub fn synthesize_response(cfg: &Config, prof: &DeviceProfile, text: &str) -> u64 {
+    let latency_ms = response_latency_ms(cfg, prof);
+    let chatty = !cfg.is_quiet();
+    if chatty {
+        println!("[voice] TTS rendering...");
+    }
+    simulate_delay(cfg, latency_ms);
+    if chatty {
+        println!("[voice] TTS done (latency={}ms)", latency_ms);
+        println!("[voice] response: {}", text);
+        println!(
+            "[voice] audio sr={} ch={} gain={:.1}dB",
+            cfg.sample_rate, cfg.channels, prof.gain_db
+        );
+    }
+    latency_ms
+}
+
+/// Like `synthesize_response` at an adjusted pace and pause, with
+/// `articulation` slowing every word down. Returns the simulated latency.
+pub fn synthesize_with(
+    cfg: &Config,
+    prof: &DeviceProfile,
+    pace: f32,
+    pause_ms: u64,
+    articulation: f32,
+    text: &str,
+) -> u64 {
+    let pace = pace.clamp(TTS_PACE_RANGE.0, TTS_PACE_RANGE.1);
+    let pause = pause_ms.clamp(TTS_PAUSE_MS_RANGE.0, TTS_PAUSE_MS_RANGE.1);
+    let chatty = !cfg.is_quiet();
+    if chatty {
+        println!(
+            "[voice] TTS rendering (pace={:.2}, pause={}ms)...",
+            pace, pause
+        );
+    }
+
+    let (base_latency, pace_adjust) = paced_latency_ms(cfg, pace, pause);
+    let articulation_adjust = articulation_latency_ms(cfg, articulation, text);
+    let latency_ms = base_latency
+        .saturating_add(pace_adjust)
+        .saturating_add(articulation_adjust);
+    if cfg.is_verbose() {
+        println!(
+            "[voice] TTS latency base={}ms pace_adjust={}ms articulation_adjust={}ms",
+            base_latency, pace_adjust, articulation_adjust
+        );
+    }
+
+    simulate_delay(cfg, latency_ms);
+    if chatty {
+        println!("[voice] TTS done (latency={}ms)", latency_ms);
+        println!("[voice] response: {}", text);
+        println!(
+            "[voice] audio sr={} ch={} gain={:.1}dB",
+            cfg.sample_rate, cfg.channels, prof.gain_db
+        );
+    }
+    if cfg.ssml {
+        println!("[voice] ssml: {}", to_ssml(pace, pause, text));
+    }
+    latency_ms
+}
+
+/// The pace and pause decisions as SSML for a real TTS engine: pace
+/// becomes a percentage rate and the pause a leading break. Both are
+/// clamped like `synthesize_with` clamps them.
+pub fn to_ssml(pace: f32, pause_ms: u64, text: &str) -> String {
+    let pace = pace.clamp(TTS_PACE_RANGE.0, TTS_PACE_RANGE.1);
+    let pause = pause_ms.clamp(TTS_PAUSE_MS_RANGE.0, TTS_PAUSE_MS_RANGE.1);
+    format!(
+        "<speak><prosody rate=\"{:.0}%\"><break time=\"{}ms\"/>{}</prosody></speak>",
+        pace * 100.0,
+        pause,
+        escape_xml(text)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(ch),
+        }
+    }
+    out
 }
@@ -20,7 +20,104 @@ fn print_summary_includes_status() {
     let mut stats = AlertStats::default();
     alerts::update(&mut stats, 0.5, 0.7, 0.35, 0.65);
 
-    alerts::print_summary(&stats, 0.35, 0.65);
-    let lines = alerts::summary_lines(&stats, 0.35, 0.65);
+    alerts::print_summary(&stats, 0.35, 0.65, false);
+    let lines = alerts::summary_lines(&stats, 0.35, 0.65, false);
     assert!(lines.iter().any(|line| line.contains("status:")));
 }
+
+#[test]
+fn crit_tier_counts_severe_excursions_separately() {
+    let mut stats = AlertStats::with_crit(Some(0.50), Some(0.45));
+    let samples = vec![(0.20, 0.80), (0.40, 0.60), (0.55, 0.70), (0.60, 0.40)];
+    for (drift, res) in samples {
+        alerts::update(&mut stats, drift, res, 0.35, 0.65);
+    }
+
+    assert_eq!(stats.drift_breaches, 3);
+    assert_eq!(stats.drift_crit, 2);
+    assert_eq!(stats.res_breaches, 2);
+    assert_eq!(stats.res_crit, 1);
+
+    let lines = alerts::summary_lines(&stats, 0.35, 0.65, true);
+    assert!(lines[0].contains("crit_drift>0.50, crit_res<0.45"));
+    assert!(lines[1].contains("drift=3 (crit=2), res=2 (crit=1)"));
+    assert!(lines.last().unwrap().contains("status: CRITICAL"));
+}
+
+#[test]
+fn warn_only_breaches_stay_at_attention() {
+    let mut stats = AlertStats::with_crit(Some(0.50), Some(0.45));
+    alerts::update(&mut stats, 0.40, 0.60, 0.35, 0.65);
+
+    assert_eq!(stats.drift_crit, 0);
+    assert_eq!(stats.res_crit, 0);
+    let lines = alerts::summary_lines(&stats, 0.35, 0.65, true);
+    assert!(lines.last().unwrap().contains("status: ATTENTION"));
+}
+
+#[test]
+fn crit_equal_to_warn_keeps_single_tier() {
+    let mut tiered = AlertStats::with_crit(Some(0.35), Some(0.65));
+    let mut plain = AlertStats::default();
+    for (drift, res) in [(0.20, 0.70), (0.90, 0.10)] {
+        alerts::update(&mut tiered, drift, res, 0.35, 0.65);
+        alerts::update(&mut plain, drift, res, 0.35, 0.65);
+    }
+
+    assert_eq!(tiered.drift_crit, 0);
+    assert_eq!(tiered.res_crit, 0);
+    assert_eq!(
+        alerts::summary_lines(&tiered, 0.35, 0.65, false),
+        alerts::summary_lines(&plain, 0.35, 0.65, false)
+    );
+}
+
+#[test]
+fn streaks_track_consecutive_breaches() {
+    let mut alternating = AlertStats::default();
+    for drift in [0.50, 0.20, 0.50, 0.20, 0.50] {
+        alerts::update(&mut alternating, drift, 0.80, 0.35, 0.65);
+    }
+    assert_eq!(alternating.drift_breaches, 3);
+    assert_eq!(alternating.max_drift_streak, 1);
+    assert_eq!(alternating.current_drift_streak, 1);
+
+    let mut sustained = AlertStats::default();
+    for res in [0.80, 0.50, 0.40, 0.50, 0.80, 0.60] {
+        alerts::update(&mut sustained, 0.20, res, 0.35, 0.65);
+    }
+    assert_eq!(sustained.res_breaches, 4);
+    assert_eq!(sustained.max_res_streak, 3);
+    assert_eq!(sustained.current_res_streak, 1);
+    assert_eq!(sustained.max_drift_streak, 0);
+    assert_eq!(sustained.longest_streak(), 3);
+
+    let lines = alerts::summary_lines(&sustained, 0.35, 0.65, false);
+    assert!(
+        lines
+            .iter()
+            .any(|line| line == "[health] longest streak: drift=0, res=3")
+    );
+}
+
+#[test]
+fn min_res_tracks_true_minimum_of_high_samples() {
+    let mut stats = AlertStats::default();
+    for res in [0.92, 0.81, 0.88] {
+        alerts::update(&mut stats, 0.10, res, 0.35, 0.65);
+    }
+    assert!((stats.min_res - 0.81).abs() < f32::EPSILON);
+
+    let mut zero_first = AlertStats::default();
+    for res in [0.0, 0.70] {
+        alerts::update(&mut zero_first, 0.10, res, 0.35, 0.65);
+    }
+    assert_eq!(zero_first.min_res, 0.0);
+}
+
+#[test]
+fn summary_without_samples_has_no_res_min() {
+    let stats = AlertStats::default();
+    let lines = alerts::summary_lines(&stats, 0.35, 0.65, false);
+    assert!(lines.iter().any(|line| line.ends_with("res_min=n/a")));
+}
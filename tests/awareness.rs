@@ -1,4 +1,11 @@
-use liminal_voice_core::awareness::{MetaCognition, MetaStabilizer};
+mod common;
+
+use common::{json_field, temp_path};
+use liminal_voice_core::awareness::{
+    self, MetaCognition, MetaStabilizer, MetaState, ObservationContext,
+};
+use liminal_voice_core::session::SyncDelta;
+use liminal_voice_core::softguard::GuardConfig;
 
 #[test]
 fn test_awareness_basic_flow() {
@@ -147,3 +154,199 @@ fn test_default_implementation() {
     assert_eq!(meta1.observation_count, meta2.observation_count);
     assert_eq!(meta1.confidence, meta2.confidence);
 }
+
+#[test]
+fn test_meta_state_round_trip() {
+    let path = temp_path("liminal_awareness.jsonl");
+    let path = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+    assert!(awareness::load_state(&path).is_none());
+
+    let mut meta = MetaCognition::new();
+    meta.observe(0.2, 0.8, "Normal", 0.01);
+    awareness::save_state(&path, &meta).unwrap();
+    for _ in 0..3 {
+        meta.observe(0.2, 0.8, "Normal", 0.01);
+    }
+    awareness::save_state(&path, &meta).unwrap();
+    // Each save replaces the previous state instead of appending to it.
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+    let state = awareness::load_state(&path).expect("state saved");
+    assert_eq!(state.observation_count, 4);
+    assert!((state.confidence - meta.confidence).abs() < 1e-5);
+    assert!((state.clarity - meta.clarity).abs() < 1e-5);
+
+    let mut restored = MetaCognition::new();
+    restored.warm_start(&state);
+    assert_eq!(restored.observation_count, 4);
+    assert!((restored.confidence - meta.confidence).abs() < 1e-5);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_restored_count_does_not_peg_clarity() {
+    let mut fresh = MetaCognition::new();
+    fresh.observe(0.4, 0.6, "Normal", 0.01);
+
+    let mut veteran = MetaCognition::new();
+    veteran.warm_start(&MetaState {
+        observation_count: 10_000,
+        confidence: 0.9,
+        clarity: 1.0,
+    });
+    veteran.observe(0.4, 0.6, "Normal", 0.01);
+
+    assert_eq!(veteran.observation_count, 10_001);
+    assert!(veteran.clarity < 1.0);
+    assert!(veteran.clarity - fresh.clarity <= 0.15 + 1e-6);
+}
+
+#[test]
+fn test_doubt_shrinks_sync_corrections() {
+    let delta = SyncDelta {
+        pace_delta: -0.02,
+        pause_delta_ms: 40,
+        res_boost: 0.01,
+        drift_relief: 0.01,
+    };
+
+    let mut calm = MetaCognition::new();
+    calm.observe(0.15, 0.85, "Normal", 0.01);
+    assert_eq!(calm.caution(), 1.0);
+    assert_eq!(delta.scaled(calm.caution()).pace_delta, delta.pace_delta);
+
+    let mut doubting = MetaCognition::new();
+    doubting.observe(0.95, 0.1, "Overheat", 0.8);
+    assert!(doubting.should_express_doubt());
+    let caution = doubting.caution();
+    assert!(caution < 1.0);
+
+    let applied = delta.scaled(caution);
+    assert!(applied.pace_delta.abs() < delta.pace_delta.abs());
+    assert!(applied.pause_delta_ms < delta.pause_delta_ms);
+}
+
+#[test]
+fn test_doubt_relaxes_guard_thresholds() {
+    let mut doubting = MetaCognition::new();
+    doubting.observe(0.95, 0.1, "Overheat", 0.8);
+
+    let cfg = GuardConfig::default();
+    let relaxed = cfg.relaxed(doubting.caution());
+    assert!(relaxed.drift_limit > cfg.drift_limit);
+    assert!(relaxed.drift_limit <= 1.0);
+    assert!(relaxed.res_limit < cfg.res_limit);
+    assert_eq!(cfg.relaxed(1.0).drift_limit, cfg.drift_limit);
+}
+
+#[test]
+fn doubt_never_tightens_drift_limit_below_a_lower_hard_limit() {
+    let cfg = GuardConfig {
+        drift_limit: 0.9,
+        hard_drift_limit: Some(0.85),
+        ..GuardConfig::default()
+    };
+    for caution in [1.0, 0.6, 0.1] {
+        let relaxed = cfg.relaxed(caution);
+        assert!(
+            relaxed.drift_limit >= cfg.drift_limit,
+            "caution {} gave {}",
+            caution,
+            relaxed.drift_limit
+        );
+    }
+
+    let capped = GuardConfig {
+        hard_drift_limit: Some(0.5),
+        ..GuardConfig::default()
+    };
+    assert_eq!(capped.relaxed(0.1).drift_limit, 0.5);
+}
+
+#[test]
+fn test_to_json_round_trips_fields() {
+    let mut meta = MetaCognition::new();
+    for _ in 0..3 {
+        meta.observe(0.2, 0.8, "Warming", 0.02);
+    }
+
+    let json = meta.to_json();
+    assert!(json.starts_with('{') && json.ends_with('}'));
+    assert_eq!(json_field(&json, "state"), meta.state_label());
+    assert_eq!(json_field(&json, "observation_count"), "3");
+    for (key, value) in [
+        ("self_drift", meta.self_drift),
+        ("self_resonance", meta.self_resonance),
+        ("confidence", meta.confidence),
+        ("clarity", meta.clarity),
+        ("doubt", meta.doubt),
+    ] {
+        let parsed: f32 = json_field(&json, key).parse().unwrap();
+        assert!((parsed - value).abs() < 1e-4, "{key}: {parsed} vs {value}");
+    }
+}
+
+#[test]
+fn test_measurement_confidence_discounts_meta_confidence() {
+    let observe = |ctx: ObservationContext| {
+        let mut meta = MetaCognition::new();
+        meta.observe_with(0.1, 0.9, "Normal", 0.01, &ctx);
+        meta.confidence
+    };
+
+    let one_word = observe(ObservationContext {
+        measurement_confidence: Some(0.3),
+        ..ObservationContext::default()
+    });
+    let full_sentence = observe(ObservationContext {
+        measurement_confidence: Some(1.0),
+        ..ObservationContext::default()
+    });
+    let legacy = observe(ObservationContext::default());
+
+    assert!(one_word < 0.5, "one-word confidence {one_word}");
+    assert!(full_sentence > one_word + 0.2);
+    assert!((full_sentence - legacy).abs() < 1e-6);
+
+    let fresh_state = observe(ObservationContext {
+        steps_in_state: Some(0),
+        ..ObservationContext::default()
+    });
+    let settled_state = observe(ObservationContext {
+        steps_in_state: Some(5),
+        ..ObservationContext::default()
+    });
+    assert!(fresh_state < settled_state);
+    assert!((settled_state - legacy).abs() < 1e-6);
+}
+
+#[test]
+fn test_rising_self_drift_trend_needs_awareness() {
+    let mut stabilizer = MetaStabilizer::new(0.3);
+    let mut meta = MetaCognition::new();
+    assert_eq!(stabilizer.self_drift_trend(), 0.0);
+
+    // Confident measurements, but sync corrections creep upward
+    for correction in [0.0, 0.01, 0.02, 0.03, 0.04] {
+        meta.observe(0.1, 0.9, "Normal", correction);
+        stabilizer.update(&meta);
+    }
+
+    let (ema_drift, ema_conf) = stabilizer.get_stable_metrics();
+    assert!(ema_drift < 0.4 && ema_conf >= 0.5);
+    assert!(stabilizer.self_drift_trend() > 0.0);
+    assert!(stabilizer.needs_more_awareness());
+}
+
+#[test]
+fn test_steady_self_drift_has_flat_trend() {
+    let mut stabilizer = MetaStabilizer::new(0.3);
+    let mut meta = MetaCognition::new();
+    for _ in 0..10 {
+        meta.observe(0.1, 0.9, "Normal", 0.0);
+        stabilizer.update(&meta);
+    }
+    assert_eq!(stabilizer.self_drift_trend(), 0.0);
+    assert!(!stabilizer.needs_more_awareness());
+}
@@ -0,0 +1,30 @@
+//! Helpers shared by the integration tests; each test binary uses a subset.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Fresh path under the system temp dir. `name` keeps its extension, with
+/// the process id and a per-process counter spliced in before it, so
+/// parallel tests and concurrent runs never share a file.
+pub fn temp_path(name: &str) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    let mut file = format!("{}_{}_{}", stem, std::process::id(), n);
+    if !ext.is_empty() {
+        file.push('.');
+        file.push_str(ext);
+    }
+    std::env::temp_dir().join(file)
+}
+
+/// Raw value text of `"key":` in a flat JSON object, without quotes.
+pub fn json_field<'a>(json: &'a str, key: &str) -> &'a str {
+    let needle = format!("\"{}\":", key);
+    let start = json.find(&needle).expect("field present") + needle.len();
+    let rest = &json[start..];
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    rest[..end].trim().trim_matches('"')
+}
@@ -2,8 +2,16 @@
 //!
 //! Tests the compassion detection and adjustment system
 
-use liminal_voice_core::compassion::{CompassionAdjustments, CompassionMetrics, SufferingType};
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::compassion::{
+    self, CompassionAdjustments, CompassionCfg, CompassionMetrics, CompassionSeed, SufferingType,
+    SufferingWeights, SupportTemplates,
+};
 use liminal_voice_core::prosody::ToneTag;
+use liminal_voice_core::session::SyncDelta;
+use liminal_voice_core::spark;
 
 #[test]
 fn test_no_suffering_baseline() {
@@ -37,6 +45,17 @@ fn test_overheat_state_increases_suffering() {
     assert!(comp.user_suffering > 0.2);
 }
 
+#[test]
+fn test_panic_state_outweighs_overheat() {
+    let mut overheat = CompassionMetrics::new();
+    overheat.detect_suffering(0.3, 0.6, ToneTag::Calm, 150.0, "Overheat", false);
+
+    let mut panic = CompassionMetrics::new();
+    panic.detect_suffering(0.3, 0.6, ToneTag::Calm, 150.0, "Panic", false);
+
+    assert!(panic.user_suffering > overheat.user_suffering);
+}
+
 #[test]
 fn test_fast_energetic_speech_signals_anxiety() {
     let mut comp = CompassionMetrics::new();
@@ -213,7 +232,7 @@ fn test_status_message_format() {
     comp.calculate_kindness(false, 0.0, 0, 0.0);
     comp.update_compassion_level();
 
-    let msg = comp.status_message();
+    let msg = comp.status_message(false);
 
     // Should contain "Compassion" and "suffering" at minimum
     assert!(msg.contains("Compassion"));
@@ -253,3 +272,275 @@ fn test_suffering_count_increments() {
     comp.detect_suffering(0.7, 0.3, ToneTag::Energetic, 180.0, "Overheat", false);
     assert!(comp.suffering_count > 1);
 }
+
+#[test]
+fn test_affect_tones_raise_suffering() {
+    let mut neutral = CompassionMetrics::new();
+    neutral.detect_suffering(0.3, 0.6, ToneTag::Neutral, 150.0, "Normal", false);
+
+    let mut anxious = CompassionMetrics::new();
+    anxious.detect_suffering(0.3, 0.6, ToneTag::Anxious, 150.0, "Normal", false);
+
+    let mut sad = CompassionMetrics::new();
+    sad.detect_suffering(0.3, 0.6, ToneTag::Sad, 150.0, "Normal", false);
+
+    assert!(anxious.user_suffering > neutral.user_suffering);
+    assert!(sad.user_suffering > neutral.user_suffering);
+}
+
+#[test]
+fn test_compassion_seed_round_trip() {
+    let path = temp_path("liminal_compassion.jsonl");
+    let path = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+    assert!(compassion::load_seed(&path).is_none());
+
+    let mut comp = CompassionMetrics::new();
+    comp.compassion_level = 0.5;
+    compassion::save_seed(&path, &comp).unwrap();
+    comp.compassion_level = 0.72;
+    compassion::save_seed(&path, &comp).unwrap();
+
+    let seed = compassion::load_seed(&path).expect("seed saved");
+    assert!((seed.compassion_level - 0.72).abs() < 1e-5);
+    assert!(seed.ts_unix > 0);
+    // Each save replaces the previous seed instead of appending to it.
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_compassion_seed_decays_with_time() {
+    let seed = CompassionSeed {
+        compassion_level: 0.8,
+        ts_unix: 1_000,
+    };
+
+    let fresh = compassion::decay_seed(&seed, 1_000, 180);
+    assert_eq!(fresh, seed);
+
+    let half = compassion::decay_seed(&seed, 1_000 + 180 * 60, 180);
+    assert!((half.compassion_level - 0.4).abs() < 1e-4);
+
+    let stale = compassion::decay_seed(&seed, 1_000 + 24 * 3600, 180);
+    assert!(stale.compassion_level < 0.01);
+}
+
+#[test]
+fn test_warm_start_carries_level_and_fades() {
+    let mut comp = CompassionMetrics::new();
+    comp.warm_start(&CompassionSeed {
+        compassion_level: 0.9,
+        ts_unix: 0,
+    });
+
+    comp.detect_suffering(0.1, 0.9, ToneTag::Calm, 150.0, "Normal", false);
+    comp.calculate_kindness(false, 0.0, 0, 0.0);
+    comp.update_compassion_level();
+    let first = comp.compassion_level;
+    assert!((first - 0.9).abs() < 1e-6);
+
+    comp.update_compassion_level();
+    assert!(comp.compassion_level < first);
+}
+
+#[test]
+fn test_support_message_escalates_with_severity() {
+    let mut comp = CompassionMetrics::new();
+    comp.detect_suffering(0.1, 0.9, ToneTag::Calm, 150.0, "Normal", false);
+    assert!(comp.support_message().is_empty());
+
+    comp.suffering_type = SufferingType::Moderate;
+    let moderate = comp.support_message();
+    comp.suffering_type = SufferingType::Severe;
+    let severe = comp.support_message();
+
+    assert!(!moderate.is_empty());
+    assert_ne!(moderate, severe);
+    assert!(severe.contains("really hard"));
+
+    comp.suffering_streak = 5;
+    let long = comp.support_message();
+    assert!(long.starts_with(&severe));
+    assert!(long.len() > severe.len());
+}
+
+#[test]
+fn test_support_templates_can_be_overridden() {
+    let cfg = CompassionCfg {
+        support: SupportTemplates {
+            moderate: "I hear you.".to_string(),
+            severe: "I'm right here.".to_string(),
+            grief: "I'm sorry.".to_string(),
+            long_streak_note: String::new(),
+            long_streak: 1,
+        },
+        ..CompassionCfg::default()
+    };
+    let mut comp = CompassionMetrics::with_cfg(cfg);
+    comp.suffering_type = SufferingType::Severe;
+    comp.suffering_streak = 4;
+    assert_eq!(comp.support_message(), "I'm right here.");
+}
+
+#[test]
+fn test_zero_wpm_weight_ignores_fast_speech() {
+    let mut default = CompassionMetrics::new();
+    default.detect_suffering(0.3, 0.7, ToneTag::Energetic, 210.0, "Normal", false);
+    assert!(default.user_suffering > 0.0);
+
+    let cfg = CompassionCfg {
+        weights: SufferingWeights {
+            fast_speech: 0.0,
+            ..SufferingWeights::default()
+        },
+        ..CompassionCfg::default()
+    };
+    let mut tuned = CompassionMetrics::with_cfg(cfg);
+    tuned.detect_suffering(0.3, 0.7, ToneTag::Energetic, 210.0, "Normal", false);
+    assert_eq!(tuned.user_suffering, 0.0);
+    assert_eq!(tuned.suffering_type, SufferingType::None);
+}
+
+#[test]
+fn test_activation_threshold_is_configurable() {
+    let cfg = CompassionCfg {
+        activation_threshold: 0.9,
+        ..CompassionCfg::default()
+    };
+    let mut comp = CompassionMetrics::with_cfg(cfg);
+    comp.compassion_level = 0.8;
+    assert!(!comp.should_activate_compassion());
+    comp.cfg.activation_threshold = 0.5;
+    assert!(comp.should_activate_compassion());
+}
+
+#[test]
+fn test_slow_sad_pattern_reads_as_grief() {
+    let mut grief = CompassionMetrics::new();
+    grief.detect_suffering(0.3, 0.45, ToneTag::Sad, 100.0, "Normal", false);
+    assert_eq!(grief.suffering_type, SufferingType::Grief);
+    assert!(grief.should_offer_support());
+    assert_ne!(grief.support_message(), String::new());
+
+    let mut anxious = CompassionMetrics::new();
+    anxious.detect_suffering(0.75, 0.45, ToneTag::Energetic, 200.0, "Overheat", false);
+    assert!(matches!(
+        anxious.suffering_type,
+        SufferingType::Moderate | SufferingType::Severe
+    ));
+
+    // Sad but brisk speech stays on the regular scale
+    let mut sad = CompassionMetrics::new();
+    sad.detect_suffering(0.3, 0.45, ToneTag::Sad, 160.0, "Normal", false);
+    assert_ne!(sad.suffering_type, SufferingType::Grief);
+}
+
+#[test]
+fn test_grief_adjustments_favor_space_over_pace() {
+    let mut grief = CompassionMetrics::new();
+    grief.detect_suffering(0.3, 0.45, ToneTag::Sad, 100.0, "Normal", false);
+    grief.compassion_level = 0.8;
+
+    let mut anxious = CompassionMetrics::new();
+    anxious.detect_suffering(0.75, 0.45, ToneTag::Energetic, 200.0, "Overheat", false);
+    anxious.compassion_level = 0.8;
+
+    let grief_adj = CompassionAdjustments::from_compassion(&grief);
+    let anxious_adj = CompassionAdjustments::from_compassion(&anxious);
+    assert!(grief_adj.pause_adjustment_ms > anxious_adj.pause_adjustment_ms);
+    assert!(grief_adj.pace_adjustment.abs() < anxious_adj.pace_adjustment.abs());
+}
+
+#[test]
+fn test_recovery_streak_tracks_calming_turns() {
+    let mut comp = CompassionMetrics::new();
+    comp.detect_recovery(0.7, 0.4);
+    assert_eq!(comp.recovery_streak, 0);
+    comp.detect_recovery(0.6, 0.5);
+    comp.detect_recovery(0.5, 0.6);
+    assert_eq!(comp.recovery_streak, 2);
+    assert!(comp.is_recovering());
+
+    // Drift creeping back up breaks the streak
+    comp.detect_recovery(0.55, 0.65);
+    assert_eq!(comp.recovery_streak, 0);
+}
+
+#[test]
+fn test_sustained_recovery_lowers_compassion() {
+    fn run(recovering: bool) -> f32 {
+        let mut comp = CompassionMetrics::new();
+        comp.detect_suffering(0.95, 0.2, ToneTag::Energetic, 200.0, "Overheat", true);
+        comp.calculate_kindness(true, -0.1, 50, 0.05);
+        comp.update_compassion_level();
+        assert!(comp.should_activate_compassion());
+        comp.warm_start(&CompassionSeed {
+            compassion_level: comp.compassion_level,
+            ts_unix: 0,
+        });
+
+        for turn in 0..4 {
+            let step = turn as f32 * 0.05;
+            let (drift, res) = if recovering {
+                (0.45 - step, 0.65 + step)
+            } else {
+                (0.45, 0.65)
+            };
+            comp.detect_suffering(drift, res, ToneTag::Calm, 140.0, "Normal", false);
+            comp.calculate_kindness(true, -0.1, 50, 0.05);
+            comp.detect_recovery(drift, res);
+            comp.update_compassion_level();
+        }
+        comp.compassion_level
+    }
+
+    let recovered = run(true);
+    let steady = run(false);
+    assert!(recovered < CompassionCfg::default().activation_threshold);
+    assert!(recovered < steady);
+}
+
+#[test]
+fn test_rephrased_turn_raises_kindness() {
+    let sync = SyncDelta {
+        pace_delta: -0.01,
+        pause_delta_ms: 5,
+        res_boost: 0.0,
+        drift_relief: 0.0,
+    };
+
+    let mut plain = CompassionMetrics::new();
+    plain.record_turn_kindness(Some("warn:high_drift"), Some(&sync));
+
+    let mut rephrased = CompassionMetrics::new();
+    rephrased.record_turn_kindness(Some("rephrased:both"), Some(&sync));
+
+    assert!(rephrased.response_kindness > plain.response_kindness + 0.1);
+
+    let mut idle = CompassionMetrics::new();
+    idle.record_turn_kindness(None, None);
+    assert_eq!(idle.response_kindness, 0.5);
+}
+
+#[test]
+fn test_kindness_history_has_one_entry_per_turn() {
+    let mut comp = CompassionMetrics::new();
+    let cycles = 5;
+    for idx in 0..cycles {
+        let flag = if idx % 2 == 0 {
+            Some("rephrased:both")
+        } else {
+            None
+        };
+        comp.record_turn_kindness(flag, None);
+    }
+
+    assert_eq!(comp.kindness_history.len(), cycles);
+    assert_eq!(comp.kindness_history[0], 0.7);
+    assert_eq!(comp.kindness_history[1], 0.5);
+    assert_eq!(
+        spark::sparkline(&comp.kindness_history).chars().count(),
+        cycles
+    );
+}
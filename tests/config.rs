@@ -0,0 +1,443 @@
+use liminal_voice_core::config::{self, ColorMode, Config, VERBOSITY_QUIET, VERBOSITY_VERBOSE};
+use liminal_voice_core::softguard::RephraseStrategy;
+
+fn args(items: &[&str]) -> Vec<String> {
+    items.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn quiet_flag_sets_verbosity_zero() {
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["-q"]));
+    assert_eq!(cfg.verbosity, VERBOSITY_QUIET);
+    assert!(cfg.is_quiet());
+
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["--quiet"]));
+    assert_eq!(cfg.verbosity, 0);
+}
+
+#[test]
+fn verbose_flag_raises_verbosity() {
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["-v"]));
+    assert_eq!(cfg.verbosity, VERBOSITY_VERBOSE);
+    assert!(cfg.is_verbose());
+}
+
+#[test]
+fn short_aliases_match_long_flags() {
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["-c", "7", "-m", "Headset"]));
+    assert_eq!(cfg.cycles, 7);
+    assert_eq!(cfg.mode, "headset");
+}
+
+#[test]
+fn sync_seed_path_flag_sets_path() {
+    let mut cfg = Config::default();
+    assert!(cfg.sync_seed_path.is_none());
+    config::apply_args(&mut cfg, args(&["--sync-seed-path", "state/seeds.jsonl"]));
+    assert_eq!(cfg.sync_seed_path.as_deref(), Some("state/seeds.jsonl"));
+}
+
+#[test]
+fn per_state_sync_rates_parse_and_clamp() {
+    let mut cfg = Config::default();
+    assert!(cfg.sync_lr_overheat.is_none());
+    config::apply_args(
+        &mut cfg,
+        args(&["--sync-lr-overheat", "0.4", "--sync-lr-cooldown", "1.5"]),
+    );
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.sync_lr_overheat, Some(0.4));
+    assert_eq!(cfg.sync_lr_cooldown, Some(1.0));
+    assert!(cfg.sync_lr_warming.is_none());
+    assert_eq!(notices.len(), 1);
+}
+
+#[test]
+fn validate_rejects_inverted_sync_pause_clamp() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&["--sync-pause-min", "30", "--sync-pause-max", "10"]),
+    );
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.sync_pause_clamp_ms, (-20, 40));
+    assert_eq!(notices.len(), 1);
+    assert!(notices[0].starts_with("[config] sync_pause_min 30"));
+
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["--sync-pause-max", "80"]));
+    assert!(config::validate(&mut cfg).is_empty());
+    assert_eq!(cfg.sync_pause_clamp_ms, (-20, 80));
+}
+
+#[test]
+fn compassion_path_flag_sets_path() {
+    let mut cfg = Config::default();
+    assert!(cfg.compassion_path.is_none());
+    config::apply_args(
+        &mut cfg,
+        args(&["--compassion-path", "state/compassion.jsonl"]),
+    );
+    assert_eq!(
+        cfg.compassion_path.as_deref(),
+        Some("state/compassion.jsonl")
+    );
+}
+
+#[test]
+fn compassion_weight_flags_parse() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--compassion-overheat-weight",
+            "0.1",
+            "--compassion-wpm-weight",
+            "0",
+        ]),
+    );
+    assert_eq!(cfg.compassion_overheat_weight, 0.1);
+    assert_eq!(cfg.compassion_wpm_weight, 0.0);
+}
+
+#[test]
+fn awareness_path_flag_sets_path() {
+    let mut cfg = Config::default();
+    assert!(cfg.awareness_path.is_none());
+    config::apply_args(&mut cfg, args(&["--awareness-path", "state/meta.jsonl"]));
+    assert_eq!(cfg.awareness_path.as_deref(), Some("state/meta.jsonl"));
+}
+
+#[test]
+fn log_append_and_rotation_flags() {
+    let mut cfg = Config::default();
+    assert!(!cfg.log_append);
+    assert!(cfg.log_max_bytes.is_none());
+    config::apply_args(&mut cfg, args(&["--log-append", "--log-max-bytes", "4096"]));
+    assert!(cfg.log_append);
+    assert_eq!(cfg.log_max_bytes, Some(4096));
+    config::apply_args(&mut cfg, args(&["--log-max-bytes", "0"]));
+    assert!(cfg.log_max_bytes.is_none());
+    assert!(!cfg.log_flush_each);
+    config::apply_args(&mut cfg, args(&["--log-flush-each"]));
+    assert!(cfg.log_flush_each);
+}
+
+#[test]
+fn metrics_window_and_out_flags() {
+    let mut cfg = Config::default();
+    assert!(cfg.metrics_window.is_none());
+    config::apply_args(&mut cfg, args(&["--metrics-window", "8"]));
+    assert_eq!(cfg.metrics_window, Some(8));
+    config::apply_args(&mut cfg, args(&["--metrics-window", "0"]));
+    assert!(cfg.metrics_window.is_none());
+    config::apply_args(&mut cfg, args(&["--metrics-out", "out/metrics.prom"]));
+    assert_eq!(cfg.metrics_out.as_deref(), Some("out/metrics.prom"));
+}
+
+#[test]
+fn viz_output_flags() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.color, ColorMode::Auto);
+    config::apply_args(&mut cfg, args(&["--color", "never"]));
+    assert_eq!(cfg.color, ColorMode::Never);
+    assert!(!cfg.color.enabled());
+    config::apply_args(&mut cfg, args(&["--color", "ALWAYS"]));
+    assert!(cfg.color.enabled());
+    config::apply_args(&mut cfg, args(&["--color", "rainbow"]));
+    assert_eq!(cfg.color, ColorMode::Always);
+    assert!(!cfg.ascii);
+    config::apply_args(&mut cfg, args(&["--ascii"]));
+    assert!(cfg.ascii);
+    assert!(cfg.viz_width.is_none());
+    config::apply_args(&mut cfg, args(&["--viz-width", "72"]));
+    assert_eq!(cfg.viz_width, Some(72));
+}
+
+#[test]
+fn trend_deadband_flag_is_clamped() {
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["--trend-deadband", "0.05"]));
+    assert_eq!(cfg.trend_deadband, 0.05);
+    config::apply_args(&mut cfg, args(&["--trend-deadband", "2"]));
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.trend_deadband, 0.5);
+    assert!(notices.iter().any(|n| n.contains("trend_deadband")));
+}
+
+#[test]
+fn mode_is_unset_until_given() {
+    let mut cfg = Config::default();
+    assert!(cfg.mode.is_empty());
+    config::apply_args(&mut cfg, args(&["--mode", "Terminal"]));
+    assert_eq!(cfg.mode, "terminal");
+}
+
+#[test]
+fn memory_max_age_flag_sets_days() {
+    let mut cfg = Config::default();
+    assert!(cfg.memory_max_age_days.is_none());
+    config::apply_args(&mut cfg, args(&["--memory-max-age-days", "30"]));
+    assert_eq!(cfg.memory_max_age_days, Some(30));
+    config::apply_args(&mut cfg, args(&["--memory-max-age-days", "0"]));
+    assert!(cfg.memory_max_age_days.is_none());
+}
+
+#[test]
+fn memory_alpha_flag_is_clamped() {
+    let mut cfg = Config::default();
+    assert!(cfg.memory_alpha > 0.0);
+    config::apply_args(&mut cfg, args(&["--memory-alpha", "0"]));
+    assert_eq!(cfg.memory_alpha, 0.0);
+    config::apply_args(&mut cfg, args(&["--memory-alpha", "1.5"]));
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.memory_alpha, 1.0);
+    assert!(notices.iter().any(|n| n.contains("memory_alpha")));
+}
+
+#[test]
+fn emote_window_flag_sets_window() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.emote_window, 1);
+    config::apply_args(&mut cfg, args(&["--emote-window", "5"]));
+    assert_eq!(cfg.emote_window, 5);
+    config::apply_args(&mut cfg, args(&["--emote-window", "0"]));
+    assert_eq!(cfg.emote_window, 1);
+}
+
+#[test]
+fn emote_decay_flags_set_anchors() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--emote-neutral-drift",
+            "0.25",
+            "--emote-neutral-wpm",
+            "500",
+            "--emote-tone-keep",
+            "0.5",
+        ]),
+    );
+    assert_eq!(cfg.emote_decay.neutral_drift, 0.25);
+    assert_eq!(cfg.emote_decay.neutral_res, 0.70);
+    assert_eq!(cfg.emote_decay.tone_keep, 0.5);
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.emote_decay.neutral_wpm, 300.0);
+    assert!(notices.iter().any(|n| n.contains("emote_neutral_wpm")));
+}
+
+#[test]
+fn emote_keep_flag_sets_limit() {
+    let mut cfg = Config::default();
+    assert!(cfg.emote_keep.is_none());
+    config::apply_args(&mut cfg, args(&["--emote-keep", "50"]));
+    assert_eq!(cfg.emote_keep, Some(50));
+}
+
+#[test]
+fn emote_profile_flag_sets_profile() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.emote_profile, "default");
+    config::apply_args(&mut cfg, args(&["--emote-profile", "alice"]));
+    assert_eq!(cfg.emote_profile, "alice");
+    config::apply_args(&mut cfg, args(&["--emote-profile", " "]));
+    assert_eq!(cfg.emote_profile, "alice");
+}
+
+#[test]
+fn latency_flags_disable_and_scale_simulation() {
+    let mut cfg = Config::default();
+    assert!(cfg.simulate_latency);
+    assert_eq!(cfg.latency_scale, 1.0);
+    config::apply_args(&mut cfg, args(&["--no-latency", "--latency-scale", "0.25"]));
+    assert!(!cfg.simulate_latency);
+    assert_eq!(cfg.latency_scale, 0.25);
+
+    cfg.latency_scale = 50.0;
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.latency_scale, config::LATENCY_SCALE_RANGE.1);
+    assert_eq!(notices.len(), 1);
+}
+
+#[test]
+fn audio_flag_sets_path() {
+    let mut cfg = Config::default();
+    assert!(cfg.audio_path.is_none());
+    config::apply_args(&mut cfg, args(&["--audio", "clip.wav"]));
+    assert_eq!(cfg.audio_path.as_deref(), Some("clip.wav"));
+}
+
+#[test]
+fn ssml_flag_enables_markup() {
+    let mut cfg = Config::default();
+    assert!(!cfg.ssml);
+    config::apply_args(&mut cfg, args(&["--ssml"]));
+    assert!(cfg.ssml);
+}
+
+#[test]
+fn crit_flags_set_alert_tiers() {
+    let mut cfg = Config::default();
+    assert!(cfg.crit_drift.is_none() && cfg.crit_res.is_none());
+    config::apply_args(
+        &mut cfg,
+        args(&["--crit-drift", "0.5", "--crit-res", "0.4"]),
+    );
+    assert_eq!(cfg.crit_drift, Some(0.5));
+    assert_eq!(cfg.crit_res, Some(0.4));
+}
+
+#[test]
+fn strict_streak_flag_sets_length() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.strict_streak, 0);
+    config::apply_args(&mut cfg, args(&["--strict", "--strict-streak", "2"]));
+    assert!(cfg.strict);
+    assert_eq!(cfg.strict_streak, 2);
+}
+
+#[test]
+fn session_id_flag_sets_fixed_id() {
+    let mut cfg = Config::default();
+    assert!(cfg.session_id.is_none());
+    config::apply_args(&mut cfg, args(&["--session-id", "kiosk"]));
+    assert_eq!(cfg.session_id.as_deref(), Some("kiosk"));
+}
+
+#[test]
+fn validate_clamps_sync_rates() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--sync-lr-fast",
+            "2.0",
+            "--sync-lr-slow",
+            "-0.5",
+            "--sync-step",
+            "0.3",
+        ]),
+    );
+
+    let notices = config::validate(&mut cfg);
+    assert_eq!(cfg.sync_lr_fast, 1.0);
+    assert_eq!(cfg.sync_lr_slow, 0.0);
+    assert_eq!(cfg.sync_step, 0.1);
+    assert_eq!(notices.len(), 3);
+    assert!(notices.contains(&"[config] clamped sync_lr_fast 2.0 -> 1.0".to_string()));
+    assert!(notices.iter().all(|n| n.starts_with("[config] clamped")));
+}
+
+#[test]
+fn validate_leaves_defaults_untouched() {
+    let mut cfg = Config::default();
+    let notices = config::validate(&mut cfg);
+    assert!(notices.is_empty());
+    assert_eq!(cfg, Config::default());
+}
+
+#[test]
+fn validate_rejects_inverted_prosody_thresholds() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--prosody-calm-wpm",
+            "200",
+            "--prosody-energetic-wpm",
+            "150",
+        ]),
+    );
+
+    let notices = config::validate(&mut cfg);
+    assert_eq!(notices.len(), 1);
+    assert!(notices[0].contains("prosody_calm_wpm"));
+    assert_eq!(cfg.prosody_calm_wpm, 120.0);
+    assert_eq!(cfg.prosody_energetic_wpm, 180.0);
+}
+
+#[test]
+fn seed_flag_enables_seeded_runs() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.seed, None);
+    config::apply_args(&mut cfg, args(&["--seed", "1234"]));
+    assert_eq!(cfg.seed, Some(1234));
+
+    config::apply_args(&mut cfg, args(&["--seed", "not-a-number"]));
+    assert_eq!(cfg.seed, Some(1234));
+}
+
+#[test]
+fn lexicon_flag_sets_path() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.lexicon_path, None);
+    config::apply_args(&mut cfg, args(&["--lexicon", "domain.csv"]));
+    assert_eq!(cfg.lexicon_path.as_deref(), Some("domain.csv"));
+}
+
+#[test]
+fn guard_banned_flag_parses_word_list() {
+    let mut cfg = Config::default();
+    config::apply_args(&mut cfg, args(&["--guard-banned", "Darn, heck,,"]));
+    assert_eq!(
+        cfg.guard_banned,
+        vec!["darn".to_string(), "heck".to_string()]
+    );
+}
+
+#[test]
+fn guard_strategy_flag_selects_strategy() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.guard_strategy, RephraseStrategy::Recenter);
+    config::apply_args(&mut cfg, args(&["--guard-strategy", "Soften"]));
+    assert_eq!(cfg.guard_strategy, RephraseStrategy::Soften);
+    config::apply_args(&mut cfg, args(&["--guard-strategy", "unknown"]));
+    assert_eq!(cfg.guard_strategy, RephraseStrategy::Soften);
+}
+
+#[test]
+fn guard_warn_low_res_flag_opts_in() {
+    let mut cfg = Config::default();
+    assert!(!cfg.guard_warn_low_res);
+    config::apply_args(&mut cfg, args(&["--guard-warn-low-res"]));
+    assert!(cfg.guard_warn_low_res);
+}
+
+#[test]
+fn stabilizer_flags_parse() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--stab-median",
+            "--stab-panic",
+            "0.8",
+            "--stab-state-path",
+            "stab.jsonl",
+        ]),
+    );
+    assert!(cfg.stab_median);
+    assert!((cfg.stab_panic - 0.8).abs() < f32::EPSILON);
+    assert_eq!(cfg.stab_state_path.as_deref(), Some("stab.jsonl"));
+}
+
+#[test]
+fn stabilizer_advice_flags_override_table() {
+    let mut cfg = Config::default();
+    config::apply_args(
+        &mut cfg,
+        args(&[
+            "--stab-overheat-pace",
+            "-0.12",
+            "--stab-warming-pause",
+            "25",
+        ]),
+    );
+    assert!((cfg.stab_advice.overheat.pace_delta + 0.12).abs() < f32::EPSILON);
+    assert_eq!(cfg.stab_advice.warming.pause_delta_ms, 25);
+}
@@ -1,10 +1,14 @@
-use liminal_voice_core::device::{self, DeviceMode};
+use liminal_voice_core::alerts::{self, AlertStats};
+use liminal_voice_core::device::{self, DeviceMode, DeviceProfile};
 
 #[test]
 fn detect_modes() {
     assert_eq!(device::detect("phone"), DeviceMode::Phone);
     assert_eq!(device::detect("headset"), DeviceMode::Headset);
     assert_eq!(device::detect("terminal"), DeviceMode::Terminal);
+    assert_eq!(device::detect("car"), DeviceMode::Car);
+    assert_eq!(device::detect("SmartSpeaker"), DeviceMode::SmartSpeaker);
+    assert_eq!(device::detect("smart-speaker"), DeviceMode::SmartSpeaker);
     assert_eq!(device::detect("UNKNOWN"), DeviceMode::Phone);
 }
 
@@ -28,9 +32,151 @@ fn profile_defaults_match() {
 
 #[test]
 fn profile_ranges() {
-    for mode in [DeviceMode::Phone, DeviceMode::Headset, DeviceMode::Terminal] {
+    for mode in [
+        DeviceMode::Phone,
+        DeviceMode::Headset,
+        DeviceMode::Terminal,
+        DeviceMode::Car,
+        DeviceMode::SmartSpeaker,
+        DeviceMode::Auto,
+    ] {
         let prof = device::profile(&mode);
         assert!((0.8..=1.2).contains(&prof.pace_factor));
         assert!((20..=200).contains(&(prof.pause_ms as i32)));
     }
 }
+
+#[test]
+fn far_field_modes_are_louder_and_slower_than_headset() {
+    let headset = device::profile(&DeviceMode::Headset);
+    for mode in [DeviceMode::Car, DeviceMode::SmartSpeaker] {
+        let prof = device::profile(&mode);
+        assert!(prof.gain_db > headset.gain_db);
+        assert!(prof.pace_factor < headset.pace_factor);
+        assert!(prof.pause_ms > headset.pause_ms);
+    }
+}
+
+#[test]
+fn mode_names_round_trip() {
+    for mode in [
+        DeviceMode::Phone,
+        DeviceMode::Headset,
+        DeviceMode::Terminal,
+        DeviceMode::Car,
+        DeviceMode::SmartSpeaker,
+        DeviceMode::Auto,
+    ] {
+        assert_eq!(mode.to_string().parse::<DeviceMode>(), Ok(mode));
+        assert_eq!(
+            mode.to_string().to_uppercase().parse::<DeviceMode>(),
+            Ok(mode)
+        );
+    }
+}
+
+#[test]
+fn unknown_mode_name_is_an_error() {
+    assert!("walkie-talkie".parse::<DeviceMode>().is_err());
+    assert!("".parse::<DeviceMode>().is_err());
+    assert_eq!(device::detect("walkie-talkie"), DeviceMode::Phone);
+}
+
+#[test]
+fn blend_lands_between_its_endpoints() {
+    let phone = device::profile(&DeviceMode::Phone);
+    let headset = device::profile(&DeviceMode::Headset);
+    let mid = device::blend(&phone, &headset, 0.5);
+
+    let between = |x: f32, a: f32, b: f32| x >= a.min(b) && x <= a.max(b);
+    assert!(between(mid.gain_db, phone.gain_db, headset.gain_db));
+    assert!(between(
+        mid.pace_factor,
+        phone.pace_factor,
+        headset.pace_factor
+    ));
+    assert!(between(
+        mid.pause_ms as f32,
+        phone.pause_ms as f32,
+        headset.pause_ms as f32
+    ));
+    assert_eq!(device::profile(&DeviceMode::Auto), mid);
+
+    assert_eq!(device::blend(&phone, &headset, 0.0), phone);
+    assert_eq!(device::blend(&phone, &headset, 1.0), headset);
+}
+
+#[test]
+fn blend_clamps_to_valid_ranges() {
+    let wild = DeviceProfile {
+        gain_db: 40.0,
+        pace_factor: 3.0,
+        pause_ms: 5_000,
+        baseline_drift: None,
+        baseline_res: None,
+    };
+    let out = device::blend(&wild, &wild, 0.5);
+    assert!((out.gain_db - 12.0).abs() < f32::EPSILON);
+    assert!((0.8..=1.2).contains(&out.pace_factor));
+    assert_eq!(out.pause_ms, 200);
+}
+
+#[test]
+fn profile_baselines_override_config_when_present() {
+    let headset = device::profile(&DeviceMode::Headset);
+    assert_eq!(headset.baselines(0.35, 0.65), (0.35, 0.65));
+
+    let noisy = DeviceProfile {
+        baseline_drift: Some(0.50),
+        ..headset
+    };
+    assert_eq!(noisy.baselines(0.35, 0.65), (0.50, 0.65));
+
+    let drifts = [0.30, 0.40, 0.45, 0.55];
+    let breaches = |prof: &DeviceProfile| {
+        let (base_drift, base_res) = prof.baselines(0.35, 0.65);
+        let mut stats = AlertStats::default();
+        for drift in drifts {
+            alerts::update(&mut stats, drift, 0.8, base_drift, base_res);
+        }
+        stats.drift_breaches
+    };
+    assert_eq!(breaches(&headset), 3);
+    assert_eq!(breaches(&noisy), 1);
+}
+
+fn env_of<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    move |key| {
+        vars.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    }
+}
+
+#[test]
+fn auto_detect_follows_the_audio_device_hint() {
+    let hinted = env_of(&[
+        ("LIMINAL_AUDIO_DEVICE", "Headset"),
+        ("SSH_TTY", "/dev/pts/0"),
+    ]);
+    assert_eq!(device::auto_detect_from(hinted), DeviceMode::Headset);
+
+    let car = env_of(&[("LIMINAL_AUDIO_DEVICE", "car")]);
+    assert_eq!(device::auto_detect_from(car), DeviceMode::Car);
+}
+
+#[test]
+fn auto_detect_falls_back_through_terminal_to_phone() {
+    let ssh = env_of(&[("SSH_TTY", "/dev/pts/0"), ("TERM", "xterm-256color")]);
+    assert_eq!(device::auto_detect_from(ssh), DeviceMode::Terminal);
+
+    let console = env_of(&[("TERM", "linux")]);
+    assert_eq!(device::auto_detect_from(console), DeviceMode::Terminal);
+
+    let bogus_hint = env_of(&[
+        ("LIMINAL_AUDIO_DEVICE", "gramophone"),
+        ("TERM", "xterm-256color"),
+    ]);
+    assert_eq!(device::auto_detect_from(bogus_hint), DeviceMode::Phone);
+    assert_eq!(device::auto_detect_from(env_of(&[])), DeviceMode::Phone);
+}
@@ -1,27 +1,24 @@
 use std::fs;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use liminal_voice_core::device_memory::{self, DeviceMemoryStore};
-
-fn temp_file_path(label: &str) -> PathBuf {
-    let mut path = std::env::temp_dir();
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    path.push(format!(
-        "liminal_voice_core_{}_{}_{}.jsonl",
-        label,
-        std::process::id(),
-        nanos
-    ));
-    path
+
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::device_memory::{self, DeviceMemory, DeviceMemoryStore};
+
+fn sample(pace: f32, pause: f32, art: f32, drift: f32, res: f32) -> DeviceMemory {
+    DeviceMemory {
+        avg_pace: pace,
+        avg_pause: pause,
+        avg_articulation: art,
+        avg_drift: drift,
+        avg_res: res,
+        ..DeviceMemory::default()
+    }
 }
 
 #[test]
 fn load_missing_file_returns_empty() {
-    let path = temp_file_path("missing");
+    let path = temp_path("liminal_device_memory_missing.jsonl");
     let path_str = path.to_string_lossy().to_string();
     if path.exists() {
         let _ = fs::remove_file(&path);
@@ -34,7 +31,7 @@ fn load_missing_file_returns_empty() {
 
 #[test]
 fn update_and_persist_device_memory() {
-    let path = temp_file_path("persist");
+    let path = temp_path("liminal_device_memory_persist.jsonl");
     let path_str = path.to_string_lossy().to_string();
     if path.exists() {
         let _ = fs::remove_file(&path);
@@ -64,3 +61,203 @@ fn update_and_persist_device_memory() {
 
     let _ = fs::remove_file(&path_str);
 }
+
+#[test]
+fn legacy_pipe_file_is_rewritten_as_json() {
+    let path = temp_path("liminal_device_memory_legacy.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+    fs::write(
+        &path_str,
+        "Phone|1.100|65.0|0.750|0.250|0.750|2\nHeadset|0.980|42.0|0.810|0.180|0.820|5\n",
+    )
+    .expect("write legacy memory file");
+
+    let store = DeviceMemoryStore::load(&path_str);
+    assert_eq!(store.data.len(), 2);
+    store.save();
+
+    let text = fs::read_to_string(&path_str).expect("memory file contents");
+    assert!(!text.contains('|'));
+    for line in text.lines() {
+        assert!(line.starts_with('{') && line.ends_with('}'), "{}", line);
+    }
+    assert!(text.contains("\"device\":\"Phone\""));
+
+    let reloaded = DeviceMemoryStore::load(&path_str);
+    let phone = device_memory::suggest_profile(&reloaded, "Phone").expect("phone profile");
+    assert_eq!(phone.sessions, 2);
+    assert!((phone.avg_pace - 1.1).abs() < 1e-3);
+    assert!((phone.avg_pause - 65.0).abs() < 1e-3);
+    assert!((phone.avg_articulation - 0.75).abs() < 1e-3);
+    let headset = device_memory::suggest_profile(&reloaded, "Headset").expect("headset profile");
+    assert_eq!(headset.sessions, 5);
+    assert!((headset.avg_res - 0.82).abs() < 1e-3);
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn device_names_with_separators_survive_a_round_trip() {
+    let path = temp_path("liminal_device_memory_separators.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+
+    let mut store = DeviceMemoryStore::load(&path_str);
+    store.update("USB|\"Studio\" Mic", 1.0, 50.0, 0.8, 0.2, 0.8);
+    store.save();
+
+    let reloaded = DeviceMemoryStore::load(&path_str);
+    assert!(device_memory::suggest_profile(&reloaded, "USB|\"Studio\" Mic").is_some());
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn malformed_line_does_not_hide_good_devices() {
+    let path = temp_path("liminal_device_memory_corrupt.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+    fs::write(
+        &path_str,
+        concat!(
+            "{\"device\":\"Phone\",\"avg_pace\":1.050,\"avg_pause\":60.0,\"avg_articulation\":0.700,\"avg_drift\":0.200,\"avg_res\":0.800,\"sessions\":3}\n",
+            "{\"device\":\"Headset\",\"avg_pace\":1.0,\"avg_pau\n",
+            "Terminal|0.95|oops|0.7|0.2|0.8|1\n",
+        ),
+    )
+    .expect("write memory file");
+
+    let store = DeviceMemoryStore::load(&path_str);
+    assert_eq!(store.data.len(), 1);
+    let phone = device_memory::suggest_profile(&store, "Phone").expect("good device loads");
+    assert_eq!(phone.sessions, 3);
+    assert!((phone.avg_pace - 1.05).abs() < 1e-3);
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn tone_buckets_are_stored_separately() {
+    let path = temp_path("liminal_device_memory_tones.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+
+    let mut store = DeviceMemoryStore::load(&path_str);
+    store.update_tone("Phone", "Calm", &sample(0.9, 80.0, 0.8, 0.1, 0.9));
+    store.update_tone("Phone", "Energetic", &sample(1.2, 40.0, 0.7, 0.3, 0.7));
+    store.save();
+
+    let store = DeviceMemoryStore::load(&path_str);
+    let calm = device_memory::suggest_tone_profile(&store, "Phone", "Calm").expect("calm bucket");
+    let energetic = device_memory::suggest_tone_profile(&store, "Phone", "Energetic")
+        .expect("energetic bucket");
+    assert_eq!(calm.sessions, 1);
+    assert!((calm.avg_pace - 0.9).abs() < 1e-3);
+    assert!((energetic.avg_pace - 1.2).abs() < 1e-3);
+    assert!(store.data.contains_key("Phone:Calm"));
+
+    // An empty bucket falls back to the device-wide average of both.
+    let neutral =
+        device_memory::suggest_tone_profile(&store, "Phone", "Neutral").expect("device fallback");
+    assert_eq!(neutral.sessions, 2);
+    assert!((neutral.avg_pace - 1.05).abs() < 1e-3);
+    assert!(device_memory::suggest_tone_profile(&store, "Headset", "Calm").is_none());
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn ema_update_weights_recent_sessions_more_than_the_running_mean() {
+    let mut mean = DeviceMemoryStore::default();
+    let mut ema = DeviceMemoryStore {
+        alpha: Some(0.5),
+        ..DeviceMemoryStore::default()
+    };
+    for store in [&mut mean, &mut ema] {
+        for _ in 0..4 {
+            store.update("Phone", 1.0, 60.0, 0.7, 0.2, 0.8);
+        }
+        store.update("Phone", 1.2, 80.0, 0.9, 0.4, 0.6);
+    }
+
+    let mean = device_memory::suggest_profile(&mean, "Phone").unwrap();
+    let ema = device_memory::suggest_profile(&ema, "Phone").unwrap();
+    assert!((mean.avg_pace - 1.04).abs() < 1e-3);
+    assert!((ema.avg_pace - 1.1).abs() < 1e-3);
+    assert!(ema.avg_pause > mean.avg_pause);
+    assert_eq!(ema.sessions, 5);
+    assert!(ema.last_seen > 0);
+}
+
+#[test]
+fn prune_drops_entries_untouched_for_too_long() {
+    let path = temp_path("liminal_device_memory_prune.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+    let now = 1_700_000_000;
+    let day = 86_400;
+
+    let mut store = DeviceMemoryStore::load(&path_str);
+    store.update("Phone", 1.0, 60.0, 0.7, 0.2, 0.8);
+    store.update("Headset", 1.0, 40.0, 0.8, 0.1, 0.9);
+    store.update("Terminal", 0.95, 80.0, 0.7, 0.2, 0.8);
+    store.data.get_mut("Phone").unwrap().last_seen = now - 2 * day;
+    store.data.get_mut("Headset").unwrap().last_seen = now - 40 * day;
+    store.data.get_mut("Terminal").unwrap().last_seen = 0;
+    store.save();
+
+    let mut store = DeviceMemoryStore::load(&path_str);
+    assert_eq!(store.data["Headset"].last_seen, now - 40 * day);
+    assert_eq!(store.prune(now, 30), 1);
+    assert!(store.data.contains_key("Phone"));
+    assert!(!store.data.contains_key("Headset"));
+    assert!(store.data.contains_key("Terminal"));
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn remove_forgets_one_device_and_clear_forgets_all() {
+    let path = temp_path("liminal_device_memory_remove.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+
+    let mut store = DeviceMemoryStore::load(&path_str);
+    store.update_tone("Phone", "Calm", &sample(1.0, 60.0, 0.7, 0.2, 0.8));
+    store.update_tone("Headset", "Calm", &sample(1.0, 40.0, 0.8, 0.1, 0.9));
+    store.save();
+
+    assert!(store.remove("Phone"));
+    assert!(!store.remove("Phone"));
+    assert!(!store.remove("Car"));
+
+    let mut reloaded = DeviceMemoryStore::load(&path_str);
+    assert!(device_memory::suggest_profile(&reloaded, "Phone").is_none());
+    assert!(!reloaded.data.contains_key("Phone:Calm"));
+    assert!(device_memory::suggest_profile(&reloaded, "Headset").is_some());
+    assert!(reloaded.data.contains_key("Headset:Calm"));
+
+    reloaded.clear();
+    assert!(DeviceMemoryStore::load(&path_str).data.is_empty());
+
+    let _ = fs::remove_file(&path_str);
+}
+
+#[test]
+fn ema_keeps_tracking_a_step_change_after_many_sessions() {
+    let mut mean = DeviceMemoryStore::default();
+    let mut ema = DeviceMemoryStore {
+        alpha: Some(device_memory::DEFAULT_MEMORY_ALPHA),
+        ..DeviceMemoryStore::default()
+    };
+    for store in [&mut mean, &mut ema] {
+        for _ in 0..50 {
+            store.update("Phone", 1.0, 60.0, 0.7, 0.2, 0.8);
+        }
+        for _ in 0..5 {
+            store.update("Phone", 1.2, 90.0, 0.7, 0.2, 0.8);
+        }
+    }
+
+    let mean = device_memory::suggest_profile(&mean, "Phone").unwrap();
+    let ema = device_memory::suggest_profile(&ema, "Phone").unwrap();
+    // Five sessions after the step the mean has covered under a tenth of it.
+    assert!(mean.avg_pace - 1.0 < 0.02);
+    assert!(ema.avg_pace - 1.0 > 0.15);
+    assert!(ema.avg_pause > 80.0);
+}
@@ -1,13 +1,18 @@
 use std::fs;
 use std::io::Write;
 
+mod common;
+
+use common::temp_path;
 use liminal_voice_core::config::Config;
 use liminal_voice_core::dialog::{default_utterance, load_inputs};
 
 #[test]
 fn load_inputs_from_script() {
-    let mut cfg = Config::default();
-    cfg.script = Some("a;b;c".to_string());
+    let cfg = Config {
+        script: Some("a;b;c".to_string()),
+        ..Config::default()
+    };
 
     let items = load_inputs(&cfg);
     assert_eq!(items, vec!["a", "b", "c"]);
@@ -16,12 +21,7 @@ fn load_inputs_from_script() {
 #[test]
 fn load_inputs_from_file_trims_empty_lines() {
     let mut cfg = Config::default();
-    let dir = std::env::temp_dir();
-    let path = dir.join(format!(
-        "liminal_dialog_{}_{}.txt",
-        std::process::id(),
-        unique_suffix()
-    ));
+    let path = temp_path("liminal_dialog.txt");
     let mut file = fs::File::create(&path).expect("create temp file");
     writeln!(file, "first").unwrap();
     writeln!(file).unwrap();
@@ -38,20 +38,14 @@ fn load_inputs_from_file_trims_empty_lines() {
 
 #[test]
 fn load_inputs_fallback_to_defaults() {
-    let mut cfg = Config::default();
-    cfg.cycles = 3;
-    cfg.script = None;
-    cfg.inputs_path = None;
+    let cfg = Config {
+        cycles: 3,
+        script: None,
+        inputs_path: None,
+        ..Config::default()
+    };
 
     let items = load_inputs(&cfg);
     assert_eq!(items.len(), cfg.cycles);
     assert!(items.iter().all(|item| item == default_utterance()));
 }
-
-fn unique_suffix() -> u128 {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("time")
-        .as_nanos()
-}
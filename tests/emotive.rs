@@ -1,8 +1,12 @@
 use std::fs;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
 
-use liminal_voice_core::emotive::{self, EmoteSeed};
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::emotive::{self, EmoteDecayCfg, EmoteSeed};
+use liminal_voice_core::prosody::ToneTag;
 
 fn approx_eq(a: f32, b: f32) {
     assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
@@ -13,9 +17,10 @@ fn decay_no_elapsed_time_preserves_seed() {
     let seed = EmoteSeed {
         ema_drift: 0.41,
         ema_res: 0.63,
-        tone: "Calm".to_string(),
+        tone: ToneTag::Calm,
         wpm: 152.0,
         ts_unix: 1_000,
+        ..EmoteSeed::default()
     };
     let decayed = emotive::decay(&seed, seed.ts_unix, 180);
     approx_eq(decayed.ema_drift, seed.ema_drift);
@@ -29,16 +34,17 @@ fn decay_large_elapsed_time_trends_to_neutral() {
     let seed = EmoteSeed {
         ema_drift: 0.65,
         ema_res: 0.45,
-        tone: "Energetic".to_string(),
+        tone: ToneTag::Energetic,
         wpm: 210.0,
         ts_unix: 2_000,
+        ..EmoteSeed::default()
     };
     let now = seed.ts_unix + 60 * 600; // 600 minutes later
     let decayed = emotive::decay(&seed, now, 30);
     approx_eq(decayed.ema_drift, 0.30);
     approx_eq(decayed.ema_res, 0.70);
     approx_eq(decayed.wpm, 160.0);
-    assert_eq!(decayed.tone, "Neutral");
+    assert_eq!(decayed.tone, ToneTag::Neutral);
 }
 
 #[test]
@@ -54,26 +60,23 @@ fn boot_bias_increases_resonance() {
 
 #[test]
 fn load_save_roundtrip_appends_and_parses() {
-    let mut path = std::env::temp_dir();
-    let unique = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
-    path.push(format!("emote-test-{}.jsonl", unique));
+    let path = temp_path("liminal_emote_test.jsonl");
 
     let seed_a = EmoteSeed {
         ema_drift: 0.25,
         ema_res: 0.74,
-        tone: "Calm".to_string(),
+        tone: ToneTag::Calm,
         wpm: 154.0,
         ts_unix: 3_000,
+        ..EmoteSeed::default()
     };
     let seed_b = EmoteSeed {
         ema_drift: 0.48,
         ema_res: 0.59,
-        tone: "Neutral".to_string(),
+        tone: ToneTag::Neutral,
         wpm: 168.0,
         ts_unix: 3_600,
+        ..EmoteSeed::default()
     };
 
     let path_string = path.to_string_lossy().to_string();
@@ -89,3 +92,317 @@ fn load_save_roundtrip_appends_and_parses() {
 
     let _ = fs::remove_file(PathBuf::from(path_string));
 }
+
+#[test]
+fn seed_tone_is_stored_lowercase_and_unknown_tones_load_as_neutral() {
+    let path = temp_path("liminal_emote_tone_test.jsonl");
+    let path_string = path.to_string_lossy().to_string();
+
+    let seed = EmoteSeed {
+        ema_drift: 0.3,
+        ema_res: 0.7,
+        tone: ToneTag::Energetic,
+        wpm: 180.0,
+        ts_unix: 4_000,
+        ..EmoteSeed::default()
+    };
+    emotive::save_append(&path_string, &seed).unwrap();
+    let text = fs::read_to_string(&path_string).unwrap();
+    assert!(text.contains("\"tone\":\"energetic\""));
+    assert_eq!(
+        emotive::load_latest(&path_string).unwrap().tone,
+        ToneTag::Energetic
+    );
+
+    fs::write(
+        &path_string,
+        "{\"ema_drift\":0.3,\"ema_res\":0.7,\"tone\":\"Calm\",\"wpm\":150.0,\"ts\":1}\n\
+         {\"ema_drift\":0.3,\"ema_res\":0.7,\"tone\":\"Grumpy\",\"wpm\":150.0,\"ts\":2}\n",
+    )
+    .unwrap();
+    let loaded = emotive::load_latest(&path_string).expect("seed should load");
+    assert_eq!(loaded.tone, ToneTag::Neutral);
+    assert_eq!(loaded.ts_unix, 2);
+
+    let _ = fs::remove_file(PathBuf::from(path_string));
+}
+
+#[test]
+fn blend_seeds_favours_the_most_recent() {
+    let seed = |ema_drift: f32, ema_res: f32, wpm: f32, tone: ToneTag, ts_unix: i64| EmoteSeed {
+        ema_drift,
+        ema_res,
+        tone,
+        wpm,
+        ts_unix,
+        ..EmoteSeed::default()
+    };
+    let seeds = [
+        seed(0.20, 0.80, 140.0, ToneTag::Calm, 100),
+        seed(0.40, 0.70, 160.0, ToneTag::Neutral, 200),
+        seed(0.80, 0.40, 200.0, ToneTag::Energetic, 300),
+    ];
+
+    let blended = emotive::blend_seeds(&seeds);
+    approx_eq(blended.ema_drift, 0.55);
+    approx_eq(blended.ema_res, 0.575);
+    approx_eq(blended.wpm, 175.0);
+    let mean_drift = seeds.iter().map(|s| s.ema_drift).sum::<f32>() / 3.0;
+    assert!((blended.ema_drift - 0.80).abs() < (mean_drift - 0.80).abs());
+    assert_eq!(blended.tone, ToneTag::Energetic);
+    assert_eq!(blended.ts_unix, 300);
+
+    assert_eq!(emotive::blend_seeds(&seeds[..1]).ts_unix, 100);
+}
+
+#[test]
+fn load_recent_returns_the_last_n_oldest_first() {
+    let path = temp_path("liminal_emote_recent_test.jsonl");
+    let path_string = path.to_string_lossy().to_string();
+
+    for ts_unix in 1..=6 {
+        let seed = EmoteSeed {
+            ema_drift: 0.3,
+            ema_res: 0.7,
+            tone: ToneTag::Calm,
+            wpm: 150.0,
+            ts_unix,
+            ..EmoteSeed::default()
+        };
+        emotive::save_append(&path_string, &seed).unwrap();
+    }
+
+    let recent = emotive::load_recent(&path_string, 3);
+    let stamps: Vec<i64> = recent.iter().map(|s| s.ts_unix).collect();
+    assert_eq!(stamps, vec![4, 5, 6]);
+    assert_eq!(emotive::load_recent(&path_string, 10).len(), 6);
+    assert!(emotive::load_recent("/nonexistent/emote.jsonl", 3).is_empty());
+
+    let _ = fs::remove_file(PathBuf::from(path_string));
+}
+
+#[test]
+fn load_latest_skips_a_truncated_final_line() {
+    let path = temp_path("liminal_emote_truncated_test.jsonl");
+    let path_string = path.to_string_lossy().to_string();
+
+    fs::write(
+        &path_string,
+        concat!(
+            "{\"ema_drift\":0.33,\"ema_res\":0.72,\"tone\":\"calm\",\"wpm\":150.0,\"ts\":10}\n",
+            "{\"ema_drift\":0.41,\"ema_res\":0.6",
+        ),
+    )
+    .unwrap();
+    let loaded = emotive::load_latest(&path_string).expect("good line should load");
+    approx_eq(loaded.ema_drift, 0.33);
+    assert_eq!(loaded.ts_unix, 10);
+
+    // Optional fields default; a missing core field rejects the line.
+    fs::write(
+        &path_string,
+        concat!(
+            "{\"ema_drift\":0.33,\"ema_res\":0.72,\"ts\":10}\n",
+            "{\"ema_res\":0.60,\"tone\":\"sad\",\"wpm\":120.0,\"ts\":20}\n",
+        ),
+    )
+    .unwrap();
+    let loaded = emotive::load_latest(&path_string).expect("defaults fill optional fields");
+    assert_eq!(loaded.ts_unix, 10);
+    assert_eq!(loaded.tone, ToneTag::Neutral);
+    approx_eq(loaded.wpm, 160.0);
+
+    let _ = fs::remove_file(PathBuf::from(path_string));
+}
+
+/// Wraps a reader and counts the bytes actually read through it.
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn load_latest_reads_only_the_tail_of_a_large_file() {
+    let mut text = String::new();
+    for ts in 0..5_000 {
+        text.push_str(&format!(
+            "{{\"ema_drift\":0.300000,\"ema_res\":0.700000,\"tone\":\"calm\",\"wpm\":150.000,\"ts\":{}}}\n",
+            ts
+        ));
+    }
+    // A crash mid-write leaves a partial line at the very end.
+    text.push_str("{\"ema_drift\":0.9");
+    let total = text.len() as u64;
+
+    let mut reader = CountingReader {
+        inner: Cursor::new(text.into_bytes()),
+        bytes_read: 0,
+    };
+    let seed = emotive::load_latest_from(&mut reader, emotive::DEFAULT_PROFILE)
+        .expect("last seed should load");
+    assert_eq!(seed.ts_unix, 4_999);
+    assert!(
+        reader.bytes_read <= 8 * 1024,
+        "read {} of {} bytes",
+        reader.bytes_read,
+        total
+    );
+    assert!(reader.bytes_read < total / 10);
+
+    reader.inner.set_position(0);
+    reader.bytes_read = 0;
+    let stamps: Vec<i64> = emotive::load_recent_from(&mut reader, emotive::DEFAULT_PROFILE, 3)
+        .iter()
+        .map(|seed| seed.ts_unix)
+        .collect();
+    assert_eq!(stamps, vec![4_997, 4_998, 4_999]);
+    assert!(
+        reader.bytes_read <= 8 * 1024,
+        "read {} of {} bytes",
+        reader.bytes_read,
+        total
+    );
+
+    assert!(
+        emotive::load_latest_from(&mut Cursor::new(Vec::new()), emotive::DEFAULT_PROFILE).is_none()
+    );
+    let single =
+        b"{\"ema_drift\":0.2,\"ema_res\":0.8,\"tone\":\"sad\",\"wpm\":110,\"ts\":7}".to_vec();
+    let seed = emotive::load_latest_from(&mut Cursor::new(single), emotive::DEFAULT_PROFILE)
+        .expect("unterminated line");
+    assert_eq!(seed.ts_unix, 7);
+}
+
+#[test]
+fn decay_with_relaxes_toward_custom_anchors() {
+    let seed = EmoteSeed {
+        ema_drift: 0.65,
+        ema_res: 0.45,
+        tone: ToneTag::Energetic,
+        wpm: 210.0,
+        ts_unix: 2_000,
+        ..EmoteSeed::default()
+    };
+    let anchors = EmoteDecayCfg {
+        neutral_drift: 0.20,
+        neutral_res: 0.80,
+        neutral_wpm: 140.0,
+        tone_keep: 0.3,
+    };
+    let decayed = emotive::decay_with(&seed, seed.ts_unix + 60 * 600, 30, &anchors);
+    approx_eq(decayed.ema_drift, 0.20);
+    approx_eq(decayed.ema_res, 0.80);
+    approx_eq(decayed.wpm, 140.0);
+    assert_eq!(decayed.tone, ToneTag::Neutral);
+
+    // One half-life in: halfway to the anchor, and a low cutoff keeps the tone.
+    let keep_tone = EmoteDecayCfg {
+        tone_keep: 0.1,
+        ..anchors
+    };
+    let halfway = emotive::decay_with(&seed, seed.ts_unix + 60 * 30, 30, &keep_tone);
+    approx_eq(halfway.ema_drift, 0.425);
+    assert_eq!(halfway.tone, ToneTag::Energetic);
+}
+
+#[test]
+fn prune_keeps_only_the_most_recent_seeds_in_order() {
+    let path = temp_path("liminal_emote_prune_test.jsonl");
+    let path_string = path.to_string_lossy().to_string();
+
+    for ts_unix in 1..=20 {
+        let seed = EmoteSeed {
+            ema_drift: 0.3,
+            ema_res: 0.7,
+            tone: ToneTag::Calm,
+            wpm: 150.0,
+            ts_unix,
+            ..EmoteSeed::default()
+        };
+        emotive::save_append(&path_string, &seed).unwrap();
+    }
+
+    assert_eq!(emotive::prune(&path_string, 5).unwrap(), 15);
+    let text = fs::read_to_string(&path_string).unwrap();
+    assert_eq!(text.lines().count(), 5);
+    let stamps: Vec<i64> = emotive::load_recent(&path_string, 10)
+        .iter()
+        .map(|s| s.ts_unix)
+        .collect();
+    assert_eq!(stamps, vec![16, 17, 18, 19, 20]);
+
+    assert_eq!(emotive::prune(&path_string, 5).unwrap(), 0);
+    assert!(emotive::prune("/nonexistent/emote.jsonl", 5).is_err());
+
+    let _ = fs::remove_file(PathBuf::from(path_string));
+}
+
+#[test]
+fn profiles_load_independently_from_one_file() {
+    let path = temp_path("liminal_emote_profile_test.jsonl");
+    let path_string = path.to_string_lossy().to_string();
+
+    // A line from before profiles existed belongs to the default profile.
+    fs::write(
+        &path_string,
+        "{\"ema_drift\":0.100000,\"ema_res\":0.900000,\"tone\":\"calm\",\"wpm\":140.000,\"ts\":1}\n",
+    )
+    .unwrap();
+    for (profile, drift, tone, ts_unix) in [
+        ("alice", 0.2, ToneTag::Energetic, 2),
+        ("bob", 0.6, ToneTag::Neutral, 3),
+        ("alice", 0.3, ToneTag::Calm, 4),
+    ] {
+        let seed = EmoteSeed {
+            ema_drift: drift,
+            ema_res: 0.7,
+            tone,
+            wpm: 150.0,
+            ts_unix,
+            profile: profile.to_string(),
+        };
+        emotive::save_append(&path_string, &seed).unwrap();
+    }
+
+    let alice = emotive::load_latest_for(&path_string, "alice").expect("alice seed");
+    assert_eq!(alice.profile, "alice");
+    assert_eq!(alice.ts_unix, 4);
+    approx_eq(alice.ema_drift, 0.3);
+
+    let bob = emotive::load_latest_for(&path_string, "bob").expect("bob seed");
+    assert_eq!(bob.ts_unix, 3);
+    approx_eq(bob.ema_drift, 0.6);
+
+    let default = emotive::load_latest(&path_string).expect("legacy seed");
+    assert_eq!(default.profile, emotive::DEFAULT_PROFILE);
+    assert_eq!(default.ts_unix, 1);
+    assert!(emotive::load_latest_for(&path_string, "carol").is_none());
+    assert_eq!(emotive::load_recent_for(&path_string, "alice", 5).len(), 2);
+
+    // Pruning keeps the newest seeds of every profile, not of the file.
+    assert_eq!(emotive::prune(&path_string, 1).unwrap(), 1);
+    assert_eq!(
+        emotive::load_latest_for(&path_string, "bob")
+            .unwrap()
+            .ts_unix,
+        3
+    );
+    assert_eq!(emotive::load_latest(&path_string).unwrap().ts_unix, 1);
+    assert_eq!(emotive::load_recent_for(&path_string, "alice", 5).len(), 1);
+
+    let _ = fs::remove_file(PathBuf::from(path_string));
+}
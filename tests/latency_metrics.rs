@@ -1,6 +1,7 @@
 use std::thread;
 use std::time::Duration;
 
+use liminal_voice_core::alerts::AlertStats;
 use liminal_voice_core::metrics;
 
 #[test]
@@ -17,3 +18,140 @@ fn clamp01_bounds() {
     assert_eq!(metrics::clamp01(0.5), 0.5);
     assert_eq!(metrics::clamp01(1.5), 1.0);
 }
+
+#[test]
+fn aggregator_reports_median_and_mean() {
+    let mut agg = metrics::LatencyAggregator::new();
+    assert!(agg.total.percentile(50.0).is_none());
+    assert!(agg.summary_line().is_none());
+
+    for (asr, tts, total) in [(10, 40, 60), (30, 20, 90), (20, 30, 70), (40, 10, 100)] {
+        let mut vm = metrics::start();
+        vm.asr_ms = asr;
+        vm.tts_ms = tts;
+        vm.total_ms = total;
+        agg.record(&vm);
+    }
+
+    assert_eq!(agg.total.len(), 4);
+    assert_eq!(agg.total.percentile(50.0), Some(80.0));
+    assert_eq!(agg.asr.percentile(50.0), Some(25.0));
+    assert_eq!(agg.tts.percentile(0.0), Some(10.0));
+    assert_eq!(agg.tts.percentile(100.0), Some(40.0));
+    assert!((agg.total.percentile(95.0).unwrap() - 98.5).abs() < 1e-3);
+    assert_eq!(agg.total.mean(), Some(80.0));
+    assert_eq!(agg.asr.mean(), Some(25.0));
+
+    let line = agg.summary_line().unwrap();
+    assert!(line.starts_with("[metrics] p50/p95 total=80ms/"));
+}
+
+#[test]
+fn rolling_window_drops_old_samples() {
+    let mut rolling = metrics::RollingMetrics::new(3);
+    assert!(rolling.mean().is_none());
+
+    for total in [100, 10, 20, 30] {
+        let mut vm = metrics::start();
+        vm.total_ms = total;
+        rolling.record(&vm);
+    }
+
+    assert_eq!(rolling.len(), 3);
+    assert_eq!(rolling.mean(), Some(20.0));
+    assert_eq!(rolling.min(), Some(10));
+    assert_eq!(rolling.max(), Some(30));
+    let jitter = rolling.jitter().unwrap();
+    assert!((jitter - (200.0f32 / 3.0).sqrt()).abs() < 1e-3);
+}
+
+#[test]
+fn prometheus_text_is_well_formed() {
+    let mut agg = metrics::LatencyAggregator::new();
+    for total in [60, 80, 100] {
+        let mut vm = metrics::start();
+        vm.asr_ms = total / 2;
+        vm.tts_ms = total / 4;
+        vm.total_ms = total;
+        agg.record(&vm);
+    }
+    let alerts = AlertStats {
+        drift_breaches: 2,
+        res_breaches: 1,
+        total: 3,
+        drift_crit: 1,
+        ..AlertStats::default()
+    };
+
+    let text = metrics::prometheus_text(&agg, &alerts);
+    assert!(text.contains("# TYPE liminal_latency_ms gauge"));
+    assert!(text.contains("liminal_latency_ms{stage=\"total\",quantile=\"0.5\"} 80\n"));
+    assert!(text.contains("liminal_drift_breaches 2\n"));
+    assert!(text.contains("liminal_resonance_breaches 1\n"));
+    assert!(text.contains("liminal_drift_critical 1\n"));
+    assert!(text.contains("liminal_resonance_critical 0\n"));
+    assert!(text.contains("liminal_cycles 3\n"));
+
+    for line in text.lines() {
+        if let Some(comment) = line.strip_prefix("# ") {
+            assert!(comment.starts_with("HELP ") || comment.starts_with("TYPE "));
+            continue;
+        }
+        let (name, value) = line.rsplit_once(' ').expect("metric line has a value");
+        assert!(name.starts_with("liminal_"), "{}", line);
+        assert!(value.parse::<f64>().is_ok(), "{}", line);
+    }
+}
+
+#[test]
+fn histogram_counts_samples_per_bucket() {
+    let mut hist = metrics::LatencyHistogram::default();
+    assert_eq!(hist.bounds(), &metrics::DEFAULT_LATENCY_BUCKETS);
+    for ms in [5, 10, 11, 30, 99, 100, 240, 600, 900] {
+        hist.record(ms);
+    }
+    assert_eq!(hist.counts(), &[2, 1, 1, 2, 1, 2]);
+    assert_eq!(hist.total(), 9);
+
+    let rendered = hist.render();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].trim_start().starts_with("<=10ms |"));
+    assert!(lines[0].ends_with("| 2"));
+    assert!(lines[5].trim_start().starts_with(">250ms |"));
+
+    let mut custom = metrics::LatencyHistogram::new(&[200, 100]);
+    custom.record(150);
+    assert_eq!(custom.bounds(), &[100, 200]);
+    assert_eq!(custom.counts(), &[0, 1, 0]);
+}
+
+#[test]
+fn asr_tts_ratio_reflects_the_dominant_stage() {
+    let run = |samples: &[(u128, u128)]| {
+        let mut agg = metrics::LatencyAggregator::new();
+        for &(asr, tts) in samples {
+            let mut vm = metrics::start();
+            vm.asr_ms = asr;
+            vm.tts_ms = tts;
+            vm.total_ms = asr + tts;
+            agg.record(&vm);
+        }
+        agg
+    };
+
+    let asr_heavy = run(&[(90, 30), (110, 20), (100, 25)]);
+    assert!((asr_heavy.asr_tts_ratio().unwrap() - 4.0).abs() < 1e-4);
+    assert!(
+        asr_heavy
+            .stage_line()
+            .unwrap()
+            .ends_with("asr_mean=100.0ms tts_mean=25.0ms ratio=4.00")
+    );
+
+    let tts_heavy = run(&[(20, 80), (30, 120)]);
+    assert!((tts_heavy.asr_tts_ratio().unwrap() - 0.25).abs() < 1e-4);
+
+    assert!(run(&[(40, 0)]).asr_tts_ratio().is_none());
+    assert!(run(&[]).stage_line().is_none());
+}
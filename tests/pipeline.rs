@@ -0,0 +1,104 @@
+use liminal_voice_core::config::{Config, VERBOSITY_QUIET};
+use liminal_voice_core::device::{self, DeviceMode};
+use liminal_voice_core::pipeline::Pipeline;
+use liminal_voice_core::session::Snapshot;
+use liminal_voice_core::voice_io::CapturingTts;
+
+fn quiet_cfg() -> Config {
+    Config {
+        mode: "phone".to_string(),
+        simulate_latency: false,
+        verbosity: VERBOSITY_QUIET,
+        enable_metrics: false,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn on_cycle_sees_every_snapshot_in_order() {
+    let cfg = Config {
+        guard_hard_drift: Some(0.65),
+        ..quiet_cfg()
+    };
+    let prof = device::profile(&DeviceMode::Phone);
+    let utterances = vec![
+        "thank you, I feel calm today".to_string(),
+        "why is nothing working?!".to_string(),
+    ];
+    let mut pipe = Pipeline::new(&cfg, &prof, utterances.len());
+
+    let mut seen: Vec<Snapshot> = Vec::new();
+    pipe.run_with(&utterances, |snap| seen.push(snap.clone()));
+
+    assert_eq!(seen.len(), 2);
+    assert_eq!(seen[0].idx, 0);
+    assert_eq!(seen[1].idx, 1);
+    assert_eq!(seen[0].utterance, utterances[0]);
+    assert_eq!(seen[1].utterance, utterances[1]);
+    for snap in &seen {
+        assert_eq!(snap.device, "phone");
+        assert!((0.0..=1.0).contains(&snap.drift));
+        assert!((0.0..=1.0).contains(&snap.resonance));
+        assert!(snap.asr_ms > 0);
+    }
+    // The calm line is voiced; the agitated one is past the hard drift limit.
+    assert!(seen[0].tts_ms > 0);
+    assert!(seen[1].guard.is_some());
+    assert_eq!(seen[1].tts_ms, 0);
+    // Only the final cycle carries the end-of-run emote state.
+    assert!(seen[0].emote_state.is_none());
+    assert!(seen[1].emote_state.is_some());
+
+    let last = pipe.last_snapshot.as_ref().expect("last snapshot kept");
+    assert_eq!(last.idx, 1);
+    assert_eq!(pipe.drift_history.len(), 2);
+    assert_eq!(pipe.latency.total.len(), 2);
+}
+
+#[test]
+fn audio_duration_only_sets_the_first_cycle_wpm() {
+    let prof = device::profile(&DeviceMode::Phone);
+    let utterances = vec![
+        "hello liminal".to_string(),
+        "thank you, I feel calm today".to_string(),
+        "tell me more about the plan".to_string(),
+    ];
+    let wpm_of = |cfg: &Config| {
+        let mut pipe = Pipeline::new(cfg, &prof, utterances.len());
+        let mut wpm = Vec::new();
+        pipe.run_with(&utterances, |snap| wpm.push(snap.wpm));
+        wpm
+    };
+
+    let scripted = wpm_of(&quiet_cfg());
+    let with_audio = wpm_of(&Config {
+        audio_duration_ms: Some(4_000),
+        ..quiet_cfg()
+    });
+
+    // Two words over four seconds for the recorded line only.
+    assert!((with_audio[0] - 30.0).abs() < 1e-3, "wpm={}", with_audio[0]);
+    assert_ne!(with_audio[0], scripted[0]);
+    assert_eq!(with_audio[1..], scripted[1..]);
+}
+
+#[test]
+fn pipeline_voices_through_a_custom_tts_backend() {
+    let mut cfg = quiet_cfg();
+    cfg.guard = false;
+    let prof = device::profile(&DeviceMode::Phone);
+    let mut pipe = Pipeline::new(&cfg, &prof, 1);
+    pipe.tts = Box::new(CapturingTts::default());
+
+    let mut spoken = Vec::new();
+    pipe.run_cycle(0, "hello liminal", |snap| {
+        spoken.push(format!(
+            "Semantic Drift: {:.2}, Resonance: {:.2}",
+            snap.drift, snap.resonance
+        ));
+    });
+    assert_eq!(spoken.len(), 1);
+    assert!(pipe.last_tone.is_some());
+    // Without a simulated figure the stage time is wall clock only.
+    assert!(pipe.last_snapshot.as_ref().unwrap().tts_ms < 50);
+}
@@ -1,8 +1,10 @@
-use liminal_voice_core::prosody::{self, ToneTag};
+use liminal_voice_core::prosody::{
+    self, HeuristicProsody, Prosody, ProsodyAnalyzer, ProsodyCfg, ToneTag,
+};
 
 #[test]
 fn basic_analysis_ranges() {
-    let p = prosody::analyze("hello liminal", 1.0, 60);
+    let p = prosody::analyze_default("hello liminal", 1.0, 60);
     assert!((60.0..=220.0).contains(&p.wpm), "unexpected wpm: {}", p.wpm);
     assert!(
         (0.0..=1.0).contains(&p.articulation),
@@ -13,22 +15,24 @@ fn basic_analysis_ranges() {
         p.tone,
         ToneTag::Neutral | ToneTag::Calm | ToneTag::Energetic
     ));
+    assert_ne!(p.tone, ToneTag::Anxious);
+    assert_ne!(p.tone, ToneTag::Sad);
 }
 
 #[test]
 fn empty_text_safe() {
-    let p = prosody::analyze("", 1.0, 60);
+    let p = prosody::analyze_default("", 1.0, 60);
     assert!(p.wpm >= 0.0);
     assert!((0.0..=1.0).contains(&p.articulation));
 }
 
 #[test]
 fn extreme_parameters_clamped() {
-    let slow = prosody::analyze("slow", 0.5, 20);
+    let slow = prosody::analyze_default("slow", 0.5, 20);
     assert!((0.0..=220.0).contains(&slow.wpm));
     assert!((0.0..=1.0).contains(&slow.articulation));
 
-    let fast = prosody::analyze("fast", 1.5, 200);
+    let fast = prosody::analyze_default("fast", 1.5, 200);
     assert!((0.0..=220.0).contains(&fast.wpm));
     assert!((0.0..=1.0).contains(&fast.articulation));
 }
@@ -40,3 +44,220 @@ fn articulation_hint_clamped() {
     let reduced = prosody::apply_articulation_hint(0.05, -0.2);
     assert!((0.0..=1.0).contains(&reduced));
 }
+
+#[test]
+fn longer_utterance_changes_wpm() {
+    let single = prosody::analyze_default("hello", 1.0, 60);
+    let long = prosody::analyze_default(
+        "this is a much longer utterance with quite a few more words in it",
+        1.0,
+        60,
+    );
+    assert!((long.wpm - single.wpm).abs() > 1.0);
+    assert!(
+        long.wpm > single.wpm,
+        "framing pauses weigh less on long turns"
+    );
+    assert!((0.0..=220.0).contains(&long.wpm));
+}
+
+#[test]
+fn repeated_punctuation_reads_anxious() {
+    let p = prosody::analyze_default("why is this happening?!", 1.0, 60);
+    assert_eq!(p.tone, ToneTag::Anxious);
+}
+
+#[test]
+fn shouted_words_read_anxious() {
+    let p = prosody::analyze_default("please STOP this now", 1.0, 60);
+    assert_eq!(p.tone, ToneTag::Anxious);
+}
+
+#[test]
+fn acronyms_are_not_shouting() {
+    let p = prosody::analyze_default("the API said OK so we are fine", 1.0, 60);
+    assert_ne!(p.tone, ToneTag::Anxious);
+    assert!(prosody::is_shouted("STOP!"));
+    assert!(!prosody::is_shouted("(API)"));
+    assert!(!prosody::is_shouted("Ok"));
+}
+
+#[test]
+fn stuck_words_read_anxious() {
+    let p = prosody::analyze_default("i can't get past this, i'm stuck", 1.0, 60);
+    assert_eq!(p.tone, ToneTag::Anxious);
+}
+
+#[test]
+fn tired_words_read_sad() {
+    let p = prosody::analyze_default("i feel so tired and alone", 1.0, 60);
+    assert_eq!(p.tone, ToneTag::Sad);
+}
+
+#[test]
+fn trailing_ellipsis_reads_sad() {
+    let p = prosody::analyze_default("i guess that is it...", 1.0, 60);
+    assert_eq!(p.tone, ToneTag::Sad);
+}
+
+#[test]
+fn tied_cues_fall_back_to_wpm() {
+    let slow = prosody::analyze_default("stuck and tired", 0.7, 200);
+    assert_eq!(slow.tone, ToneTag::Sad);
+
+    let fast = prosody::analyze_default("stuck and tired", 1.3, 20);
+    assert_eq!(fast.tone, ToneTag::Anxious);
+}
+
+#[test]
+fn lower_energetic_threshold_retags_neutral() {
+    let text = "hello liminal";
+    let default = prosody::analyze_default(text, 1.0, 60);
+    assert_eq!(default.tone, ToneTag::Neutral);
+
+    let cfg = ProsodyCfg {
+        calm_wpm: 80.0,
+        energetic_wpm: 100.0,
+        ..ProsodyCfg::default()
+    };
+    let tuned = prosody::analyze(text, 1.0, 60, &cfg);
+    assert_eq!(tuned.tone, ToneTag::Energetic);
+    assert!((tuned.wpm - default.wpm).abs() < f32::EPSILON);
+}
+
+#[test]
+fn measured_duration_sets_wpm() {
+    let cfg = ProsodyCfg {
+        duration_ms: Some(2_000),
+        ..ProsodyCfg::default()
+    };
+    // Four words over two seconds, whatever the device pacing.
+    let measured = prosody::analyze("hello liminal world today", 1.4, 30, &cfg);
+    assert!((measured.wpm - 120.0).abs() < 1e-3, "wpm={}", measured.wpm);
+    assert_eq!(measured.tone, ToneTag::Neutral);
+}
+
+#[test]
+fn syllable_estimate_counts_vowel_groups() {
+    assert_eq!(prosody::estimate_syllables(""), 0);
+    assert_eq!(prosody::estimate_syllables("cat"), 1);
+    assert_eq!(prosody::estimate_syllables("make"), 1);
+    assert_eq!(prosody::estimate_syllables("hello"), 2);
+    assert_eq!(prosody::estimate_syllables("beautiful"), 3);
+    assert_eq!(prosody::estimate_syllables("the cat sat"), 3);
+}
+
+#[test]
+fn polysyllabic_utterance_articulates_differently() {
+    let mono = prosody::analyze_default("the cat sat on a mat", 1.0, 60);
+    let poly = prosody::analyze_default(
+        "unbelievably complicated organizational considerations",
+        1.0,
+        60,
+    );
+    assert!((0.0..=1.0).contains(&mono.articulation));
+    assert!((0.0..=1.0).contains(&poly.articulation));
+    assert!(poly.articulation > mono.articulation);
+}
+
+#[test]
+fn confidence_ramps_with_word_count() {
+    let short = prosody::analyze_default("hi", 1.0, 60);
+    assert!((short.confidence - 0.3).abs() < 1e-6);
+
+    let long = prosody::analyze_default(
+        "this sentence has more than eight words in it today",
+        1.0,
+        60,
+    );
+    assert!((long.confidence - 1.0).abs() < 1e-6);
+
+    let mid = prosody::analyze_default("four words right here", 1.0, 60);
+    assert!(mid.confidence > short.confidence && mid.confidence < long.confidence);
+}
+
+#[test]
+fn terminal_punctuation_sets_sentence_flags() {
+    let alarmed = prosody::analyze_default("why is this happening?!", 1.0, 60);
+    assert!(alarmed.is_question);
+    assert!(alarmed.is_exclamation);
+
+    let plain = prosody::analyze_default("this is fine.", 1.0, 60);
+    assert!(!plain.is_question);
+    assert!(!plain.is_exclamation);
+
+    let unpunctuated = prosody::analyze_default("how do i start", 1.0, 60);
+    assert!(unpunctuated.is_question);
+}
+
+#[test]
+fn filler_density_counts_filler_tokens() {
+    assert_eq!(prosody::filler_density(""), 0.0);
+    assert_eq!(prosody::filler_density("the plan is ready"), 0.0);
+
+    let density = prosody::filler_density("um, so, like, you know, the plan");
+    assert!((density - 4.0 / 7.0).abs() < 1e-6, "density={}", density);
+
+    let p = prosody::analyze_default("uh I think it works", 1.0, 60);
+    assert!((p.filler_density - 0.2).abs() < 1e-6);
+}
+
+#[test]
+fn tokenize_strips_punctuation_and_splits_unicode_whitespace() {
+    assert_eq!(
+        prosody::tokenize("¿Qué tal, señor?"),
+        vec!["Qué", "tal", "señor"]
+    );
+    assert_eq!(
+        prosody::tokenize("well\u{3000}— it's fine ..."),
+        vec!["well", "it's", "fine"]
+    );
+    assert_eq!(prosody::tokenize("你好世界"), vec!["你", "好", "世", "界"]);
+    assert!(prosody::tokenize("?! ... —").is_empty());
+}
+
+#[test]
+fn cyrillic_and_accented_text_yield_sane_counts() {
+    let text = "Привет, как у тебя дела?";
+    assert_eq!(prosody::tokenize(text).len(), 5);
+    assert!(prosody::estimate_syllables(text) > 0);
+
+    let p = prosody::analyze_default(text, 1.0, 60);
+    assert!(p.wpm > 0.0 && p.wpm <= 220.0, "wpm={}", p.wpm);
+    assert!(p.is_question);
+
+    let accented = prosody::analyze_default("Ça va très bien, élève", 1.0, 60);
+    assert!(accented.wpm > 0.0 && accented.wpm <= 220.0);
+    assert!(prosody::estimate_syllables("élève") >= 2);
+}
+
+struct AlwaysCalm;
+
+impl ProsodyAnalyzer for AlwaysCalm {
+    fn analyze(&self, _text: &str, _pace: f32, _pause_ms: u64) -> Prosody {
+        Prosody {
+            wpm: 100.0,
+            articulation: 0.9,
+            tone: ToneTag::Calm,
+            confidence: 1.0,
+            is_question: false,
+            is_exclamation: false,
+            filler_density: 0.0,
+        }
+    }
+}
+
+#[test]
+fn analyzer_trait_allows_custom_models() {
+    let analyzers: Vec<Box<dyn ProsodyAnalyzer>> =
+        vec![Box::new(AlwaysCalm), Box::new(HeuristicProsody::default())];
+
+    let text = "I'm stuck and can't figure this out!!";
+    let stub = analyzers[0].analyze(text, 1.3, 60);
+    assert_eq!(stub.tone, ToneTag::Calm);
+
+    let heuristic = analyzers[1].analyze(text, 1.3, 60);
+    let direct = prosody::analyze_default(text, 1.3, 60);
+    assert_eq!(heuristic.tone, direct.tone);
+    assert!((heuristic.wpm - direct.wpm).abs() < 1e-6);
+}
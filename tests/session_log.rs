@@ -1,11 +1,14 @@
 use std::fs;
 use std::path::Path;
 
-use liminal_voice_core::session;
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::{config, session};
 
 #[test]
 fn session_writes_jsonl() -> std::io::Result<()> {
-    let tmp_dir = std::env::temp_dir().join("liminal_session_test");
+    let tmp_dir = temp_path("liminal_session_test");
     if tmp_dir.exists() {
         fs::remove_dir_all(&tmp_dir)?;
     }
@@ -33,20 +36,7 @@ fn session_writes_jsonl() -> std::io::Result<()> {
         total_ms: 35,
         idx: 0,
         utterance: "hello liminal".to_string(),
-        guard: None,
-        state: None,
-        emote_state: None,
-        sync: None,
-        meta_self_drift: None,
-        meta_self_resonance: None,
-        meta_confidence: None,
-        meta_clarity: None,
-        meta_doubt: None,
-        compassion_suffering: None,
-        compassion_type: None,
-        compassion_kindness: None,
-        compassion_healing: None,
-        compassion_level: None,
+        ..session::Snapshot::default()
     };
 
     let snapshot2 = session::Snapshot {
@@ -69,7 +59,7 @@ fn session_writes_jsonl() -> std::io::Result<()> {
     session::write(&mut sess, &snapshot1)?;
     session::write(&mut sess, &snapshot2)?;
 
-    session::close(sess);
+    session::close(sess)?;
 
     let log_path = Path::new(&log_dir_copy).join(format!("session-{}.jsonl", session_id));
     assert!(log_path.exists());
@@ -81,3 +71,421 @@ fn session_writes_jsonl() -> std::io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn minimal_snapshot_writes_nulls_for_optional_layers() -> std::io::Result<()> {
+    let log_dir = temp_path("liminal_session_minimal");
+    let mut sess = session::start(1, &log_dir.to_string_lossy());
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+
+    let snapshot = session::Snapshot {
+        drift: 0.3,
+        resonance: 0.7,
+        utterance: "just the basics".into(),
+        ..session::Snapshot::default()
+    };
+    session::write(&mut sess, &snapshot)?;
+    session::close(sess)?;
+
+    let contents = fs::read_to_string(&log_path)?;
+    assert!(contents.contains("\"drift\":0.300"));
+    assert!(contents.contains("\"utt\":\"just the basics\""));
+    assert!(contents.contains("\"guard\":null"));
+    assert!(contents.contains("\"sync\":null"));
+
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
+
+#[test]
+fn meta_and_compassion_fields_are_logged() -> std::io::Result<()> {
+    let log_dir = temp_path("liminal_session_layers");
+    let mut sess = session::start(1, &log_dir.to_string_lossy());
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+
+    let snapshot = session::Snapshot {
+        meta_self_drift: Some(0.12),
+        meta_self_resonance: Some(0.8),
+        meta_confidence: Some(0.65),
+        meta_clarity: Some(0.7),
+        meta_doubt: Some(0.35),
+        compassion_suffering: Some(0.45),
+        compassion_type: Some("Moderate".into()),
+        compassion_kindness: Some(0.6),
+        compassion_healing: Some(0.5),
+        compassion_level: None,
+        ..session::Snapshot::default()
+    };
+    session::write(&mut sess, &snapshot)?;
+    session::close(sess)?;
+
+    let contents = fs::read_to_string(&log_path)?;
+    for expected in [
+        "\"meta_self_drift\":0.120",
+        "\"meta_self_resonance\":0.800",
+        "\"meta_confidence\":0.650",
+        "\"meta_clarity\":0.700",
+        "\"meta_doubt\":0.350",
+        "\"compassion_suffering\":0.450",
+        "\"compassion_type\":\"Moderate\"",
+        "\"compassion_kindness\":0.600",
+        "\"compassion_healing\":0.500",
+        "\"compassion_level\":null",
+    ] {
+        assert!(
+            contents.contains(expected),
+            "missing {expected} in {contents}"
+        );
+    }
+
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
+
+#[test]
+fn read_log_round_trips_snapshots() -> std::io::Result<()> {
+    let log_dir = temp_path("liminal_session_replay");
+    let mut sess = session::start(2, &log_dir.to_string_lossy());
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+
+    let first = session::Snapshot {
+        ts: "2024-01-01T00:00:00.000Z".into(),
+        device: "phone".into(),
+        drift: 0.312,
+        resonance: 0.688,
+        wpm: 152.5,
+        tone: "Calm".into(),
+        utterance: "hello, \"liminal\" {world}".into(),
+        ..session::Snapshot::default()
+    };
+    let second = session::Snapshot {
+        idx: 1,
+        drift: 0.45,
+        resonance: 0.51,
+        tone: "Energetic".into(),
+        guard: Some("warn:high_drift".into()),
+        sync: Some(session::SyncDelta {
+            pace_delta: -0.02,
+            pause_delta_ms: 12,
+            res_boost: 0.001,
+            drift_relief: 0.0,
+        }),
+        meta_confidence: Some(0.42),
+        compassion_type: Some("Mild".into()),
+        ..first.clone()
+    };
+    session::write(&mut sess, &first)?;
+    session::write(&mut sess, &second)?;
+    session::close(sess)?;
+
+    // A truncated line is skipped, not fatal.
+    let mut file = fs::OpenOptions::new().append(true).open(&log_path)?;
+    std::io::Write::write_all(&mut file, b"{\"ts\":\"broken\n")?;
+    drop(file);
+
+    let snaps = session::read_log(&log_path.to_string_lossy())?;
+    assert_eq!(snaps.len(), 2);
+    assert!((snaps[0].drift - 0.312).abs() < 1e-3);
+    assert!((snaps[0].resonance - 0.688).abs() < 1e-3);
+    assert_eq!(snaps[0].tone, "Calm");
+    assert_eq!(snaps[0].utterance, first.utterance);
+    assert!(snaps[0].sync.is_none() && snaps[0].guard.is_none());
+
+    assert_eq!(snaps[1].idx, 1);
+    assert_eq!(snaps[1].tone, "Energetic");
+    assert_eq!(snaps[1].guard.as_deref(), Some("warn:high_drift"));
+    let sync = snaps[1].sync.expect("sync logged");
+    assert_eq!(sync.pause_delta_ms, 12);
+    assert!((sync.pace_delta + 0.02).abs() < 1e-4);
+    assert!((snaps[1].meta_confidence.unwrap() - 0.42).abs() < 1e-3);
+    assert_eq!(snaps[1].compassion_type.as_deref(), Some("Mild"));
+    assert!(snaps[1].compassion_level.is_none());
+
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
+
+#[test]
+fn read_log_keeps_layers_from_untagged_lines() -> std::io::Result<()> {
+    let log_dir = temp_path("liminal_schema");
+    let mut sess = session::start(1, &log_dir.to_string_lossy());
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+    session::write(
+        &mut sess,
+        &session::Snapshot {
+            meta_clarity: Some(0.7),
+            ..session::Snapshot::default()
+        },
+    )?;
+    session::close(sess)?;
+
+    let current = fs::read_to_string(&log_path)?;
+    assert!(current.starts_with(&format!("{{\"schema\":{},", session::Snapshot::SCHEMA)));
+    assert_eq!(session::Snapshot::SCHEMA, 1);
+
+    // A line without meta/compassion fields, and an untagged line in the
+    // layout written before the schema tag existed.
+    let legacy = concat!(
+        r#"{"ts":"t0","device":"phone","drift":0.2,"resonance":0.7,"wpm":150,"articulation":0.5,"tone":"Calm","asr_ms":10,"tts_ms":20,"total_ms":30,"idx":0,"utt":"old","guard":null,"state":null,"emote_state":null,"sync":null}"#,
+        "\n",
+        r#"{"ts":"t1","device":"phone","drift":0.3,"resonance":0.6,"wpm":140,"articulation":0.4,"tone":"Calm","asr_ms":10,"tts_ms":20,"total_ms":30,"idx":1,"utt":"older","guard":"warn","state":null,"emote_state":null,"sync":null,"meta_self_drift":0.310,"meta_self_resonance":0.640,"meta_confidence":0.800,"meta_clarity":0.900,"meta_doubt":0.100,"compassion_suffering":0.200,"compassion_type":"Mild","compassion_kindness":0.500,"compassion_healing":0.050,"compassion_level":0.400}"#,
+        "\n",
+    );
+    fs::write(&log_path, format!("{}{}", legacy, current))?;
+
+    let snaps = session::read_log(&log_path.to_string_lossy())?;
+    assert_eq!(snaps.len(), 3);
+    assert_eq!(snaps[0].utterance, "old");
+    assert!(snaps[0].meta_confidence.is_none() && snaps[0].compassion_type.is_none());
+    assert_eq!(snaps[1].guard.as_deref(), Some("warn"));
+    assert!((snaps[1].meta_clarity.unwrap() - 0.9).abs() < 1e-3);
+    assert!((snaps[1].meta_doubt.unwrap() - 0.1).abs() < 1e-3);
+    assert_eq!(snaps[1].compassion_type.as_deref(), Some("Mild"));
+    assert!((snaps[1].compassion_level.unwrap() - 0.4).abs() < 1e-3);
+    assert!((snaps[2].meta_clarity.unwrap() - 0.7).abs() < 1e-3);
+
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
+
+#[test]
+fn read_log_skips_lines_from_a_newer_schema() -> std::io::Result<()> {
+    let path = temp_path("liminal_schema_newer.jsonl");
+    let line = |schema: u32, utt: &str| {
+        format!(
+            r#"{{"schema":{},"ts":"t0","device":"phone","drift":0.2,"resonance":0.7,"wpm":150,"articulation":0.5,"tone":"Calm","asr_ms":10,"tts_ms":20,"total_ms":30,"idx":0,"utt":"{}","guard":null,"state":null,"emote_state":null,"sync":null}}"#,
+            schema, utt
+        )
+    };
+    let contents = [
+        line(session::Snapshot::SCHEMA, "current"),
+        line(session::Snapshot::SCHEMA + 1, "future"),
+    ]
+    .join("\n");
+    fs::write(&path, contents)?;
+
+    let snaps = session::read_log(&path.to_string_lossy())?;
+    assert_eq!(snaps.len(), 1);
+    assert_eq!(snaps[0].utterance, "current");
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn summarize_computes_means_and_tone_counts() -> std::io::Result<()> {
+    let snap = |drift: f32, resonance: f32, tone: &str, total_ms: u128| session::Snapshot {
+        drift,
+        resonance,
+        tone: tone.to_string(),
+        total_ms,
+        ..session::Snapshot::default()
+    };
+    let snaps = vec![
+        snap(0.1, 0.8, "Calm", 30),
+        snap(0.3, 0.6, "Energetic", 40),
+        snap(0.2, 0.7, "Calm", 50),
+    ];
+
+    let summary = session::summarize(&snaps);
+    assert_eq!(summary.turns, 3);
+    assert!((summary.drift_mean - 0.2).abs() < 1e-6);
+    assert!((summary.res_mean - 0.7).abs() < 1e-6);
+    assert_eq!(summary.drift_min, 0.1);
+    assert_eq!(summary.drift_max, 0.3);
+    assert_eq!(summary.res_min, 0.6);
+    assert_eq!(summary.res_max, 0.8);
+    assert_eq!(
+        summary.tone_counts,
+        vec![("Calm".to_string(), 2), ("Energetic".to_string(), 1)]
+    );
+    assert_eq!(summary.total_latency_ms, 120);
+    assert_eq!(session::summarize(&[]), session::SessionSummary::default());
+
+    let log_dir = temp_path("liminal_summary");
+    let sess = session::start(3, &log_dir.to_string_lossy());
+    let path = session::write_summary(&sess, &summary)?;
+    assert_eq!(
+        path.file_name().and_then(|n| n.to_str()),
+        Some(format!("session-{}-summary.json", sess.id).as_str())
+    );
+    let json = fs::read_to_string(&path)?;
+    assert!(json.contains("\"turns\":3"));
+    assert!(json.contains("\"drift\":{\"mean\":0.200,"));
+    assert!(json.contains("\"tones\":{\"Calm\":2,\"Energetic\":1}"));
+    assert!(json.contains("\"total_latency_ms\":120"));
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
+
+#[test]
+fn export_csv_round_trips_from_read_log() -> std::io::Result<()> {
+    let tmp_dir = temp_path("liminal_csv");
+    let log_dir = tmp_dir.to_string_lossy().to_string();
+
+    let mut sess = session::start(2, &log_dir);
+    session::open_file(&mut sess)?;
+    let first = session::Snapshot {
+        ts: "2024-01-01T00:00:00.000Z".into(),
+        device: "car, front".into(),
+        drift: 0.25,
+        resonance: 0.8,
+        tone: "Calm".into(),
+        total_ms: 42,
+        ..session::Snapshot::default()
+    };
+    let second = session::Snapshot {
+        idx: 1,
+        device: "phone".into(),
+        guard: Some("warn".into()),
+        state: Some("Warming".into()),
+        ..first.clone()
+    };
+    session::write(&mut sess, &first)?;
+    session::write(&mut sess, &second)?;
+    let jsonl = tmp_dir.join(format!("session-{}.jsonl", sess.id));
+    session::close(sess)?;
+
+    let snaps = session::read_log(&jsonl.to_string_lossy())?;
+    let csv_path = tmp_dir.join("session.csv");
+    session::export_csv(&snaps, &csv_path.to_string_lossy())?;
+
+    let csv = fs::read_to_string(&csv_path)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "ts,device,drift,resonance,wpm,articulation,tone,asr_ms,tts_ms,total_ms,guard,state"
+    );
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("2024-01-01T00:00:00.000Z,\"car, front\",0.250,0.800,"));
+    assert!(lines[1].ends_with(",42,,"));
+    assert!(lines[2].ends_with(",42,warn,Warming"));
+
+    fs::remove_dir_all(&tmp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn exceeding_max_log_bytes_rotates_the_log() -> std::io::Result<()> {
+    let tmp_dir = temp_path("liminal_rotate");
+    let mut sess = session::start(3, &tmp_dir.to_string_lossy());
+    sess.max_log_bytes = Some(200);
+    session::open_file(&mut sess)?;
+    let id = sess.id.clone();
+    let base = tmp_dir.join(format!("session-{}.jsonl", id));
+    let rotated = |n: usize| tmp_dir.join(format!("session-{}.jsonl.{}", id, n));
+
+    let snap = session::Snapshot {
+        tone: "Calm".into(),
+        ..session::Snapshot::default()
+    };
+    session::write(&mut sess, &snap)?;
+    assert!(!rotated(1).exists());
+    session::write(&mut sess, &snap)?;
+    assert!(rotated(1).exists());
+    session::write(&mut sess, &snap)?;
+    assert!(rotated(2).exists());
+    session::close(sess)?;
+
+    for path in [base, rotated(1), rotated(2)] {
+        assert_eq!(fs::read_to_string(&path)?.lines().count(), 1);
+    }
+    fs::remove_dir_all(&tmp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn append_mode_keeps_existing_lines() -> std::io::Result<()> {
+    let tmp_dir = temp_path("liminal_append");
+    let snap = session::Snapshot::default();
+
+    let mut first = session::start(1, &tmp_dir.to_string_lossy());
+    session::open_file(&mut first)?;
+    session::write(&mut first, &snap)?;
+    let id = first.id.clone();
+    session::close(first)?;
+
+    let mut second = session::start(1, &tmp_dir.to_string_lossy());
+    second.id = id.clone();
+    second.append = true;
+    session::open_file(&mut second)?;
+    session::write(&mut second, &snap)?;
+    session::close(second)?;
+
+    let path = tmp_dir.join(format!("session-{}.jsonl", id));
+    assert_eq!(fs::read_to_string(&path)?.lines().count(), 2);
+    fs::remove_dir_all(&tmp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn log_append_flag_reuses_the_session_file_across_runs() -> std::io::Result<()> {
+    let tmp_dir = temp_path("liminal_append_cli");
+    let dir = tmp_dir.to_string_lossy().to_string();
+    let run = |extra: &[&str]| -> std::io::Result<String> {
+        let mut cfg = config::Config::default();
+        let mut argv = vec![
+            "--log-dir".to_string(),
+            dir.clone(),
+            "--log-append".to_string(),
+        ];
+        argv.extend(extra.iter().map(|s| s.to_string()));
+        config::apply_args(&mut cfg, argv);
+        let mut sess = session::start_from(&cfg);
+        session::open_file(&mut sess)?;
+        session::write(&mut sess, &session::Snapshot::default())?;
+        let id = sess.id.clone();
+        session::close(sess)?;
+        Ok(id)
+    };
+
+    assert_eq!(run(&[])?, session::APPEND_SESSION_ID);
+    assert_eq!(run(&[])?, session::APPEND_SESSION_ID);
+    let shared = tmp_dir.join(format!("session-{}.jsonl", session::APPEND_SESSION_ID));
+    assert_eq!(fs::read_to_string(&shared)?.lines().count(), 2);
+
+    assert_eq!(run(&["--session-id", "kiosk"])?, "kiosk");
+    assert_eq!(run(&["--session-id", "kiosk"])?, "kiosk");
+    let named = tmp_dir.join("session-kiosk.jsonl");
+    assert_eq!(fs::read_to_string(&named)?.lines().count(), 2);
+
+    fs::remove_dir_all(&tmp_dir)?;
+    Ok(())
+}
+
+#[test]
+fn buffered_writes_are_on_disk_after_close() -> std::io::Result<()> {
+    let log_dir = temp_path("liminal_buffered");
+    let mut sess = session::start(50, &log_dir.to_string_lossy());
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+
+    for idx in 0..50 {
+        let snap = session::Snapshot {
+            idx,
+            ..session::Snapshot::default()
+        };
+        session::write(&mut sess, &snap)?;
+    }
+    session::close(sess)?;
+
+    let snaps = session::read_log(&log_path.to_string_lossy())?;
+    assert_eq!(snaps.len(), 50);
+    assert_eq!(snaps[49].idx, 49);
+
+    // With flush_each, a line is visible before the session closes.
+    let mut sess = session::start(1, &log_dir.to_string_lossy());
+    sess.flush_each = true;
+    session::open_file(&mut sess)?;
+    let log_path = log_dir.join(format!("session-{}.jsonl", sess.id));
+    session::write(&mut sess, &session::Snapshot::default())?;
+    assert_eq!(fs::read_to_string(&log_path)?.lines().count(), 1);
+    session::close(sess)?;
+
+    fs::remove_dir_all(&log_dir)?;
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use liminal_voice_core::softguard::{GuardAction, GuardConfig, check_and_rephrase};
+use liminal_voice_core::softguard::{
+    self, GuardAction, GuardConfig, GuardContext, GuardReason, GuardStats, RephraseStrategy,
+    check_and_rephrase,
+};
 
 fn default_cfg() -> GuardConfig {
     GuardConfig::default()
@@ -16,8 +19,9 @@ fn guard_warns_on_high_drift() {
     let cfg = default_cfg();
     let result = check_and_rephrase("hello", cfg.drift_limit + 0.1, cfg.res_limit + 0.1, &cfg);
     match result {
-        GuardAction::Warn(msg) => {
-            assert!(msg.contains("soft-guard"));
+        GuardAction::Warn { reason, message } => {
+            assert_eq!(reason, GuardReason::HighDrift);
+            assert!(message.contains("soft-guard"));
         }
         other => panic!("expected warn, got {:?}", other),
     }
@@ -28,7 +32,8 @@ fn guard_rephrases_when_resonance_low() {
     let cfg = default_cfg();
     let result = check_and_rephrase("excited!", cfg.drift_limit + 0.2, cfg.res_limit - 0.2, &cfg);
     match result {
-        GuardAction::Rephrased(text) => {
+        GuardAction::Rephrased { reason, text } => {
+            assert_eq!(reason, GuardReason::Both);
             assert!(text.contains("[recentered]"));
             assert!(!text.contains("!"));
         }
@@ -41,3 +46,297 @@ fn guard_handles_empty_text() {
     let cfg = default_cfg();
     let _ = check_and_rephrase("", cfg.drift_limit + 0.5, cfg.res_limit - 0.5, &cfg);
 }
+
+fn banned_cfg() -> GuardConfig {
+    GuardConfig {
+        banned: vec!["darn".to_string(), "heck".to_string()],
+        ..GuardConfig::default()
+    }
+}
+
+#[test]
+fn guard_leaves_clean_phrase_untouched() {
+    let cfg = banned_cfg();
+    let result = check_and_rephrase("hello there, darnell", 0.2, 0.9, &cfg);
+    assert!(matches!(result, GuardAction::None));
+}
+
+#[test]
+fn guard_redacts_banned_words_at_low_drift() {
+    let cfg = banned_cfg();
+    let result = check_and_rephrase("Well DARN it, what the heck!", 0.1, 0.95, &cfg);
+    match result {
+        GuardAction::Rephrased {
+            reason: GuardReason::Banned,
+            text,
+        } => {
+            assert_eq!(text, "Well [redacted] it, what the [redacted]!");
+        }
+        other => panic!("expected rephrased, got {:?}", other),
+    }
+}
+
+#[test]
+fn guard_redacts_before_recentering() {
+    let cfg = banned_cfg();
+    let result = check_and_rephrase(
+        "heck yes!",
+        cfg.drift_limit + 0.2,
+        cfg.res_limit - 0.2,
+        &cfg,
+    );
+    match result {
+        GuardAction::Rephrased {
+            reason: GuardReason::Banned,
+            text,
+        } => {
+            assert!(text.starts_with("[redacted] yes."));
+            assert!(text.contains("[recentered]"));
+        }
+        other => panic!("expected rephrased, got {:?}", other),
+    }
+}
+
+fn strategy_cfg(strategy: RephraseStrategy) -> GuardConfig {
+    GuardConfig {
+        strategy,
+        ..GuardConfig::default()
+    }
+}
+
+#[test]
+fn recenter_strategy_is_default() {
+    assert_eq!(GuardConfig::default().strategy, RephraseStrategy::Recenter);
+    assert_eq!(
+        softguard::rephrase("so excited!", RephraseStrategy::Recenter),
+        "so excited. [recentered]"
+    );
+}
+
+#[test]
+fn soften_strategy_lowers_shouting_and_drops_intensifiers() {
+    let cfg = strategy_cfg(RephraseStrategy::Soften);
+    let result = check_and_rephrase(
+        "This is REALLY BAD and very URGENT!",
+        cfg.drift_limit + 0.2,
+        cfg.res_limit - 0.2,
+        &cfg,
+    );
+    assert_eq!(
+        result,
+        GuardAction::Rephrased {
+            reason: GuardReason::Both,
+            text: "This is bad and urgent.".to_string(),
+        }
+    );
+}
+
+#[test]
+fn soften_keeps_acronyms_and_plain_so() {
+    let cfg = strategy_cfg(RephraseStrategy::Soften);
+    let result = check_and_rephrase(
+        "The API is down. I am so tired of this, it is REALLY not OK!",
+        cfg.drift_limit + 0.2,
+        cfg.res_limit - 0.2,
+        &cfg,
+    );
+    assert_eq!(
+        result,
+        GuardAction::Rephrased {
+            reason: GuardReason::Both,
+            text: "The API is down. I am so tired of this, it is not OK.".to_string(),
+        }
+    );
+}
+
+#[test]
+fn shorten_strategy_keeps_first_sentence() {
+    assert_eq!(
+        softguard::rephrase(
+            "Stop. Take a breath. Then try again!",
+            RephraseStrategy::Shorten
+        ),
+        "Stop."
+    );
+    assert_eq!(
+        softguard::rephrase("wait?! what now", RephraseStrategy::Shorten),
+        "wait?!"
+    );
+    assert_eq!(
+        softguard::rephrase("no punctuation here", RephraseStrategy::Shorten),
+        "no punctuation here"
+    );
+}
+
+#[test]
+fn guard_reason_tracks_drift_and_resonance() {
+    let cfg = default_cfg();
+    let (hi_d, lo_d) = (cfg.drift_limit + 0.1, cfg.drift_limit - 0.1);
+    let (hi_r, lo_r) = (cfg.res_limit + 0.1, cfg.res_limit - 0.1);
+
+    let reason = |drift, res| check_and_rephrase("hello", drift, res, &cfg).reason();
+    assert_eq!(reason(lo_d, hi_r), None);
+    assert_eq!(reason(hi_d, hi_r), Some(GuardReason::HighDrift));
+    assert_eq!(reason(lo_d, lo_r), None);
+    let warn_low = GuardConfig {
+        warn_low_res: true,
+        ..default_cfg()
+    };
+    assert!(matches!(
+        check_and_rephrase("hello", lo_d, lo_r, &warn_low),
+        GuardAction::Warn {
+            reason: GuardReason::LowResonance,
+            ..
+        }
+    ));
+    assert_eq!(reason(hi_d, lo_r), Some(GuardReason::Both));
+
+    let banned = banned_cfg();
+    let action = check_and_rephrase("oh heck", hi_d, lo_r, &banned);
+    assert_eq!(action.reason(), Some(GuardReason::Banned));
+    assert_eq!(action.label().as_deref(), Some("rephrased:banned"));
+    assert_eq!(
+        check_and_rephrase("hello", hi_d, hi_r, &cfg)
+            .label()
+            .as_deref(),
+        Some("warn:high_drift")
+    );
+}
+
+#[test]
+fn guard_stats_count_each_action_kind() {
+    let cfg = banned_cfg();
+    let (hi_d, lo_d) = (cfg.drift_limit + 0.1, cfg.drift_limit - 0.1);
+    let (hi_r, lo_r) = (cfg.res_limit + 0.1, cfg.res_limit - 0.1);
+
+    let mut stats = GuardStats::default();
+    for (text, drift, res) in [
+        ("hello", lo_d, hi_r),
+        ("hello", hi_d, hi_r),
+        ("hello", lo_d, lo_r),
+        ("hello!", hi_d, lo_r),
+        ("oh heck", lo_d, hi_r),
+        ("darn it!", hi_d, lo_r),
+    ] {
+        stats.record(&check_and_rephrase(text, drift, res, &cfg));
+    }
+
+    assert_eq!(
+        stats,
+        GuardStats {
+            warns: 1,
+            rephrases: 1,
+            redactions: 2,
+            blocks: 0,
+        }
+    );
+    assert_eq!(stats.total(), 4);
+    assert!(
+        stats
+            .summary_line()
+            .starts_with("[guard] interventions: warns=1")
+    );
+}
+
+#[test]
+fn guard_reengages_very_low_resonance() {
+    let cfg = default_cfg();
+    let very_low = cfg.res_limit - cfg.low_res_margin - 0.05;
+    match check_and_rephrase("sure, whatever", 0.1, very_low, &cfg) {
+        GuardAction::Rephrased { reason, text } => {
+            assert_eq!(reason, GuardReason::LowResonance);
+            assert_eq!(text, format!("sure, whatever {}", softguard::REENGAGE_CUE));
+        }
+        other => panic!("expected rephrased, got {:?}", other),
+    }
+
+    let wide = GuardConfig {
+        low_res_margin: 0.5,
+        ..default_cfg()
+    };
+    assert_eq!(
+        check_and_rephrase("sure, whatever", 0.1, very_low, &wide),
+        GuardAction::None
+    );
+    let wide_warn = GuardConfig {
+        warn_low_res: true,
+        ..wide
+    };
+    assert!(matches!(
+        check_and_rephrase("sure, whatever", 0.1, very_low, &wide_warn),
+        GuardAction::Warn { .. }
+    ));
+}
+
+#[test]
+fn rephrase_only_touches_offending_sentences() {
+    let text = "Thanks for waiting. This is AMAZING news!";
+    assert_eq!(
+        softguard::rephrase(text, RephraseStrategy::Recenter),
+        "Thanks for waiting. This is AMAZING news. [recentered]"
+    );
+    assert_eq!(
+        softguard::rephrase(text, RephraseStrategy::Soften),
+        "Thanks for waiting. This is amazing news."
+    );
+    assert_eq!(
+        softguard::split_sentences("One. Two?! three"),
+        vec!["One.", "Two?!", "three"]
+    );
+}
+
+#[test]
+fn compassion_warms_the_rephrase() {
+    let cfg = default_cfg();
+    let (drift, res) = (cfg.drift_limit + 0.2, cfg.res_limit - 0.2);
+    let rephrase_at = |level: f32| {
+        let ctx = GuardContext {
+            compassion_level: level,
+        };
+        match softguard::check_and_rephrase_with("I give up!", drift, res, &cfg, &ctx) {
+            GuardAction::Rephrased { text, .. } => text,
+            other => panic!("expected rephrased, got {:?}", other),
+        }
+    };
+
+    let clinical = rephrase_at(0.0);
+    assert_eq!(
+        clinical,
+        match check_and_rephrase("I give up!", drift, res, &cfg) {
+            GuardAction::Rephrased { text, .. } => text,
+            other => panic!("expected rephrased, got {:?}", other),
+        }
+    );
+    assert_eq!(clinical, "I give up. [recentered]");
+
+    let warm = rephrase_at(0.8);
+    assert_eq!(warm, "Take your time — I give up.");
+}
+
+#[test]
+fn guard_blocks_unrecoverable_inputs() {
+    let cfg = GuardConfig {
+        hard_drift_limit: Some(0.85),
+        ..banned_cfg()
+    };
+    let extreme = check_and_rephrase("whatever!", 0.90, 0.2, &cfg);
+    assert_eq!(extreme, GuardAction::Block(GuardReason::HighDrift));
+    assert_eq!(extreme.label().as_deref(), Some("blocked:high_drift"));
+
+    let mostly_banned = check_and_rephrase("darn heck it", 0.1, 0.9, &cfg);
+    assert_eq!(mostly_banned, GuardAction::Block(GuardReason::Banned));
+
+    let unlimited = check_and_rephrase("whatever!", 0.99, 0.2, &banned_cfg());
+    assert!(
+        matches!(unlimited, GuardAction::Rephrased { .. }),
+        "no drift block unless a hard limit is set"
+    );
+
+    let recoverable = check_and_rephrase("darn, that was close", 0.1, 0.9, &cfg);
+    assert!(matches!(recoverable, GuardAction::Rephrased { .. }));
+
+    let mut stats = GuardStats::default();
+    stats.record(&extreme);
+    stats.record(&mostly_banned);
+    assert_eq!(stats.blocks, 2);
+}
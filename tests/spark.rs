@@ -14,3 +14,26 @@ fn sparkline_uses_defined_glyphs() {
         assert!(spark::GLYPHS.contains(&ch));
     }
 }
+
+#[test]
+fn scaled_sparkline_spreads_a_narrow_range() {
+    let values = [0.30_f32, 0.32, 0.35, 0.38, 0.40];
+    let fixed = spark::sparkline(&values);
+    let fixed_glyphs: std::collections::HashSet<char> = fixed.chars().collect();
+    assert!(fixed_glyphs.len() <= 2);
+
+    let scaled = spark::sparkline_scaled(&values);
+    let glyphs: Vec<char> = scaled.chars().collect();
+    assert_eq!(glyphs.len(), values.len());
+    assert_eq!(glyphs.first(), Some(&spark::GLYPHS[1]));
+    assert_eq!(glyphs.last(), spark::GLYPHS.last());
+    let distinct: std::collections::HashSet<char> = glyphs.iter().copied().collect();
+    assert_eq!(distinct.len(), values.len());
+}
+
+#[test]
+fn scaled_sparkline_falls_back_on_flat_series() {
+    let flat = [0.5_f32; 4];
+    assert_eq!(spark::sparkline_scaled(&flat), spark::sparkline(&flat));
+    assert_eq!(spark::sparkline_scaled(&[]), "");
+}
@@ -1,4 +1,9 @@
-use liminal_voice_core::stabilizer::{self, EmoState, Stabilizer, StabilizerCfg};
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::stabilizer::{
+    self, AdviceTable, EmoState, StabSnapshot, Stabilizer, StabilizerCfg,
+};
 
 #[test]
 fn progression_and_advice_mapping() {
@@ -10,6 +15,9 @@ fn progression_and_advice_mapping() {
         low_res: 0.58,
         cool_steps: 3,
         calm_boost: 0.08,
+        panic_drift: 0.95,
+        hysteresis: 0.0,
+        use_median: false,
     };
 
     let mut stab = Stabilizer::new(cfg);
@@ -43,7 +51,308 @@ fn progression_and_advice_mapping() {
     stab.push(0.25, 0.78);
     assert_eq!(stab.state, EmoState::Normal);
 
-    let status = stabilizer::format_status(stab.state, stab.ema_drift, stab.ema_res);
+    let status = stabilizer::format_status(stab.state, stab.ema_drift, stab.ema_res, None);
     assert!(!status.is_empty());
     assert!(status.contains("state=Normal"));
 }
+
+fn spike_cfg(use_median: bool) -> StabilizerCfg {
+    StabilizerCfg {
+        win: 5,
+        ema_alpha: 0.4,
+        warm_drift: 0.32,
+        hot_drift: 0.42,
+        low_res: 0.58,
+        cool_steps: 3,
+        calm_boost: 0.08,
+        panic_drift: 0.95,
+        hysteresis: 0.02,
+        use_median,
+    }
+}
+
+#[test]
+fn median_mode_ignores_a_lone_spike() {
+    let mut instant = Stabilizer::new(spike_cfg(false));
+    let mut median = Stabilizer::new(spike_cfg(true));
+    for stab in [&mut instant, &mut median] {
+        for _ in 0..3 {
+            stab.push(0.20, 0.80);
+        }
+        stab.push(0.90, 0.30);
+    }
+
+    assert_eq!(instant.state, EmoState::Overheat);
+    assert_eq!(median.state, EmoState::Normal);
+    assert!((median.ema_drift - instant.ema_drift).abs() < f32::EPSILON);
+
+    // A sustained run still gets through the median.
+    for _ in 0..3 {
+        median.push(0.90, 0.30);
+    }
+    assert_eq!(median.state, EmoState::Overheat);
+}
+
+#[test]
+fn sustained_extreme_drift_escalates_to_panic_and_recovers() {
+    let mut stab = Stabilizer::new(StabilizerCfg {
+        panic_drift: 0.8,
+        ..spike_cfg(false)
+    });
+    let cool_steps = stab.cfg.cool_steps;
+
+    stab.push(0.90, 0.30);
+    assert_eq!(stab.state, EmoState::Overheat);
+    for _ in 1..cool_steps {
+        stab.push(0.90, 0.30);
+    }
+    assert_eq!(stab.state, EmoState::Panic);
+
+    let panic = stab.advice();
+    stab.state = EmoState::Overheat;
+    let overheat = stab.advice();
+    stab.state = EmoState::Panic;
+    assert!(panic.pace_delta < overheat.pace_delta);
+    assert!(panic.pause_delta_ms > overheat.pause_delta_ms);
+    assert!(
+        stabilizer::format_status(stab.state, stab.ema_drift, stab.ema_res, None)
+            .contains("state=Panic")
+    );
+
+    stab.push(0.90, 0.30);
+    assert_eq!(
+        stab.state,
+        EmoState::Panic,
+        "held while drift stays extreme"
+    );
+
+    for _ in 0..cool_steps {
+        stab.push(0.20, 0.80);
+        assert_eq!(stab.state, EmoState::Cooldown);
+    }
+    stab.push(0.20, 0.80);
+    assert_eq!(stab.state, EmoState::Normal);
+}
+
+#[test]
+fn panic_decays_through_cooldown_while_drift_stays_warm() {
+    let mut stab = Stabilizer::new(StabilizerCfg {
+        panic_drift: 0.8,
+        ..spike_cfg(false)
+    });
+    let cool_steps = stab.cfg.cool_steps;
+    for _ in 0..cool_steps {
+        stab.push(0.90, 0.30);
+    }
+    assert_eq!(stab.state, EmoState::Panic);
+
+    let lingering = 0.50;
+    assert!(lingering > stab.cfg.warm_drift && lingering < stab.cfg.panic_drift);
+    for i in 0..cool_steps {
+        stab.push(lingering, 0.75);
+        assert_eq!(stab.state, EmoState::Cooldown, "push {}", i);
+    }
+    stab.push(lingering, 0.75);
+    assert_eq!(stab.state, EmoState::Warming);
+}
+
+#[test]
+fn snapshot_round_trips_through_jsonl() {
+    let mut stab = Stabilizer::new(spike_cfg(false));
+    for (drift, res) in [
+        (0.20, 0.80),
+        (0.34, 0.70),
+        (0.45, 0.55),
+        (0.30, 0.75),
+        (0.50, 0.40),
+        (0.28, 0.77),
+        (0.36, 0.66),
+    ] {
+        stab.push(drift, res);
+    }
+
+    let snap = stab.snapshot();
+    assert_eq!(snap.ring_drift.len(), 5);
+    assert!(
+        (snap.ring_drift[4] - 0.36).abs() < 1e-6,
+        "newest sample last"
+    );
+
+    let parsed = StabSnapshot::from_json_line(&snap.to_json_line()).expect("parse snapshot");
+    let restored = Stabilizer::restore(spike_cfg(false), &parsed);
+    assert_eq!(restored.state, stab.state);
+    assert_eq!(restored.steps_in_state, stab.steps_in_state);
+    assert!((restored.ema_drift - stab.ema_drift).abs() < 1e-5);
+    assert!((restored.ema_res - stab.ema_res).abs() < 1e-5);
+    assert_eq!(restored.snapshot().ring_drift.len(), 5);
+
+    let path = temp_path("liminal_stab_state.jsonl");
+    let path_str = path.to_string_lossy().to_string();
+    let _ = std::fs::remove_file(&path);
+    assert!(stabilizer::load_snapshot(&path_str).is_none());
+    stabilizer::save_snapshot(&path_str, &Stabilizer::new(spike_cfg(false)).snapshot()).unwrap();
+    stabilizer::save_snapshot(&path_str, &snap).unwrap();
+    let loaded = stabilizer::load_snapshot(&path_str).expect("latest snapshot");
+    let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(lines, 1, "each save replaces the file");
+    assert_eq!(loaded.state, snap.state);
+    assert_eq!(loaded.ring_res.len(), snap.ring_res.len());
+}
+
+#[test]
+fn restart_mid_run_keeps_counting_toward_panic() {
+    let cfg = StabilizerCfg {
+        panic_drift: 0.8,
+        ..spike_cfg(false)
+    };
+    let mut stab = Stabilizer::new(cfg);
+    for _ in 1..cfg.cool_steps {
+        stab.push(0.90, 0.30);
+    }
+    assert_eq!(stab.state, EmoState::Overheat);
+
+    let line = stab.snapshot().to_json_line();
+    let snap = StabSnapshot::from_json_line(&line).expect("parse snapshot");
+    assert_eq!(snap.extreme_streak, cfg.cool_steps - 1);
+
+    let mut restored = Stabilizer::restore(cfg, &snap);
+    restored.push(0.90, 0.30);
+    assert_eq!(restored.state, EmoState::Panic);
+
+    // Snapshots saved before the streak was persisted still load.
+    let legacy = line.replace(&format!(",\"extreme_streak\":{}", cfg.cool_steps - 1), "");
+    assert_eq!(
+        StabSnapshot::from_json_line(&legacy).map(|s| s.extreme_streak),
+        Some(0)
+    );
+}
+
+#[test]
+fn advice_table_overrides_per_state_values() {
+    let mut stab = Stabilizer::new(spike_cfg(false));
+    assert_eq!(stab.advice_table, AdviceTable::default());
+
+    stab.advice_table.warming.pace_delta = -0.10;
+    stab.push(0.34, 0.70);
+    assert_eq!(stab.state, EmoState::Warming);
+    assert!((stab.advice().pace_delta + 0.10).abs() < 1e-6);
+
+    stab.push(0.45, 0.55);
+    assert_eq!(stab.state, EmoState::Overheat);
+    let expected = AdviceTable::default().overheat.pace_delta - stab.cfg.calm_boost;
+    assert!((stab.advice().pace_delta - expected).abs() < 1e-6);
+}
+
+#[test]
+fn history_is_chronological_across_wraparound() {
+    let mut stab = Stabilizer::new(spike_cfg(false));
+    stab.push(0.10, 0.90);
+    stab.push(0.20, 0.80);
+    assert_eq!(stab.history(), (vec![0.10, 0.20], vec![0.90, 0.80]));
+
+    for i in 3..=7 {
+        stab.push(i as f32 / 10.0, 0.5);
+    }
+    let (drift, res) = stab.history();
+    assert_eq!(drift.len(), 5);
+    let expected = [0.3, 0.4, 0.5, 0.6, 0.7];
+    for (got, want) in drift.iter().zip(expected) {
+        assert!((got - want).abs() < 1e-6, "history={:?}", drift);
+    }
+    assert!(res.iter().all(|r| (r - 0.5).abs() < 1e-6));
+}
+
+#[test]
+fn reset_clears_state_but_keeps_cfg() {
+    let mut stab = Stabilizer::new(spike_cfg(true));
+    stab.advice_table.warming.pace_delta = -0.2;
+    for _ in 0..4 {
+        stab.push(0.90, 0.30);
+    }
+    assert_ne!(stab.state, EmoState::Normal);
+
+    stab.reset();
+    assert_eq!(stab.state, EmoState::Normal);
+    assert_eq!(stab.steps_in_state, 0);
+    assert_eq!(stab.history(), (Vec::new(), Vec::new()));
+    assert!(stab.cfg.use_median);
+    assert_eq!(stab.cfg.win, 5);
+    assert!((stab.advice_table.warming.pace_delta + 0.2).abs() < 1e-6);
+
+    // The first push after a reset seeds the EMA again.
+    stab.push(0.25, 0.70);
+    assert!((stab.ema_drift - 0.25).abs() < 1e-6);
+    assert!((stab.ema_res - 0.70).abs() < 1e-6);
+    assert_eq!(stab.history(), (vec![0.25], vec![0.70]));
+}
+
+#[test]
+fn hysteresis_keeps_warming_stable_near_threshold() {
+    let flappy = StabilizerCfg {
+        hysteresis: 0.0,
+        ..spike_cfg(false)
+    };
+    let mut without = Stabilizer::new(flappy);
+    let mut with = Stabilizer::new(spike_cfg(false));
+    let warm = with.cfg.warm_drift;
+
+    let mut flips = 0;
+    for i in 0..8 {
+        let drift = if i % 2 == 0 {
+            warm + 0.005
+        } else {
+            warm - 0.005
+        };
+        let before = without.state;
+        without.push(drift, 0.80);
+        with.push(drift, 0.80);
+        if i > 0 && without.state != before {
+            flips += 1;
+        }
+        if i > 0 {
+            assert_eq!(with.state, EmoState::Warming, "push {}", i);
+        }
+    }
+    assert!(flips >= 6, "no hysteresis should flap, flips={}", flips);
+
+    with.push(warm - with.cfg.hysteresis - 0.01, 0.80);
+    assert_eq!(with.state, EmoState::Normal);
+}
+
+#[test]
+fn cooldown_remaining_counts_down() {
+    let mut stab = Stabilizer::new(spike_cfg(false));
+    let cool_steps = stab.cfg.cool_steps;
+    stab.push(0.20, 0.80);
+    assert_eq!(stab.cooldown_remaining(), None);
+
+    // Overheat held for fewer than cool_steps pushes, so it cools down.
+    for _ in 1..cool_steps {
+        stab.push(0.45, 0.55);
+        assert_eq!(stab.state, EmoState::Overheat);
+        assert_eq!(
+            stab.cooldown_remaining(),
+            None,
+            "not cooling while overheated"
+        );
+    }
+
+    let mut seen = Vec::new();
+    stab.push(0.25, 0.75);
+    while stab.state == EmoState::Cooldown {
+        seen.push(stab.cooldown_remaining().expect("cooling"));
+        let status = stabilizer::format_status(
+            stab.state,
+            stab.ema_drift,
+            stab.ema_res,
+            stab.cooldown_remaining(),
+        );
+        assert!(status.contains(&format!("cooldown_remaining={}", seen[seen.len() - 1])));
+        stab.push(0.25, 0.75);
+    }
+    let expected: Vec<usize> = (1..=cool_steps).rev().collect();
+    assert_eq!(seen, expected, "one fewer per push, ending at 1");
+    assert_eq!(stab.state, EmoState::Normal);
+    assert_eq!(stab.cooldown_remaining(), None);
+}
@@ -1,12 +1,11 @@
+mod common;
+
+use common::temp_path;
 use liminal_voice_core::stabilizer::EmoState;
-use liminal_voice_core::sync::{Baselines, Seeds, SyncCfg, SyncState};
+use liminal_voice_core::sync::{self, Baselines, Residual, Seeds, SyncCfg, SyncState};
 
 fn default_cfg() -> SyncCfg {
-    SyncCfg {
-        lr_fast: 0.15,
-        lr_slow: 0.05,
-        clamp_step: 0.02,
-    }
+    SyncCfg::default()
 }
 
 #[test]
@@ -60,8 +59,8 @@ fn to_slow_increments_reflects_means() {
         "drift bias should push Astro toward lower drift"
     );
     assert!(res_bias > 0.0, "res bias should encourage higher resonance");
-    assert!(drift_bias >= -0.03 && drift_bias <= 0.0);
-    assert!(res_bias >= 0.0 && res_bias <= 0.03);
+    assert!((-0.03..=0.0).contains(&drift_bias));
+    assert!((0.0..=0.03).contains(&res_bias));
 }
 
 #[test]
@@ -116,3 +115,271 @@ fn warm_start_resets_accumulators() {
     assert_eq!(sync.accum_res, 0.0);
     assert_eq!(sync.steps, 0);
 }
+
+#[test]
+fn seeds_round_trip_through_jsonl() {
+    let path = temp_path("liminal_sync_seeds.jsonl");
+    let path = path.to_str().unwrap().to_string();
+    let _ = std::fs::remove_file(&path);
+    assert!(sync::load_seeds(&path).is_none());
+
+    let first = Seeds {
+        pace_bias: 0.01,
+        pause_bias_ms: 10,
+        res_warm: 0.0,
+        drift_soft: 0.0,
+    };
+    let latest = Seeds {
+        pace_bias: -0.035,
+        pause_bias_ms: -12,
+        res_warm: 0.012,
+        drift_soft: 0.004,
+    };
+    sync::save_seeds(&path, &first).unwrap();
+    sync::save_seeds(&path, &latest).unwrap();
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| std::io::Write::write_all(&mut f, b"{not json\n"))
+        .unwrap();
+
+    assert_eq!(sync::load_seeds(&path), Some(latest));
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        contents.lines().count(),
+        2,
+        "saves overwrite instead of appending"
+    );
+    assert_eq!(Seeds::from_json_line(&latest.to_json_line()), Some(latest));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn updated_seeds_fold_slow_increments() {
+    let cfg = default_cfg();
+    let mut sync = SyncState::default();
+    sync.warm_start(
+        Seeds::default(),
+        Baselines {
+            drift: 0.3,
+            res: 0.7,
+        },
+    );
+    for _ in 0..4 {
+        sync.step(0.5, 0.55, EmoState::Normal, &cfg);
+    }
+
+    let updated = sync.updated_seeds(&cfg);
+    assert_eq!(updated.pace_bias, 0.0);
+    assert_eq!(updated.pause_bias_ms, 0);
+    assert!(updated.drift_soft > 0.0);
+    assert!(updated.res_warm > 0.0);
+}
+
+#[test]
+fn momentum_smooths_alternating_drift() {
+    fn pace_swing(momentum: f32) -> f32 {
+        let cfg = SyncCfg {
+            lr_fast: 0.05,
+            momentum,
+            ..SyncCfg::default()
+        };
+        let mut sync = SyncState::default();
+        sync.warm_start(
+            Seeds::default(),
+            Baselines {
+                drift: 0.3,
+                res: 0.7,
+            },
+        );
+        let paces: Vec<f32> = (0..8)
+            .map(|i| {
+                let drift = if i % 2 == 0 { 0.6 } else { 0.1 };
+                sync.step(drift, 0.7, EmoState::Normal, &cfg).0
+            })
+            .collect();
+        paces.windows(2).map(|w| (w[1] - w[0]).abs()).sum()
+    }
+
+    let plain = pace_swing(0.0);
+    let smoothed = pace_swing(0.6);
+    assert!(smoothed < plain * 0.7, "plain={plain} smoothed={smoothed}");
+}
+
+#[test]
+fn overheat_learning_rate_overrides_lr_fast() {
+    let cfg = SyncCfg {
+        lr_fast: 0.05,
+        clamp_step: 0.1,
+        lr_overheat: Some(0.2),
+        ..SyncCfg::default()
+    };
+    assert_eq!(cfg.lr_for(EmoState::Normal), 0.05);
+    assert_eq!(cfg.lr_for(EmoState::Warming), 0.05);
+    assert_eq!(cfg.lr_for(EmoState::Panic), 0.2);
+
+    let base = Baselines {
+        drift: 0.3,
+        res: 0.7,
+    };
+    let mut normal = SyncState::default();
+    normal.warm_start(Seeds::default(), base);
+    let mut hot = SyncState::default();
+    hot.warm_start(Seeds::default(), base);
+
+    let (normal_pace, ..) = normal.step(0.5, 0.7, EmoState::Normal, &cfg);
+    let (hot_pace, ..) = hot.step(0.5, 0.7, EmoState::Overheat, &cfg);
+    // Discount the fixed Overheat slowdown so only the learning rate differs.
+    assert!((hot_pace + 0.01).abs() > normal_pace.abs() * 2.0);
+}
+
+#[test]
+fn accum_decay_fades_early_spike() {
+    fn slow_drift_bias(accum_decay: f32) -> f32 {
+        let cfg = SyncCfg {
+            accum_decay,
+            ..SyncCfg::default()
+        };
+        let mut sync = SyncState::default();
+        sync.warm_start(
+            Seeds::default(),
+            Baselines {
+                drift: 0.3,
+                res: 0.7,
+            },
+        );
+        sync.step(0.9, 0.7, EmoState::Overheat, &cfg);
+        for _ in 0..30 {
+            sync.step(0.3, 0.7, EmoState::Normal, &cfg);
+        }
+        sync.to_slow_increments(&cfg).0
+    }
+
+    let plain = slow_drift_bias(1.0);
+    let decayed = slow_drift_bias(0.8);
+    assert!(plain < 0.0);
+    assert!(
+        decayed.abs() < plain.abs() * 0.1,
+        "plain={plain} decayed={decayed}"
+    );
+}
+
+#[test]
+fn effective_count_matches_steps_without_decay() {
+    let cfg = default_cfg();
+    assert_eq!(cfg.effective_count(7), 7.0);
+    let decayed = SyncCfg {
+        accum_decay: 0.5,
+        ..default_cfg()
+    };
+    assert!((decayed.effective_count(30) - 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn recent_residuals_track_steps() {
+    let cfg = default_cfg();
+    let mut sync = SyncState::default();
+    sync.residual_capacity = 4;
+    sync.warm_start(
+        Seeds::default(),
+        Baselines {
+            drift: 0.3,
+            res: 0.7,
+        },
+    );
+    let drifts = [0.35, 0.4, 0.45, 0.5, 0.55, 0.6];
+    for drift in drifts {
+        sync.step(drift, 0.65, EmoState::Normal, &cfg);
+    }
+
+    let recent = sync.recent_residuals(10);
+    assert_eq!(recent.len(), 4);
+    let last = recent.last().copied().unwrap();
+    assert!((last.d_drift - (0.6 - 0.3)).abs() < 1e-6);
+    assert!((last.d_res - (0.7 - 0.65)).abs() < 1e-6);
+    assert!(recent[0].d_drift < last.d_drift);
+    assert_eq!(sync.recent_residuals(1), vec![last]);
+
+    sync.warm_start(Seeds::default(), sync.baselines);
+    assert!(sync.recent_residuals(3).is_empty());
+}
+
+#[test]
+fn zero_capacity_disables_residual_history() {
+    let mut sync = SyncState::default();
+    sync.residual_capacity = 0;
+    sync.step(0.5, 0.5, EmoState::Normal, &default_cfg());
+    assert_eq!(sync.recent_residuals(5), Vec::<Residual>::new());
+}
+
+#[test]
+fn wider_pause_clamp_allows_longer_pause() {
+    fn first_pause(pause_clamp_ms: (i64, i64)) -> i64 {
+        let cfg = SyncCfg {
+            lr_fast: 1.0,
+            pause_clamp_ms,
+            ..SyncCfg::default()
+        };
+        let mut sync = SyncState::default();
+        sync.warm_start(
+            Seeds::default(),
+            Baselines {
+                drift: 0.3,
+                res: 0.7,
+            },
+        );
+        sync.step(0.3, 0.0, EmoState::Normal, &cfg).1
+    }
+
+    assert_eq!(first_pause((-20, 40)), 40);
+    assert!(first_pause((-20, 80)) > 50);
+}
+
+#[test]
+fn boost_cap_follows_clamp_step_unless_set() {
+    fn first_boost(clamp_step: f32, boost_clamp: Option<f32>) -> f32 {
+        let cfg = SyncCfg {
+            lr_fast: 1.0,
+            clamp_step,
+            boost_clamp,
+            ..SyncCfg::default()
+        };
+        let mut sync = SyncState::default();
+        sync.warm_start(
+            Seeds::default(),
+            Baselines {
+                drift: 0.3,
+                res: 0.7,
+            },
+        );
+        sync.step(0.3, 0.0, EmoState::Normal, &cfg).2
+    }
+
+    assert!((first_boost(0.02, None) - 0.02).abs() < 1e-6);
+    assert!((first_boost(0.05, None) - 0.035).abs() < 1e-6);
+    assert!((first_boost(0.05, Some(0.01)) - 0.01).abs() < 1e-6);
+}
+
+#[test]
+fn blend_moves_seeds_partway() {
+    let merged = Seeds {
+        pace_bias: 0.02,
+        pause_bias_ms: 10,
+        res_warm: 0.0,
+        drift_soft: 0.04,
+    };
+    let saved = Seeds {
+        pace_bias: -0.02,
+        pause_bias_ms: 30,
+        res_warm: 0.02,
+        drift_soft: 0.0,
+    };
+
+    assert_eq!(merged.blend(&saved, 0.0), merged);
+    assert_eq!(merged.blend(&saved, 1.0), saved);
+    let half = merged.blend(&saved, 0.5);
+    assert!(half.pace_bias.abs() < 1e-6);
+    assert_eq!(half.pause_bias_ms, 20);
+    assert!((half.res_warm - 0.01).abs() < 1e-6);
+    assert!((half.drift_soft - 0.02).abs() < 1e-6);
+}
@@ -1,4 +1,11 @@
-use liminal_voice_core::viz;
+mod common;
+
+use common::json_field;
+use liminal_voice_core::alerts::{self, AlertStats};
+use liminal_voice_core::awareness::MetaCognition;
+use liminal_voice_core::compassion::{CompassionMetrics, SufferingType};
+use liminal_voice_core::viz::{TableData, VizCfg};
+use liminal_voice_core::{spark, viz};
 
 #[test]
 fn bar_zero_empty() {
@@ -12,7 +19,163 @@ fn bar_full_length() {
 
 #[test]
 fn print_table_outputs_lines() {
-    let lines = viz::print_table(0.12, 0.88, 162.0, 0.74, "Neutral", 45, 32, 90, None, None, None, None);
+    let lines = viz::print_table(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg::default());
     assert!(!lines.is_empty());
     assert!(lines.iter().any(|line| line.contains("Semantic Drift")));
 }
+
+#[test]
+fn print_table_accepts_a_shared_table_data() {
+    let data = TableData { drift: 0.30, res: 0.65, tone: "Calm", stab_state: Some("Stable"), ..Default::default() };
+    let lines = viz::print_table(&data, &VizCfg::default());
+    assert!(lines.iter().any(|line| line.contains("Semantic Drift")));
+    assert!(lines.iter().any(|line| line.contains("Calm")));
+    assert_eq!(json_field(&viz::table_json(&data), "tone"), "Calm");
+}
+
+#[test]
+fn print_table_shows_filler_density_when_present() {
+    let without = viz::print_table(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg::default());
+    assert!(!without.iter().any(|line| line.contains("Filler Density")));
+
+    let with = viz::print_table(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.25, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg::default());
+    assert!(with.iter().any(|line| line.contains("Filler Density") && line.contains("0.25")));
+}
+
+#[test]
+fn table_json_carries_the_table_metrics() {
+    let json = viz::table_json(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, stab_state: Some("Stable"), ..Default::default() });
+    assert!(json.starts_with('{') && json.ends_with('}'));
+    assert_eq!(json.matches('{').count(), json.matches('}').count());
+
+    let drift: f32 = json_field(&json, "drift").parse().expect("drift is a number");
+    assert!((drift - 0.12).abs() < 1e-6);
+    assert_eq!(json_field(&json, "resonance"), "0.880");
+    assert_eq!(json_field(&json, "tone"), "Neutral");
+    assert_eq!(json_field(&json, "total_ms"), "90");
+    assert_eq!(json_field(&json, "stabilizer"), "Stable");
+    assert_eq!(json_field(&json, "meta"), "null");
+    assert_eq!(json_field(&json, "compassion"), "null");
+
+    let meta = MetaCognition::new();
+    let with_meta = viz::table_json(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, meta_cognition: Some(&meta), ..Default::default() });
+    assert!(with_meta.contains(&format!("\"meta\":{}", meta.to_json())));
+}
+
+#[test]
+fn color_never_has_no_escapes_and_always_does() {
+    let plain = viz::print_table(&TableData { drift: 0.72, res: 0.30, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg::default());
+    assert!(plain.iter().all(|line| !line.contains('\x1b')));
+
+    let colored = viz::print_table(&TableData { drift: 0.72, res: 0.30, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg { color: true, ..VizCfg::default() });
+    let drift_row = colored.iter().find(|line| line.contains("Semantic Drift")).unwrap();
+    assert!(drift_row.contains("\x1b[31m"));
+    let res_row = colored.iter().find(|line| line.contains("Resonance")).unwrap();
+    assert!(res_row.contains("\x1b[31m"));
+
+    assert_eq!(viz::drift_severity(0.1), viz::Severity::Ok);
+    assert_eq!(viz::drift_severity(0.4), viz::Severity::Warn);
+    assert_eq!(viz::resonance_severity(0.9), viz::Severity::Ok);
+    assert_eq!(viz::resonance_severity(0.5), viz::Severity::Warn);
+    assert_eq!(viz::paint("x", viz::Severity::Ok, false), "x");
+}
+
+#[test]
+fn print_table_shows_trend_arrows() {
+    let rising = viz::print_table(&TableData { drift: 0.45, res: 0.70, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, drift_prev: Some(0.30), res_prev: Some(0.71), ..Default::default() }, &VizCfg::default());
+    assert!(rising.iter().any(|line| line.contains("Semantic Drift ↑")));
+    assert!(rising.iter().any(|line| line.contains("Resonance →")));
+
+    let no_history = viz::print_table(&TableData { drift: 0.45, res: 0.70, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &VizCfg::default());
+    assert!(!no_history.iter().any(|line| line.contains('↑') || line.contains('→')));
+
+    assert_eq!(viz::trend(0.5, 0.4, 0.02), viz::Trend::Falling);
+    assert_eq!(viz::trend_arrow(viz::Trend::Rising, true), "^");
+    assert_eq!(viz::trend_arrow(viz::Trend::Falling, true), "v");
+    assert_eq!(viz::trend_arrow(viz::Trend::Flat, true), "-");
+}
+
+#[test]
+fn ascii_mode_output_is_pure_ascii() {
+    let mut meta = MetaCognition::new();
+    meta.doubt = 0.9;
+    meta.confidence = 0.1;
+    let mut comp = CompassionMetrics::new();
+    comp.suffering_type = SufferingType::Severe;
+    comp.user_suffering = 0.9;
+    comp.compassion_level = 0.9;
+
+    let lines = viz::print_table(&TableData { drift: 0.72, res: 0.30, wpm: 162.0, articulation: 0.74, filler_density: 0.2, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, stab_state: Some("Overheat"), meta_cognition: Some(&meta), compassion: Some(&comp), drift_prev: Some(0.5), res_prev: Some(0.4), ..Default::default() }, &VizCfg { ascii: true, ..VizCfg::default() });
+    assert!(lines.iter().any(|line| line.contains("UNCERTAIN STATE")));
+    assert!(lines.iter().any(|line| line.contains("Semantic Drift ^")));
+
+    let mut text = lines.join("\n");
+    text.push_str(&spark::sparkline_for(&[0.0, 0.3, 0.6, 1.0], true));
+    text.push_str(&spark::sparkline_scaled_for(&[0.30, 0.35, 0.40], true));
+    text.push_str(&comp.status_message(true));
+    let stats = AlertStats { drift_breaches: 1, total: 1, ..AlertStats::default() };
+    text.push_str(&alerts::summary_lines(&stats, 0.35, 0.65, true).join("\n"));
+    assert!(text.is_ascii(), "non-ASCII output: {}", text);
+    assert!(text.contains("[<3]") && text.contains("[!]"));
+}
+
+#[test]
+fn print_table_honours_custom_widths() {
+    let mut meta = MetaCognition::new();
+    meta.doubt = 0.9;
+    for width in [40, 44, 46, 54, 80] {
+        let narrow = VizCfg::for_width(width);
+        assert_eq!(narrow.table_width(), width);
+        assert!(narrow.label_width >= "Latency (ASR/TTS/T)".len());
+        let lines = viz::print_table(&TableData { drift: 0.12, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.3, tone: "Neutral", asr_ms: 145, tts_ms: 132, total_ms: 290, drift_prev: Some(0.3), meta_cognition: Some(&meta), ..Default::default() }, &narrow);
+        for line in &lines {
+            assert_eq!(line.chars().count(), width, "{}-column row {:?}", width, line);
+        }
+        assert!(lines.iter().any(|line| line.contains("Latency (ASR/TTS/T)")));
+        assert!(lines.iter().any(|line| line.contains("doubt=0.90")));
+    }
+
+    let custom = VizCfg { label_width: 16, value_width: 40, bar_width: 30, ..VizCfg::default() };
+    let lines = viz::print_table(&TableData { drift: 1.0, res: 0.88, wpm: 162.0, articulation: 0.74, filler_density: 0.0, tone: "Neutral", asr_ms: 45, tts_ms: 32, total_ms: 90, ..Default::default() }, &custom);
+    assert_eq!(lines[0], format!("+{}+{}+", "-".repeat(18), "-".repeat(42)));
+    assert!(lines.iter().any(|line| line.contains(&"#".repeat(30))));
+
+    assert_eq!(VizCfg::for_width(54), VizCfg::default());
+    assert_eq!(VizCfg::for_width(10).table_width(), 40);
+}
+
+#[test]
+fn compact_meta_and_compassion_lines_carry_metrics() {
+    let mut meta = MetaCognition::new();
+    meta.confidence = 0.62;
+    meta.clarity = 0.7;
+    meta.doubt = 0.1;
+    let line = viz::print_compact_meta(&meta);
+    assert!(line.starts_with("[meta] "));
+    for token in ["conf=0.62", "clarity=0.70", "doubt=0.10"] {
+        assert!(line.contains(token), "{} missing from {}", token, line);
+    }
+
+    let mut comp = CompassionMetrics::new();
+    comp.user_suffering = 0.42;
+    comp.suffering_type = SufferingType::Moderate;
+    comp.response_kindness = 0.6;
+    comp.compassion_level = 0.55;
+    let line = viz::print_compact_compassion(&comp);
+    assert!(line.starts_with("[compassion] "));
+    for token in ["suffering=0.42", "type=Moderate", "kind=0.60", "level=0.55"] {
+        assert!(line.contains(token), "{} missing from {}", token, line);
+    }
+}
+
+#[test]
+fn heatmap_fills_the_requested_width() {
+    let long: Vec<f32> = (0..50).map(|i| i as f32 / 50.0).collect();
+    assert_eq!(viz::heatmap(&long, 12).chars().count(), 12);
+    assert_eq!(viz::heatmap(&[0.2, 0.9], 8).chars().count(), 8);
+    assert!(viz::heatmap(&[], 8).is_empty());
+
+    let map = viz::heatmap(&[0.0, 0.0, 1.0, 1.0], 2);
+    assert_eq!(map, " █");
+    assert!(viz::heatmap_for(&long, 12, true).is_ascii());
+}
@@ -1,8 +1,19 @@
-use liminal_voice_core::adaptive_qa;
+use std::fs;
+
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::adaptive_qa::{Lexicon, LexiconKind};
+use liminal_voice_core::config::{Config, VERBOSITY_QUIET};
+use liminal_voice_core::device::{self, DeviceMode};
+use liminal_voice_core::pipeline::Pipeline;
+use liminal_voice_core::softguard::{self, GuardAction, GuardConfig};
+use liminal_voice_core::{adaptive_qa, prosody};
 
 #[test]
 fn drift_and_resonance_within_range() {
-    let (drift, res) = adaptive_qa::analyze_prompt("hello liminal");
+    let profile = device::profile(&DeviceMode::Phone);
+    let (drift, res) = adaptive_qa::analyze_prompt("hello liminal", profile.pace_factor);
     assert!(
         (0.0..=1.0).contains(&drift),
         "drift out of range: {}",
@@ -14,3 +25,204 @@ fn drift_and_resonance_within_range() {
         res
     );
 }
+
+#[test]
+fn alarmed_question_biases_more_than_statement() {
+    let alarmed = prosody::analyze_default("why is this happening?!", 1.0, 60);
+    let plain = prosody::analyze_default("this is fine.", 1.0, 60);
+
+    let (d_alarmed, r_alarmed) = adaptive_qa::apply_prosody_bias(0.4, 0.6, &alarmed);
+    let (d_plain, r_plain) = adaptive_qa::apply_prosody_bias(0.4, 0.6, &plain);
+
+    assert!(d_alarmed > d_plain);
+    assert!(r_alarmed < r_plain);
+    assert!((0.0..=1.0).contains(&d_alarmed) && (0.0..=1.0).contains(&r_alarmed));
+}
+
+#[test]
+fn analyze_prompt_is_deterministic() {
+    let text = "how do I keep this steady?";
+    assert_eq!(
+        adaptive_qa::analyze_prompt(text, 1.0),
+        adaptive_qa::analyze_prompt(text, 1.0)
+    );
+    assert_eq!(
+        adaptive_qa::analyze_prompt("", 1.0),
+        adaptive_qa::analyze_prompt("  ?! ", 1.0)
+    );
+}
+
+#[test]
+fn chaotic_prompt_drifts_more_than_calm_one() {
+    let (d_chaos, r_chaos) =
+        adaptive_qa::analyze_prompt("why?! what is happening, nothing works, no, why not?!", 1.0);
+    let (d_calm, r_calm) =
+        adaptive_qa::analyze_prompt("thank you, I feel calm and grateful today", 1.0);
+
+    assert!(d_chaos > d_calm, "chaos={} calm={}", d_chaos, d_calm);
+    assert!(r_calm > r_chaos, "calm={} chaos={}", r_calm, r_chaos);
+}
+
+#[test]
+fn calm_prompt_stays_under_default_drift_thresholds() {
+    let baseline = Config::default().baseline_drift;
+    let guard = GuardConfig::default();
+    for text in ["hello liminal", "I feel calm and peaceful today, thank you"] {
+        let (drift, res) = adaptive_qa::analyze_prompt(text, 1.0);
+        assert!(
+            drift < baseline,
+            "{:?}: drift {} >= {}",
+            text,
+            drift,
+            baseline
+        );
+        assert!(
+            !matches!(
+                softguard::check_and_rephrase(text, drift, res, &guard),
+                GuardAction::Warn { .. }
+            ),
+            "{:?} warned at drift {} res {}",
+            text,
+            drift,
+            res
+        );
+    }
+}
+
+fn seeded_run(seed: u64, inputs: &[&str]) -> (Vec<f32>, Vec<f32>) {
+    let cfg = Config {
+        seed: Some(seed),
+        simulate_latency: false,
+        verbosity: VERBOSITY_QUIET,
+        enable_metrics: false,
+        ..Config::default()
+    };
+    let prof = device::profile(&DeviceMode::Phone);
+    let utterances: Vec<String> = inputs.iter().map(|s| s.to_string()).collect();
+    let mut pipe = Pipeline::new(&cfg, &prof, utterances.len());
+    let mut seen = 0;
+    pipe.run_with(&utterances, |_| seen += 1);
+    assert_eq!(seen, inputs.len());
+    (pipe.drift_history, pipe.resonance_history)
+}
+
+#[test]
+fn seeded_runs_are_reproducible() {
+    let inputs = [
+        "hello liminal",
+        "hello liminal",
+        "why is this stuck?!",
+        "thanks, calm now",
+    ];
+
+    let first = seeded_run(42, &inputs);
+    let second = seeded_run(42, &inputs);
+    assert_eq!(first, second);
+
+    let other = seeded_run(7, &inputs);
+    assert_ne!(first, other);
+
+    for (idx, text) in inputs.iter().enumerate() {
+        let (base, _) = adaptive_qa::analyze_prompt(text, 1.0);
+        let (drift, _) = adaptive_qa::analyze_prompt_seeded(text, 1.0, 42 + idx as u64);
+        assert!((drift - base).abs() <= adaptive_qa::SEEDED_JITTER);
+    }
+}
+
+#[test]
+fn faster_pace_raises_drift() {
+    let text = "tell me about the weather";
+    let slow = device::profile(&DeviceMode::Terminal);
+    let fast = device::profile(&DeviceMode::Phone);
+
+    let (d_slow, r_slow) = adaptive_qa::analyze_prompt(text, slow.pace_factor);
+    let (d_fast, r_fast) = adaptive_qa::analyze_prompt(text, fast.pace_factor);
+    assert!(d_fast > d_slow, "fast={} slow={}", d_fast, d_slow);
+    let pace_gap = fast.pace_factor - slow.pace_factor;
+    assert!(
+        d_fast - d_slow <= 0.2 * pace_gap + 1e-6,
+        "pace should only nudge drift: {} for a pace gap of {}",
+        d_fast - d_slow,
+        pace_gap
+    );
+    assert_eq!(r_fast, r_slow);
+}
+
+fn temp_lexicon(label: &str, contents: &str) -> String {
+    let path = temp_path(&format!("liminal_lexicon_{}.csv", label));
+    fs::write(&path, contents).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+#[test]
+fn loaded_lexicon_shifts_domain_words() {
+    let path = temp_lexicon(
+        "domain",
+        "word,weight,kind\n# on-call vocabulary\noutage,2.0,drift\nrollback,1.5,res\nbogus row\nping,abc,drift\n",
+    );
+    let lexicon = adaptive_qa::load_lexicon(&path);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(lexicon.drift.get("outage"), Some(&2.0));
+    assert_eq!(lexicon.res.get("rollback"), Some(&1.5));
+    assert!(!lexicon.drift.contains_key("ping"));
+    assert!(lexicon.res.contains_key("calm"), "defaults are kept");
+
+    let text = "the outage started after the rollback";
+    let (d_default, r_default) = adaptive_qa::analyze_prompt(text, 1.0);
+    let (d_domain, r_domain) = adaptive_qa::analyze_prompt_with_lexicon(text, 1.0, &lexicon);
+    assert!(
+        d_domain > d_default,
+        "domain={} default={}",
+        d_domain,
+        d_default
+    );
+    assert!(
+        r_domain > r_default,
+        "domain={} default={}",
+        r_domain,
+        r_default
+    );
+}
+
+#[test]
+fn default_lexicon_matches_analyze_prompt() {
+    let text = "why won't this work, I'm not calm";
+    assert_eq!(
+        adaptive_qa::analyze_prompt(text, 1.0),
+        adaptive_qa::analyze_prompt_with_lexicon(text, 1.0, &Lexicon::default())
+    );
+
+    let missing = adaptive_qa::load_lexicon("/nonexistent/liminal_lexicon.csv");
+    assert_eq!(missing, Lexicon::default());
+
+    let mut muted = Lexicon::default();
+    muted.insert("Calm", 0.0, LexiconKind::Res);
+    let (_, r_muted) = adaptive_qa::analyze_prompt_with_lexicon("calm", 1.0, &muted);
+    let (_, r_default) = adaptive_qa::analyze_prompt("calm", 1.0);
+    assert!(r_muted < r_default);
+}
+
+#[test]
+fn analysis_cache_reuses_scores_for_repeated_prompts() {
+    let mut calls = 0;
+    let mut cache = adaptive_qa::AnalysisCache::new(2, |text: &str| {
+        calls += 1;
+        adaptive_qa::analyze_prompt(text, 1.0)
+    });
+
+    let first = cache.analyze_cached("hello liminal");
+    let again = cache.analyze_cached("  Hello Liminal ");
+    assert_eq!(first, again);
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+    cache.analyze_cached("why is this stuck?!");
+    cache.analyze_cached("thanks, calm now");
+    assert_eq!(cache.len(), 2, "capacity bounds the cache");
+
+    // The oldest entry was evicted, so it is recomputed.
+    cache.analyze_cached("hello liminal");
+    assert_eq!(cache.misses(), 4);
+    drop(cache);
+    assert_eq!(calls, 4);
+}
@@ -0,0 +1,264 @@
+use std::fs;
+use std::io::{Cursor, ErrorKind};
+use std::time::Instant;
+
+mod common;
+
+use common::temp_path;
+use liminal_voice_core::config::{Config, VERBOSITY_QUIET};
+use liminal_voice_core::device::{self, DeviceMode, DeviceProfile};
+use liminal_voice_core::metrics;
+use liminal_voice_core::voice_io::{
+    self, AsrBackend, CapturingTts, SimulatedAsr, SimulatedTts, TtsBackend,
+};
+
+/// A 16-bit PCM WAV with `frames` silent samples per channel and an extra
+/// LIST chunk before the data, as many recorders write.
+fn wav_bytes(sample_rate: u32, channels: u16, frames: u32) -> Vec<u8> {
+    let block_align = channels * 2;
+    let data_len = frames * block_align as u32;
+    let list = b"INFOtest";
+    let mut out = Vec::new();
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(4 + 24 + 8 + list.len() as u32 + 8 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVE");
+    out.extend_from_slice(b"fmt ");
+    out.extend_from_slice(&16u32.to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&channels.to_le_bytes());
+    out.extend_from_slice(&sample_rate.to_le_bytes());
+    out.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    out.extend_from_slice(&block_align.to_le_bytes());
+    out.extend_from_slice(&16u16.to_le_bytes());
+    out.extend_from_slice(b"LIST");
+    out.extend_from_slice(&(list.len() as u32).to_le_bytes());
+    out.extend_from_slice(list);
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    out.resize(out.len() + data_len as usize, 0);
+    out
+}
+
+struct UppercaseAsr;
+
+impl AsrBackend for UppercaseAsr {
+    fn transcribe(&self, _cfg: &Config, _prof: &DeviceProfile, input: &str) -> String {
+        input.to_uppercase()
+    }
+}
+
+#[test]
+fn custom_asr_backend_replaces_the_simulated_one() {
+    let cfg = Config::default();
+    let prof = device::profile(&DeviceMode::Phone);
+    let asr: Box<dyn AsrBackend> = Box::new(UppercaseAsr);
+    assert_eq!(
+        asr.transcribe(&cfg, &prof, "hello liminal"),
+        "HELLO LIMINAL"
+    );
+}
+
+#[test]
+fn capturing_tts_records_spoken_text_in_order() {
+    let cfg = Config::default();
+    let prof = device::profile(&DeviceMode::Headset);
+    let mut tts = CapturingTts::default();
+    {
+        let backend: &mut dyn TtsBackend = &mut tts;
+        backend.speak(
+            &cfg,
+            &prof,
+            1.0,
+            60,
+            0.0,
+            "Semantic Drift: 0.30, Resonance: 0.70",
+        );
+        backend.speak(&cfg, &prof, 0.9, 80, 0.05, "Let's slow down a little.");
+    }
+    assert_eq!(
+        tts.spoken,
+        vec![
+            "Semantic Drift: 0.30, Resonance: 0.70".to_string(),
+            "Let's slow down a little.".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn disabled_latency_still_reports_stage_times_but_runs_fast() {
+    let cfg = Config {
+        simulate_latency: false,
+        verbosity: VERBOSITY_QUIET,
+        ..Config::default()
+    };
+    let prof = device::profile(&DeviceMode::Phone);
+    let asr = SimulatedAsr;
+    let mut tts = SimulatedTts::new(true);
+
+    let run_start = Instant::now();
+    for _ in 0..10 {
+        let mut vm = metrics::start();
+        let asr_start = Instant::now();
+        let text = asr.transcribe(&cfg, &prof, "hello liminal");
+        vm.asr_ms = voice_io::stage_ms(&cfg, asr_start, asr.simulated_ms(&cfg, &prof));
+
+        let tts_start = Instant::now();
+        tts.speak(&cfg, &prof, 0.9, 80, 0.0, &text);
+        vm.tts_ms = voice_io::stage_ms(&cfg, tts_start, tts.simulated_ms());
+        metrics::finish(&mut vm);
+
+        // Phone: 60ms pause + 20ms frame; TTS half the 80ms pause + 20ms frame,
+        // plus 8ms for pace 0.9.
+        assert!((80..90).contains(&vm.asr_ms), "asr_ms={}", vm.asr_ms);
+        assert!((68..78).contains(&vm.tts_ms), "tts_ms={}", vm.tts_ms);
+        assert!(vm.total_ms >= vm.asr_ms + vm.tts_ms);
+    }
+    // Ten cycles would sleep well over a second with latency simulated.
+    assert!(run_start.elapsed().as_millis() < 500);
+}
+
+#[test]
+fn latency_scale_stretches_simulated_figures() {
+    let mut cfg = Config::default();
+    let prof = device::profile(&DeviceMode::Phone);
+    assert_eq!(voice_io::asr_latency_ms(&cfg, &prof), 80);
+    assert_eq!(voice_io::response_latency_ms(&cfg, &prof), 50);
+
+    cfg.latency_scale = 0.5;
+    assert_eq!(voice_io::asr_latency_ms(&cfg, &prof), 40);
+    assert_eq!(voice_io::paced_latency_ms(&cfg, 0.9, 80), (30, 4));
+    cfg.latency_scale = 0.0;
+    assert_eq!(SimulatedAsr.simulated_ms(&cfg, &prof), Some(0));
+}
+
+#[test]
+fn audio_duration_adds_to_asr_latency() {
+    let mut cfg = Config::default();
+    let prof = device::profile(&DeviceMode::Phone);
+    cfg.simulate_latency = false;
+    // 80ms device latency plus a tenth of the half-second recording.
+    assert_eq!(voice_io::asr_latency_ms(&cfg, &prof), 80);
+    assert_eq!(voice_io::simulate_audio_latency(&cfg, 500), 50);
+}
+
+#[test]
+fn read_wav_parses_format_and_duration() {
+    let path = temp_path("liminal_voice_io.wav");
+    let path_string = path.to_string_lossy().to_string();
+    // Half a second of stereo audio at 8kHz.
+    fs::write(&path_string, wav_bytes(8_000, 2, 4_000)).unwrap();
+
+    let info = voice_io::read_wav(&path_string).expect("valid wav");
+    assert_eq!(info.sample_rate, 8_000);
+    assert_eq!(info.channels, 2);
+    assert_eq!(info.bits_per_sample, 16);
+    assert_eq!(info.frames, 4_000);
+    assert_eq!(info.duration_ms(), 500);
+
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn read_wav_rejects_malformed_headers() {
+    let invalid = |bytes: Vec<u8>| {
+        voice_io::read_wav_from(&mut Cursor::new(bytes))
+            .expect_err("should be rejected")
+            .kind()
+    };
+
+    let mut not_riff = wav_bytes(16_000, 1, 10);
+    not_riff[0..4].copy_from_slice(b"RIFX");
+    assert_eq!(invalid(not_riff), ErrorKind::InvalidData);
+
+    let mut eight_bit = wav_bytes(16_000, 1, 10);
+    eight_bit[34..36].copy_from_slice(&8u16.to_le_bytes());
+    assert_eq!(invalid(eight_bit), ErrorKind::InvalidData);
+
+    // Header cut off before the data chunk.
+    assert_eq!(
+        invalid(wav_bytes(16_000, 1, 10)[..40].to_vec()),
+        ErrorKind::InvalidData
+    );
+    assert!(voice_io::read_wav("/nonexistent/clip.wav").is_err());
+
+    // A fmt chunk claiming gigabytes fails on the missing bytes instead of
+    // allocating them.
+    let mut huge_fmt = wav_bytes(16_000, 1, 10)[..36].to_vec();
+    huge_fmt[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(invalid(huge_fmt), ErrorKind::InvalidData);
+}
+
+#[test]
+fn read_wav_skips_fmt_extension_bytes() {
+    // WAVE_FORMAT_EX headers append a cbSize field to the 16-byte body.
+    let mut bytes = wav_bytes(22_050, 1, 2_205);
+    bytes[16..20].copy_from_slice(&18u32.to_le_bytes());
+    bytes.splice(36..36, 0u16.to_le_bytes());
+
+    let info = voice_io::read_wav_from(&mut Cursor::new(bytes)).expect("valid wav");
+    assert_eq!(info.sample_rate, 22_050);
+    assert_eq!(info.frames, 2_205);
+    assert_eq!(info.duration_ms(), 100);
+}
+
+#[test]
+fn ssml_reflects_pace_and_pause() {
+    assert_eq!(
+        voice_io::to_ssml(0.9, 80, "Semantic Drift: 0.30"),
+        "<speak><prosody rate=\"90%\"><break time=\"80ms\"/>Semantic Drift: 0.30</prosody></speak>"
+    );
+
+    let fast = voice_io::to_ssml(1.25, 40, "ok");
+    assert!(fast.contains("rate=\"125%\""), "{}", fast);
+    assert!(fast.contains("<break time=\"40ms\"/>"), "{}", fast);
+
+    // Out-of-range values are clamped the way synthesis clamps them.
+    let clamped = voice_io::to_ssml(5.0, 1_000, "ok");
+    assert!(clamped.contains("rate=\"200%\""), "{}", clamped);
+    assert!(clamped.contains("<break time=\"250ms\"/>"), "{}", clamped);
+}
+
+#[test]
+fn ssml_escapes_xml_in_text() {
+    let ssml = voice_io::to_ssml(1.0, 60, "<b>Tom & \"Jerry\"</b>'s");
+    assert!(
+        ssml.contains("&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;&apos;s</prosody>"),
+        "{}",
+        ssml
+    );
+}
+
+#[test]
+fn articulation_hint_slows_synthesis_at_equal_pace_and_pause() {
+    let cfg = Config {
+        simulate_latency: false,
+        verbosity: VERBOSITY_QUIET,
+        ..Config::default()
+    };
+    let prof = device::profile(&DeviceMode::Phone);
+    let text = "Semantic Drift: 0.30, Resonance: 0.70";
+
+    let plain = voice_io::synthesize_with(&cfg, &prof, 1.0, 80, 0.0, text);
+    let clear = voice_io::synthesize_with(&cfg, &prof, 1.0, 80, 0.05, text);
+    let clearer = voice_io::synthesize_with(&cfg, &prof, 1.0, 80, 0.08, text);
+    assert!(
+        plain < clear && clear < clearer,
+        "{} {} {}",
+        plain,
+        clear,
+        clearer
+    );
+    // Five words at 100ms per word per unit of hint.
+    assert_eq!(clear - plain, 25);
+
+    let mut tts = SimulatedTts::new(true);
+    tts.speak(&cfg, &prof, 1.0, 80, 0.08, text);
+    assert_eq!(tts.simulated_ms(), Some(clearer));
+    // The unpaced path keeps the device profile's latency.
+    let mut unpaced = SimulatedTts::new(false);
+    unpaced.speak(&cfg, &prof, 1.0, 80, 0.08, text);
+    assert_eq!(
+        unpaced.simulated_ms(),
+        Some(voice_io::response_latency_ms(&cfg, &prof))
+    );
+}